@@ -18,9 +18,28 @@ __all__ = [
     "IteratorFlags",
     "OwnedKey",
     "SourceMap",
+    "OriginalPosition",
     "JSModule",
     "JSPromise",
     "JSPromiseState",
+    "ConversionFallback",
+    "EventLoopStatus",
+    "StackFrame",
+    "HeapStats",
+    "Realm",
+    "RuntimePool",
+    "TypeScriptDiagnostic",
+    # exceptions
+    "JSError",
+    "JSSyntaxError",
+    "JSTypeError",
+    "JSRangeError",
+    "JSReferenceError",
+    "JSCompileError",
+    "JSThrownValue",
+    "JSTimeoutError",
+    "JSRuntimeClosedError",
+    "TypeScriptError",
     # functions
     "typeof",
 ]
@@ -2,6 +2,7 @@
 # ruff: noqa: E501, F401
 
 import builtins
+import concurrent.futures
 import typing
 from enum import Enum, auto
 
@@ -48,6 +49,19 @@ class ESClass:
     def other() -> ESClass: ...
     def __repr__(self) -> builtins.str: ...
 
+class HeapStats:
+    r"""
+    Snapshot of the memory usage and garbage collections of the JavaScript heap.
+    """
+    gc_heap_bytes: builtins.int
+    malloc_bytes: builtins.int
+    max_heap_bytes: builtins.int
+    gc_count: builtins.int
+    major_gc_count: builtins.int
+    minor_gc_count: builtins.int
+    persistent_roots: builtins.int
+    def __repr__(self) -> builtins.str: ...
+
 class IteratorFlags:
     @staticmethod
     def private() -> IteratorFlags:
@@ -192,8 +206,29 @@ class JSBigInt:
     def __str__(self) -> builtins.str: ...
     def __repr__(self) -> builtins.str: ...
 
+class JSCompileError(JSError):
+    r"""
+    Raised when a module fails to compile or link.
+    """
+    ...
+
 class JSContext: ...
 
+class JSError(builtins.RuntimeError):
+    r"""
+    Raised when JavaScript code throws an error or fails to compile.
+    """
+    value: typing.Optional[JSValue]
+    message: builtins.str
+    filename: typing.Optional[builtins.str]
+    line: typing.Optional[builtins.int]
+    column: typing.Optional[builtins.int]
+    generated_filename: typing.Optional[builtins.str]
+    generated_line: typing.Optional[builtins.int]
+    generated_column: typing.Optional[builtins.int]
+    stack: builtins.list[StackFrame]
+
+
 class JSFunction:
     r"""
     Represents a [JSFunction] within the JavaScript Runtime.
@@ -207,6 +242,24 @@ class JSFunction:
         """
         ...
 
+    @staticmethod
+    def from_py(
+        callable: typing.Callable[..., typing.Any],
+        name: typing.Optional[builtins.str] = None,
+        nargs: builtins.int = 0,
+    ) -> JSFunction:
+        r"""
+        Creates a new [JSFunction] which calls the given Python callable.
+
+        JavaScript arguments are converted with [JSValue::to_py], keeping values without a
+        Python counterpart as [JSValue]s, and the return value is converted with
+        [JSValue::from_py]. Python exceptions are rethrown as JavaScript `Error`s.
+
+        `name` defaults to the `__name__` of the callable and `nargs` sets the `length`
+        of the function.
+        """
+        ...
+
     def to_object(self) -> JSObject:
         r"""
         Converts the [JSFunction] into an [JSObject].
@@ -248,12 +301,17 @@ class JSFunction:
         ...
 
     def call(
-        self, args: typing.Optional[typing.Sequence[JSValue]] = None, this: typing.Optional[JSObject] = None
+        self,
+        args: typing.Optional[typing.Sequence[typing.Any]] = None,
+        this: typing.Optional[JSObject] = None,
+        timeout: typing.Optional[builtins.float] = None,
     ) -> JSValue:
         r"""
         Calls the [JSFunction] with the given `this` [JSObject] and arguments.
+        Arguments that are not [JSValue]s are converted with [JSValue::from_py].
         Returns the result of the [JSFunction] as a [JSValue].
-        Returns [Err] if the function call fails or an exception occurs.
+        Raises a [JSError] subclass if the function throws, and [JSTimeoutError] if the call
+        runs for longer than `timeout` seconds, which defaults to the timeout of the runtime.
         """
         ...
 
@@ -299,6 +357,32 @@ class JSModule:
         """
         ...
 
+    @property
+    def default(self) -> typing.Optional[JSValue]:
+        r"""
+        The `default` export of the module, or [None] if it has no default export.
+        """
+        ...
+
+    def namespace(self) -> JSObject:
+        r"""
+        Returns the namespace object of the module, whose properties are its exports.
+        Raises [RuntimeError] if the module has not been linked.
+        """
+        ...
+
+    def get_export(self, name: builtins.str) -> typing.Optional[JSValue]:
+        r"""
+        Returns the value of the export with the given name, or [None] if it is not exported.
+        """
+        ...
+
+    def export_names(self) -> builtins.list[builtins.str]:
+        r"""
+        Returns the names of all exports of the module, in sorted order.
+        """
+        ...
+
 class JSObject:
     def __init__(self) -> JSObject: ...
     @staticmethod
@@ -353,9 +437,10 @@ class JSObject:
         """
         ...
 
-    def set(self, key: typing.Union[builtins.str, PropertyKey], value: JSValue) -> builtins.bool:
+    def set(self, key: typing.Union[builtins.str, PropertyKey], value: typing.Any) -> builtins.bool:
         r"""
         Sets the [JSValue] at the given key of the [JSObject].
+        Values that are not [JSValue]s are converted with [JSValue::from_py].
 
         Returns `false` if the property cannot be set.
 
@@ -445,13 +530,32 @@ class JSPromise:
         ...
 
     @staticmethod
-    def resolved(value: JSValue) -> JSPromise:
+    def resolved(value: typing.Any) -> JSPromise:
         r"""
         Creates a new [Promise], that is resolved to the given value.
         Similar to `Promise.resolve`
         """
         ...
 
+    @staticmethod
+    def from_awaitable(awaitable: typing.Any) -> JSPromise:
+        r"""
+        Creates a new [JSPromise] which is settled with the outcome of a Python awaitable,
+        such as a coroutine.
+
+        The awaitable runs as an asyncio task on the running event loop. When none is running,
+        it runs on a private event loop of the current thread, which is stepped alongside the
+        timers and other work of the event loop of the runtime, so the awaitable can itself await
+        [JSPromise]s. The task is waited for on the future queue of the runtime, so
+        `Runtime.run_event_loop` keeps running until the promise is settled.
+        Python exceptions reject the promise.
+
+        # Errors
+        Raises [TypeError] if the object is not awaitable, and [RuntimeError] if the
+        macrotask queue is disabled.
+        """
+        ...
+
     def id(self) -> builtins.int:
         r"""
         Returns the ID of the [JSPromise].
@@ -472,20 +576,149 @@ class JSPromise:
         """
         ...
 
-    def resolve(self, value: JSValue) -> builtins.bool:
+    def resolve(self, value: typing.Any) -> builtins.bool:
         r"""
         Resolves the [JSPromise] with the given [JSValue].
+        Values that are not [JSValue]s are converted with [JSValue::from_py].
         """
         ...
 
-    def reject(self, value: JSValue) -> builtins.bool:
+    def reject(self, value: typing.Any) -> builtins.bool:
         r"""
         Rejects the [JSPromise] with the given [JSValue].
+        Values that are not [JSValue]s are converted with [JSValue::from_py].
+        """
+        ...
+
+    def then(
+        self,
+        on_fulfilled: typing.Optional[typing.Callable[..., typing.Any]] = None,
+        on_rejected: typing.Optional[typing.Callable[..., typing.Any]] = None,
+    ) -> JSPromise:
+        r"""
+        Appends fulfillment and rejection handlers to the [JSPromise], like `Promise.prototype.then`.
+
+        Handlers can be Python callables or [JSFunction]s. Python callables receive the value
+        converted with [JSValue::to_py] and their return value is converted with
+        [JSValue::from_py], so returning an awaitable chains it. Exceptions reject the
+        returned promise.
+
+        Returns a new [JSPromise] which is settled with the result of the called handler.
+        """
+        ...
+
+    def catch_(self, on_rejected: typing.Callable[..., typing.Any]) -> JSPromise:
+        r"""
+        Appends a rejection handler to the [JSPromise], like `Promise.prototype.catch`.
+        Returns a new [JSPromise], which is fulfilled with the value of the [JSPromise] if it
+        is fulfilled, or with the result of the handler if it is rejected.
+        """
+        ...
+
+    def finally_(self, on_finally: typing.Callable[[], typing.Any]) -> JSPromise:
+        r"""
+        Appends a handler which is called without arguments when the [JSPromise] is settled,
+        like `Promise.prototype.finally`.
+        Returns a new [JSPromise] which is settled like the [JSPromise], unless the handler
+        throws or returns a rejected promise.
+        """
+        ...
+
+    @staticmethod
+    def all(promises: typing.Sequence[typing.Any]) -> JSPromise:
+        r"""
+        Creates a [JSPromise] which is fulfilled with a list of the values of the given promises
+        when all of them are fulfilled, or rejected with the first rejection, like `Promise.all`.
+        Values that are not [JSPromise]s are converted with [JSValue::from_py].
+        """
+        ...
+
+    @staticmethod
+    def any(promises: typing.Sequence[typing.Any]) -> JSPromise:
+        r"""
+        Creates a [JSPromise] which is fulfilled with the value of the first fulfilled promise,
+        or rejected with an `AggregateError` if all of them are rejected, like `Promise.any`.
+        Values that are not [JSPromise]s are converted with [JSValue::from_py].
+        """
+        ...
+
+    @staticmethod
+    def race(promises: typing.Sequence[typing.Any]) -> JSPromise:
+        r"""
+        Creates a [JSPromise] which is settled like the first of the given promises to settle,
+        like `Promise.race`.
+        Values that are not [JSPromise]s are converted with [JSValue::from_py].
+        """
+        ...
+
+    @staticmethod
+    def all_settled(promises: typing.Sequence[typing.Any]) -> JSPromise:
+        r"""
+        Creates a [JSPromise] which is fulfilled when all of the given promises are settled,
+        with a list of objects describing their outcomes, like `Promise.allSettled`.
+        Values that are not [JSPromise]s are converted with [JSValue::from_py].
+        """
+        ...
+
+    def __await__(self) -> typing.Any:
+        r"""
+        Awaits the [JSPromise] from asyncio, driving the event loop on the current thread
+        until it is settled.
+
+        The [JSPromise] completes an `asyncio.Future` of the running event loop from its
+        reactions, which is awaited instead. The result is the fulfilled value converted with
+        [JSValue::to_py], keeping values without a Python counterpart as [JSValue]s.
+        Rejections are raised as [JSError]s.
+
+        The event loop runs whenever its timers or network requests may be ready, and when
+        promises are settled from Python, rather than at a fixed interval.
         """
         ...
 
     def __str__(self) -> builtins.str: ...
 
+class JSRangeError(JSError):
+    r"""
+    Raised for a JavaScript `RangeError`.
+    """
+    ...
+
+class JSReferenceError(JSError):
+    r"""
+    Raised for a JavaScript `ReferenceError`.
+    """
+    ...
+
+class JSRuntimeClosedError(builtins.RuntimeError):
+    r"""
+    Raised when a runtime, or a value created by it, is used after the runtime was closed.
+    """
+    ...
+
+class JSSyntaxError(JSError):
+    r"""
+    Raised for a JavaScript `SyntaxError`.
+    """
+    ...
+
+class JSThrownValue(JSError):
+    r"""
+    Raised when JavaScript code throws a value which is not an `Error`, e.g. `throw 42`.
+    """
+    ...
+
+class JSTimeoutError(JSError):
+    r"""
+    Raised when JavaScript code is interrupted because it exceeded its time limit.
+    """
+    ...
+
+class JSTypeError(JSError):
+    r"""
+    Raised for a JavaScript `TypeError`.
+    """
+    ...
+
 class JSValue:
     r"""
     Represents a JavaScript value in the Python environment.
@@ -750,6 +983,38 @@ class JSValue:
         """
         ...
 
+    @staticmethod
+    def from_py(obj: typing.Any, max_depth: builtins.int = 64) -> JSValue:
+        r"""
+        Creates a [JSValue] by recursively converting a native Python object.
+
+        `None` becomes `null`, `dict`s and other mappings become plain objects, `list`s and
+        `tuple`s become arrays, `bytes` become a `Uint8Array` and `datetime`s become a `Date`.
+        Integers outside the safe integer range become BigInts and `Decimal`s become numbers.
+        Awaitables become promises as with [JSPromise::from_awaitable], callables become
+        functions as with [JSFunction::from_py] and existing JavaScript wrappers are passed
+        through unchanged.
+
+        Raises `TypeError` for unsupported objects and `ValueError` for cyclic structures
+        or when nesting exceeds `max_depth`.
+        """
+        ...
+
+    def to_py(self, max_depth: builtins.int = 64, fallback: ConversionFallback = ConversionFallback.Error) -> typing.Any:
+        r"""
+        Recursively converts the [JSValue] into the matching native Python object.
+
+        Primitives map to `None`, `bool`, `int`, `float` and `str`, BigInts to `int`,
+        arrays to `list`, plain objects and `Map`s to `dict`, `Set`s to `set`, `Date`s to
+        timezone-aware `datetime`s and `ArrayBuffer`/`Uint8Array` to `bytes`.
+        Other typed arrays become a `list` of numbers.
+
+        Values without a native counterpart (functions, symbols, promises, ...) are handled
+        according to `fallback`. Raises `ValueError` on cyclic structures or when nesting
+        exceeds `max_depth`.
+        """
+        ...
+
     @staticmethod
     def undefined() -> JSValue:
         r"""
@@ -775,6 +1040,17 @@ class JSValue:
     def __str__(self) -> builtins.str: ...
     def __repr__(self) -> builtins.str: ...
 
+class OriginalPosition:
+    r"""
+    Position in an original source, found by looking up a generated position in a [SourceMap].
+    """
+    source: builtins.str
+    line: builtins.int
+    column: builtins.int
+    name: typing.Optional[builtins.str]
+    def __str__(self) -> builtins.str: ...
+    def __repr__(self) -> builtins.str: ...
+
 class OwnedKey:
     @staticmethod
     def int(value: builtins.int) -> OwnedKey:
@@ -904,14 +1180,67 @@ class PropertyKey:
     def __str__(self) -> builtins.str: ...
     def __repr__(self) -> builtins.str: ...
 
+class Realm:
+    r"""
+    An isolated realm with its own global object and built-ins, in its own compartment.
+
+    Scripts evaluated in different realms cannot see each other's globals. Values only cross
+    realms explicitly, through [set](PyRealm::set) and [get](PyRealm::get), as
+    cross-compartment wrappers of the original objects.
+
+    Values returned to Python are always wrapped for the main realm of the runtime, so they
+    can be used like any other value.
+    """
+    def global_object(self) -> JSObject:
+        r"""
+        Returns the global object of the realm, wrapped for the main realm.
+        """
+        ...
+
+    def compile_and_evaluate_script(
+        self, source: builtins.str, filename: builtins.str = "inline.js", timeout: typing.Optional[builtins.float] = None
+    ) -> JSValue:
+        r"""
+        Compiles and evaluates a script in the realm, and returns its return value.
+
+        # Arguments
+        * `source` - JavaScript source code to execute
+        * `filename` - Name to use in error messages (defaults to "inline.js")
+        * `timeout` - Time limit in seconds, which defaults to the timeout of the runtime
+
+        # Errors
+        Raises a [JSError] subclass if the compilation or evaluation fails,
+        and [JSTimeoutError] if the evaluation exceeds the time limit
+        """
+        ...
+
+    def set(self, name: builtins.str, value: typing.Any) -> None:
+        r"""
+        Transfers a value into the realm as the global variable `name`.
+
+        Objects are shared rather than copied, so changes made in either realm are visible in
+        the other.
+        """
+        ...
+
+    def get(self, name: builtins.str) -> typing.Optional[JSValue]:
+        r"""
+        Transfers the global variable `name` of the realm out of it.
+
+        Returns [None] if the realm has no global variable with that name.
+        """
+        ...
+
 class Runtime:
     r"""
     Python wrapper for JavaScript runtime functionality.
 
-    # Warning
-    The runtime must be kept alive until the program ends.
-    If the runtime is dropped and an attempt is made to use it or any
-    JavaScript variables or objects created within it, an error will occur.
+    The runtime is closed when it is dropped or when [close](PythonJSRuntime::close) is called.
+    Using the runtime or any JavaScript value created within it afterwards raises
+    [JSRuntimeClosedError].
+
+    `script`, `typescript` and `log_level` are shared by all runtimes of the process, so those
+    created after the first one must use the same values.
     """
     def __init__(
         self,
@@ -920,8 +1249,25 @@ class Runtime:
         script: builtins.bool = False,
         typescript: builtins.bool = True,
         log_level: builtins.int = 0,
+        import_map: typing.Optional[typing.Any] = None,
+        timeout: typing.Optional[builtins.float] = None,
+        max_heap_bytes: typing.Optional[builtins.int] = None,
+        max_nursery_bytes: typing.Optional[builtins.int] = None,
+        gc_zeal: typing.Optional[builtins.int] = None,
+        gc_zeal_frequency: builtins.int = 100,
+        allow_fs: builtins.bool = True,
+        allow_net: builtins.bool = True,
+        allow_timers: builtins.bool = True,
+        allow_modules: builtins.bool = True,
+        allow_eval: builtins.bool = True,
+        jsx: builtins.str = "react",
+        jsx_factory: typing.Optional[builtins.str] = None,
+        jsx_fragment: typing.Optional[builtins.str] = None,
+        jsx_import_source: typing.Optional[builtins.str] = None,
     ) -> Runtime: ...
-    def compile_and_evaluate_script(self, source: builtins.str, filename: builtins.str = "inline.js") -> JSValue:
+    def compile_and_evaluate_script(
+        self, source: builtins.str, filename: builtins.str = "inline.js", timeout: typing.Optional[builtins.float] = None
+    ) -> JSValue:
         r"""
         Compiles and evaluates a script with a given filename, and returns its return value.
         Returns [Err] when script compilation fails or an exception occurs during script evaluation.
@@ -940,12 +1286,14 @@ class Runtime:
         # Arguments
         * `source` - JavaScript source code to execute
         * `filename` - Name to use in error messages (defaults to "inline.js")
+        * `timeout` - Time limit in seconds, which defaults to the timeout of the runtime
 
         # Returns
         The result of evaluating the JavaScript code as a JSValue
 
         # Errors
-        Returns an error if the compilation or evaluation fails
+        Raises a [JSError] subclass if the compilation or evaluation fails,
+        and [JSTimeoutError] if the evaluation exceeds the time limit
         """
         ...
 
@@ -954,29 +1302,392 @@ class Runtime:
         source: builtins.str,
         filename: builtins.str = "inline.js",
         path: typing.Optional[builtins.str] = "inline.js",
+        timeout: typing.Optional[builtins.float] = None,
     ) -> tuple[JSModule, typing.Optional[JSPromise]]:
         r"""
         Compiles and evaluates a [Module] with the given source and filename.
         On success, returns the compiled module object and a promise. The promise resolves with the return value of the module.
         The promise is a byproduct of enabling top-level await.
+
+        Synchronous evaluation is limited to `timeout` seconds, which defaults to the timeout
+        of the runtime, after which a [JSTimeoutError] is raised.
+        """
+        ...
+
+    def register_module(self, specifier: builtins.str, source: builtins.str, kind: builtins.str = "js") -> None:
+        r"""
+        Registers an in-memory module which can be imported as if it were a file at `specifier`.
+
+        Relative imports within the module are resolved relative to `specifier`, and may refer to
+        other registered modules or files on disk.
+
+        # Arguments
+        * `specifier` - Path under which the module is imported, such as `gen/util.js`
+        * `source` - Source code of the module
+        * `kind` - Language of the source: `"js"`, `"json"` or `"ts"`, which is compiled with the
+          TypeScript options of the runtime
+
+        # Errors
+        Raises [ValueError] if the kind is unknown, the source cannot be compiled
+        or a module is already registered for the specifier.
+        """
+        ...
+
+    def define_synthetic_module(self, name: builtins.str, exports: builtins.dict) -> None:
+        r"""
+        Defines a synthetic module named `name`, whose named exports are the items of `exports`.
+
+        The module is importable from JavaScript without any file, e.g. with
+        `import { query } from "py:db"`. Values are converted with [JSValue::from_py] when the
+        module is defined, so callables become JavaScript functions, and later changes to
+        `exports` are not reflected in the module.
+
+        # Errors
+        Raises [TypeError] if a key of `exports` is not a string and [ValueError] if a module is
+        already registered under `name`.
         """
         ...
 
     def compile_typescript(
-        self, source: builtins.str, filename: builtins.str = "inline.js"
+        self,
+        source: builtins.str,
+        filename: builtins.str = "inline.js",
+        target: builtins.str = "esnext",
+        jsx: typing.Optional[builtins.str] = None,
+        module: builtins.str = "esnext",
+        strip_only: builtins.bool = False,
+        emit_decorators: builtins.bool = False,
+        jsx_factory: typing.Optional[builtins.str] = None,
+        jsx_fragment: typing.Optional[builtins.str] = None,
+        jsx_import_source: typing.Optional[builtins.str] = None,
     ) -> tuple[builtins.str, SourceMap]:
         r"""
-        TODO: Full support for typescript
+        Compiles TypeScript into JavaScript.
+
+        # Arguments
+        * `source` - TypeScript source to compile
+        * `filename` - Name of the file, used in diagnostics and the source map
+        * `target` - ECMAScript version of the output, from `"es2015"` to `"es2022"` or `"esnext"`.
+          Syntax is not compiled down, so sources using newer syntax than the target are rejected
+        * `jsx` - How JSX is compiled, either `"preserve"`, `"react"` or `"react-jsx"`.
+          JSX is only parsed if this is set.
+        * `module` - Module format of the output, either `"esnext"` or `"commonjs"`
+        * `strip_only` - Only allow TypeScript syntax which can be erased, rejecting enums,
+          namespaces and parameter properties
+        * `emit_decorators` - Compile legacy (experimental) decorators and emit their metadata.
+          Otherwise decorators are kept as is
+        * `jsx_factory` - Function called for JSX elements in the `"react"` mode, such as `"h"`
+        * `jsx_fragment` - Component of JSX fragments in the `"react"` mode, such as `"Fragment"`
+        * `jsx_import_source` - Module which the `"react-jsx"` mode imports `jsx-runtime` from,
+          such as `"preact"`
+
+        # Returns
+        The compiled JavaScript and its source map.
+
+        # Errors
+        Raises [ValueError] for an unknown option value or a JSX pragma which does not apply to
+        the JSX mode, and [TypeScriptError] with the diagnostics of the compilation if the source
+        cannot be compiled.
+        """
+        ...
+
+    def compile_and_evaluate_typescript(
+        self,
+        source: builtins.str,
+        filename: typing.Optional[builtins.str] = None,
+        as_module: builtins.bool = False,
+        timeout: typing.Optional[builtins.float] = None,
+    ) -> typing.Union[JSValue, tuple[JSModule, typing.Optional[JSPromise]]]:
+        r"""
+        Compiles TypeScript and evaluates the output as a script or a module.
+
+        The source is compiled with the TypeScript options of the runtime, and JSX is only
+        compiled if `filename` ends with `.tsx`. The source map of the compiled JavaScript is
+        registered under `filename`, so the locations of errors raised by the evaluation refer to
+        lines of the TypeScript source.
+
+        # Arguments
+        * `source` - TypeScript source code to execute
+        * `filename` - Name of the file, used in error messages and to register the source map,
+          which defaults to a unique name such as `inline-1.ts`
+        * `as_module` - Whether to evaluate the source as a module rather than a script
+        * `timeout` - Time limit in seconds, which defaults to the timeout of the runtime
+
+        # Returns
+        The result of the script as a JSValue, or the module and the promise of its evaluation
+        as returned by `compile_and_evaluate_module`.
+
+        # Errors
+        Raises [TypeScriptError] if the source cannot be compiled, a [JSError] subclass if the
+        evaluation fails, and [JSTimeoutError] if the evaluation exceeds the time limit
+        """
+        ...
+
+    def run_event_loop(
+        self, timeout: typing.Optional[builtins.float] = None, max_ticks: typing.Optional[builtins.int] = None
+    ) -> EventLoopStatus:
+        r"""
+        Runs the event loop until it has no pending work left.
+
+        Each tick runs the ready futures, one macrotask such as a timer callback and then
+        the microtasks queued by it.
+
+        # Arguments
+        * `timeout` - Maximum time to run for, in seconds
+        * `max_ticks` - Maximum number of ticks of the event loop to run
+
+        # Returns
+        `EventLoopStatus.Idle` if the event loop has no pending work left, or
+        `EventLoopStatus.TimedOut` if the timeout or tick limit was reached first. Pending work
+        is kept and continues on the next run.
+
+        # Errors
+        Raises [ValueError] for an invalid timeout, [RuntimeError] if the event loop is already
+        running, a [JSError] subclass if a task of the event loop throws, and [JSTimeoutError]
+        if a task is still running when the timeout elapses.
+        """
+        ...
+
+    def run_microtasks(self) -> None:
+        r"""
+        Runs the jobs of the microtask queue, such as promise reactions, until it is empty.
+        Timers and futures are not run.
+
+        # Errors
+        Raises [RuntimeError] if the event loop is already running, and a [JSError] subclass
+        if a job throws.
         """
         ...
 
-    def run_event_loop(self) -> None: ...
+    def run_until_settled(
+        self, promise: JSPromise, timeout: typing.Optional[builtins.float] = None
+    ) -> EventLoopStatus:
+        r"""
+        Runs the event loop until the promise is settled.
+
+        # Arguments
+        * `promise` - The promise to wait for
+        * `timeout` - Maximum time to run for, in seconds
+
+        # Returns
+        `EventLoopStatus.PromiseSettled` once the promise is settled, `EventLoopStatus.Idle` if
+        the event loop ran out of work while the promise is still pending, or
+        `EventLoopStatus.TimedOut` if the timeout was reached first.
+
+        # Errors
+        Raises [ValueError] for an invalid timeout, [RuntimeError] if the event loop is already
+        running, a [JSError] subclass if a task of the event loop throws, and [JSTimeoutError]
+        if a task is still running when the timeout elapses.
+        """
+        ...
+
+    def new_realm(self) -> Realm:
+        r"""
+        Creates a new [Realm](PyRealm) with its own global object, isolated from the globals of
+        the runtime and of other realms.
+
+        The realm has the same capabilities as the runtime, but no timers and no modules.
+        """
+        ...
+
+    def heap_stats(self) -> HeapStats:
+        r"""
+        Returns a snapshot of the memory usage and garbage collection counts of the heap.
+        """
+        ...
+
+    def gc(self, shrinking: builtins.bool = False) -> None:
+        r"""
+        Runs a full, non-incremental garbage collection.
+
+        # Arguments
+        * `shrinking` - Also release unused memory back to the operating system and compact
+          the heap where possible
+        """
+        ...
+
+    def close(self) -> None:
+        r"""
+        Closes the runtime, releasing the JavaScript engine resources of the current thread.
+
+        Using the runtime or any value created within it afterwards raises
+        [JSRuntimeClosedError], and a new runtime can be created on this thread. Closing a
+        runtime which is already closed does nothing.
+
+        # Errors
+        Raises [RuntimeError] if called while JavaScript code of the runtime is running, such
+        as from a Python callback.
+        """
+        ...
+
+    @property
+    def closed(self) -> builtins.bool:
+        r"""
+        Whether the runtime has been closed.
+        """
+        ...
+
+    def __enter__(self) -> Runtime: ...
+    def __exit__(self, *_args: typing.Any) -> None: ...
     @staticmethod
     def is_initialized() -> builtins.bool: ...
     @staticmethod
-    def empty() -> Runtime: ...
+    def empty() -> Runtime:
+        r"""
+        Returns another reference to the runtime of the current thread, which does not close the
+        runtime when it is dropped, unlike the object returned when creating the runtime.
+        """
+
+class RuntimePool:
+    r"""
+    A pool of threads which each own a JavaScript runtime.
+
+    Unlike [Runtime](PythonJSRuntime) and JavaScript values, which can only be used on the
+    thread that created them, the pool can be shared by any number of Python threads. Work is
+    submitted as source code or module exports, and results are delivered through
+    `concurrent.futures.Future`s as plain Python values.
+
+    Runtimes of the pool do not share any state, so globals set by one task are only visible
+    to later tasks which happen to run on the same runtime.
+    """
+    def __init__(self, size: builtins.int = 1, **options: typing.Any) -> RuntimePool:
+        r"""
+        Creates a pool of `size` threads, each with its own runtime.
+
+        # Arguments
+        * `size` - Number of threads and runtimes
+        * `options` - Keyword arguments for the [Runtime](PythonJSRuntime) of each thread
+
+        # Errors
+        Raises [ValueError] if `size` is 0, and the error of the first runtime which fails to
+        be created.
+        """
+        ...
+
+    @property
+    def size(self) -> builtins.int:
+        r"""
+        Number of threads and runtimes of the pool.
+        """
+        ...
+
+    def submit(
+        self, source: builtins.str, filename: builtins.str = "inline.js", timeout: typing.Optional[builtins.float] = None
+    ) -> concurrent.futures.Future[typing.Any]:
+        r"""
+        Evaluates a script on one of the runtimes.
+
+        # Arguments
+        * `source` - JavaScript source code to execute
+        * `filename` - Name to use in error messages (defaults to "inline.js")
+        * `timeout` - Time limit in seconds, which defaults to the timeout of the runtimes
+
+        # Returns
+        A `concurrent.futures.Future` of the result of the script, converted into a plain
+        Python value. Promises are awaited first.
+        """
+        ...
+
+    def call(
+        self,
+        module: builtins.str,
+        function: builtins.str,
+        args: typing.Sequence[typing.Any] = [],
+        timeout: typing.Optional[builtins.float] = None,
+    ) -> concurrent.futures.Future[typing.Any]:
+        r"""
+        Calls a function exported by a module on one of the runtimes.
+
+        # Arguments
+        * `module` - Specifier of the module, resolved like a dynamic `import()`
+        * `function` - Name of the exported function
+        * `args` - Arguments of the call, which are converted with [JSValue::from_py]
+        * `timeout` - Time limit in seconds, which defaults to the timeout of the runtimes
+
+        # Returns
+        A `concurrent.futures.Future` of the return value of the function, converted into a
+        plain Python value. Promises are awaited first.
+        """
+        ...
+
+    def shutdown(self, wait: builtins.bool = True) -> None:
+        r"""
+        Stops accepting work and shuts the runtimes down once the queued work is done.
+
+        # Arguments
+        * `wait` - Wait until the queued work is done and the threads have exited
+        """
+        ...
+
+    def __enter__(self) -> RuntimePool: ...
+    def __exit__(self, *_args: typing.Any) -> None: ...
 
 class SourceMap:
+    r"""
+    Maps positions of generated JavaScript back to the sources it was compiled from.
+    """
+    @staticmethod
+    def from_json(json: builtins.str) -> SourceMap:
+        r"""
+        Parses a source map from its JSON representation.
+
+        # Errors
+        Raises [ValueError] if the JSON is not a valid source map.
+        """
+        ...
+
+    def to_json(self) -> builtins.str:
+        r"""
+        Serialises the source map to JSON, in the format served to browsers and other tools.
+        """
+        ...
+
+    def lookup(self, line: builtins.int, column: builtins.int) -> typing.Optional[OriginalPosition]:
+        r"""
+        Finds the original position of a position in the generated code.
+
+        # Arguments
+        * `line` - 1-based line number in the generated code
+        * `column` - 1-based column number in the generated code
+
+        # Returns
+        The position in the original source, or [None] if the generated position is not mapped.
+        """
+        ...
+
+    def sources(self) -> builtins.list[builtins.str]:
+        r"""
+        Returns the names of the original sources.
+        """
+        ...
+
+    def names(self) -> builtins.list[builtins.str]:
+        r"""
+        Returns the original names of identifiers referenced by the mappings.
+        """
+        ...
+
+    def sources_content(self) -> builtins.list[typing.Optional[builtins.str]]:
+        r"""
+        Returns the contents of each original source, in the order of [PySourceMap::sources],
+        or [None] for sources whose contents are not embedded.
+        """
+        ...
+
+    def __repr__(self) -> builtins.str: ...
+
+class StackFrame:
+    r"""
+    Represents a single frame of a JavaScript stack trace.
+    """
+    function: typing.Optional[builtins.str]
+    filename: builtins.str
+    line: builtins.int
+    column: builtins.int
+    generated_filename: builtins.str
+    generated_line: builtins.int
+    generated_column: builtins.int
+    def __str__(self) -> builtins.str: ...
     def __repr__(self) -> builtins.str: ...
 
 class Symbol:
@@ -1050,6 +1761,62 @@ class SymbolCode:
 
     def __repr__(self) -> builtins.str: ...
 
+class TypeScriptDiagnostic:
+    r"""
+    A problem found while compiling TypeScript.
+    """
+    code: typing.Optional[builtins.str]
+    message: builtins.str
+    filename: builtins.str
+    line: builtins.int
+    column: builtins.int
+    def __str__(self) -> builtins.str: ...
+    def __repr__(self) -> builtins.str: ...
+
+class TypeScriptError(JSCompileError):
+    r"""
+    Raised when TypeScript source fails to compile.
+    """
+    diagnostics: builtins.list[TypeScriptDiagnostic]
+
+class ConversionFallback(Enum):
+    r"""
+    Decides what happens to JavaScript values that have no native Python counterpart,
+    such as functions, symbols, promises or class instances wrapped by proxies.
+    """
+
+    Error = auto()
+    r"""
+    Raise a `TypeError`.
+    """
+    Wrap = auto()
+    r"""
+    Keep the value as a `JSValue`.
+    """
+    Null = auto()
+    r"""
+    Replace the value with `None`.
+    """
+
+class EventLoopStatus(Enum):
+    r"""
+    Outcome of running the event loop with [PythonJSRuntime::run_event_loop] or
+    [PythonJSRuntime::run_until_settled].
+    """
+
+    Idle = auto()
+    r"""
+    The event loop has no pending work left.
+    """
+    TimedOut = auto()
+    r"""
+    The timeout or the tick limit was reached while work was still pending.
+    """
+    PromiseSettled = auto()
+    r"""
+    The awaited promise was settled.
+    """
+
 class JSPromiseState(Enum):
     Pending = auto()
     Fulfilled = auto()
@@ -0,0 +1,169 @@
+use crate::conversions::{DEFAULT_MAX_DEPTH, PyToJS};
+use crate::errors::to_js_exception;
+use crate::handle::Handle;
+use crate::runtime::{with_js_cx, within_local_set};
+use crate::traits::ExtendLifetime;
+use crate::types::promise::{PyJSPromise, wake_drivers};
+use ion::conversions::ToValue;
+use ion::{Context, Value};
+use js_runtime::promise::future_to_promise;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyCFunction};
+use std::cell::{Cell, RefCell};
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+thread_local! {
+    /// asyncio event loop which runs awaitables while no event loop is running on the thread.
+    static DRIVER_LOOP: RefCell<Option<PyObject>> = const { RefCell::new(None) };
+    /// Number of awaitables whose promises are not settled yet.
+    static PENDING_AWAITABLES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Returns the number of awaitables of the current thread whose promises are not settled yet.
+pub fn pending_awaitables() -> usize {
+    PENDING_AWAITABLES.get()
+}
+
+/// Counts an awaitable as pending until it is dropped, along with the future waiting for it.
+struct Pending;
+
+impl Pending {
+    fn new() -> Pending {
+        PENDING_AWAITABLES.set(PENDING_AWAITABLES.get() + 1);
+        Pending
+    }
+}
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        let _ = PENDING_AWAITABLES.try_with(|pending| pending.set(pending.get() - 1));
+    }
+}
+
+/// Value which settles the promise of an awaitable, rooted until the promise is settled.
+struct Settlement(Handle<Value<'static>>);
+
+impl Settlement {
+    fn new(value: Value<'_>) -> Settlement {
+        Settlement(Handle::new(value.extend_lifetime()))
+    }
+}
+
+impl<'cx> ToValue<'cx> for Settlement {
+    fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+        if let Ok(settlement) = self.0.get() {
+            settlement.to_value(cx, value);
+        }
+    }
+}
+
+/// Creates a [Promise](ion::Promise) which is settled with the outcome of a Python awaitable.
+///
+/// The awaitable is scheduled as a task on the running asyncio event loop, or on a private event
+/// loop of the current thread when none is running. A future waiting for the task is pushed onto
+/// the future queue of the runtime, so the JavaScript event loop is kept alive until the promise
+/// is settled. The future is woken by a done callback of the task, or steps the private event
+/// loop one iteration at a time while the JavaScript event loop is driven.
+pub fn promise_from_awaitable(
+    py: Python<'_>,
+    awaitable: &Bound<'_, PyAny>,
+) -> PyResult<Py<PyJSPromise>> {
+    let asyncio = py.import("asyncio")?;
+    let running = asyncio.call_method0("_get_running_loop")?;
+    let (event_loop, driver) = if running.is_none() {
+        let driver = driver_loop(py)?;
+        (driver.clone(), Some(driver.unbind()))
+    } else {
+        (running, None)
+    };
+
+    let kwargs = [("loop", event_loop)].into_py_dict(py)?;
+    let task = asyncio.call_method("ensure_future", (awaitable,), Some(&kwargs))?;
+    let (sender, receiver) = oneshot::channel();
+    let sender = Mutex::new(Some(sender));
+    let wake = PyCFunction::new_closure(py, None, None, move |_, _| {
+        if let Some(sender) = sender.lock().unwrap().take() {
+            let _ = sender.send(());
+        }
+        // Promises awaited from asyncio may wait for this one.
+        wake_drivers();
+    })?;
+    task.call_method1("add_done_callback", (wake,))?;
+
+    let task = task.unbind();
+    let pending = Pending::new();
+    let future = async move {
+        let _pending = pending;
+        if let Some(driver) = driver {
+            // Nothing else runs the private event loop, so it is stepped whenever the future is
+            // polled, yielding to the JavaScript event loop between steps. Timers and other tasks
+            // keep running, and the task can itself await promises settled by them.
+            while !Python::with_gil(|py| step(driver.bind(py), task.bind(py)))
+                .map_err(|error| with_js_cx(|cx| rejection(cx, error)))?
+            {
+                tokio::task::yield_now().await;
+            }
+        } else {
+            let _ = receiver.await;
+        }
+        Python::with_gil(|py| settle(task.bind(py)))
+    };
+
+    let promise = with_js_cx(|cx| {
+        within_local_set(|| future_to_promise(cx, future)).map(|p| p.extend_lifetime())
+    })
+    .ok_or_else(|| {
+        PyRuntimeError::new_err("Awaitables require the macrotask queue to be enabled")
+    })?;
+    Py::new(py, PyJSPromise::from(promise))
+}
+
+/// Returns the private asyncio event loop of the current thread, creating it if necessary.
+fn driver_loop(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    DRIVER_LOOP.with(|cell| {
+        let mut driver = cell.borrow_mut();
+        if let Some(event_loop) = driver.as_ref() {
+            let event_loop = event_loop.bind(py);
+            if !event_loop.call_method0("is_closed")?.is_truthy()? {
+                return Ok(event_loop.clone());
+            }
+        }
+        let event_loop = py.import("asyncio")?.call_method0("new_event_loop")?;
+        *driver = Some(event_loop.clone().unbind());
+        Ok(event_loop)
+    })
+}
+
+/// Runs a single iteration of the private event loop, unless it is already running further up
+/// the stack, and returns whether the task is done.
+fn step(driver: &Bound<'_, PyAny>, task: &Bound<'_, PyAny>) -> PyResult<bool> {
+    let done = || task.call_method0("done")?.is_truthy();
+    if !done()? && !driver.call_method0("is_running")?.is_truthy()? {
+        // Stopping first makes the event loop return after one iteration, which runs the ready
+        // callbacks without waiting for timers or I/O.
+        driver.call_method1("call_soon", (driver.getattr("stop")?,))?;
+        driver.call_method0("run_forever")?;
+    }
+    done()
+}
+
+/// Converts the outcome of a done task into the value which fulfils or rejects its promise.
+///
+/// `Task.result` raises the exception of the task, or `CancelledError` if it was cancelled,
+/// which rejects the promise.
+fn settle(task: &Bound<'_, PyAny>) -> Result<Settlement, Settlement> {
+    with_js_cx(|cx| {
+        let result = task.call_method0("result")?;
+        PyToJS::new(cx, DEFAULT_MAX_DEPTH)
+            .convert(&result)
+            .map(Settlement::new)
+    })
+    .map_err(|error| with_js_cx(|cx| rejection(cx, error)))
+}
+
+/// Converts a Python exception into the value which rejects the promise of an awaitable.
+fn rejection(cx: &Context, error: PyErr) -> Settlement {
+    Settlement::new(to_js_exception(error).as_value(cx))
+}
@@ -0,0 +1,166 @@
+use crate::errors::JSErrorHandling;
+use ion::script::Script;
+use ion::{Context, Object};
+use js_runtime::modules::StandardModules;
+use modules::Modules;
+use mozjs::jsapi::{
+    HandleString, JS_SetSecurityCallbacks, JSContext, JSSecurityCallbacks, RuntimeCode,
+};
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+
+/// Specifiers of the standard module which accesses the filesystem.
+const FS_MODULES: [&str; 2] = ["fs", "node:fs"];
+
+/// Globals which access the filesystem, defined by the standard modules.
+const FS_GLOBALS: [&str; 1] = ["fs"];
+
+/// Globals which perform network requests.
+const NET_GLOBALS: [&str; 1] = ["fetch"];
+
+/// Globals which schedule macrotasks.
+const TIMER_GLOBALS: [&str; 5] = [
+    "setTimeout",
+    "setInterval",
+    "clearTimeout",
+    "clearInterval",
+    "queueMacrotask",
+];
+
+/// Replaces globals by accessors which throw. Globals in `required` must be replaceable,
+/// while those in `optional` are left as they are if they cannot be replaced.
+const RESTRICT_GLOBALS: &str = r#"(required, optional) => {
+    for (const name of [...required, ...optional]) {
+        const replaced = Reflect.defineProperty(globalThis, name, {
+            get() {
+                throw new Error(`${name} is not allowed in this runtime`);
+            },
+            configurable: false,
+        });
+        if (!replaced && required.includes(name)) {
+            throw new Error(`${name} cannot be removed`);
+        }
+    }
+}"#;
+
+/// Rejects every compilation of strings, such as `eval` and the `Function` constructor.
+static DENY_EVAL_CALLBACKS: JSSecurityCallbacks = JSSecurityCallbacks {
+    contentSecurityPolicyAllows: Some(deny_eval),
+    subsumes: None,
+};
+
+unsafe extern "C" fn deny_eval(_: *mut JSContext, _: RuntimeCode, _: HandleString) -> bool {
+    false
+}
+
+/// Set of operations that JavaScript code running in a runtime is allowed to perform.
+///
+/// Disallowed globals are replaced by accessors which throw, and disallowed modules fail to
+/// resolve, so untrusted code gets an `Error` instead of silently missing functionality.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Capabilities {
+    /// Reading module files from disk and the `fs` standard module.
+    pub fs: bool,
+    /// Network requests with `fetch`.
+    pub net: bool,
+    /// Timers such as `setTimeout`, which require the macrotask queue.
+    pub timers: bool,
+    /// Importing modules and the standard modules.
+    pub modules: bool,
+    /// Compiling strings with `eval`, `new Function` and similar.
+    pub eval: bool,
+}
+
+impl Default for Capabilities {
+    fn default() -> Capabilities {
+        Capabilities {
+            fs: true,
+            net: true,
+            timers: true,
+            modules: true,
+            eval: true,
+        }
+    }
+}
+
+impl Capabilities {
+    /// Returns the reason an import of `specifier` is disallowed, if it is.
+    pub fn check_import(&self, specifier: &str) -> Option<String> {
+        if !self.modules {
+            Some(format!(
+                "Cannot import \"{specifier}\": modules are not allowed in this runtime"
+            ))
+        } else if !self.fs && FS_MODULES.contains(&specifier) {
+            Some(format!(
+                "Cannot import \"{specifier}\": filesystem access is not allowed in this runtime"
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the reason reading module files from disk is disallowed, if it is.
+    pub fn check_read(&self, path: &str) -> Option<String> {
+        (!self.fs).then(|| {
+            format!(
+                "Cannot read module \"{path}\": filesystem access is not allowed in this runtime"
+            )
+        })
+    }
+
+    /// Returns the standard modules to install, which are omitted when modules are disallowed.
+    pub fn standard_modules(&self) -> AllowedModules {
+        AllowedModules(self.modules.then_some(Modules))
+    }
+
+    /// Removes the disallowed globals of the runtime and disables the compilation of strings.
+    ///
+    /// Globals which cannot be replaced make this fail, so that a runtime is never created with
+    /// more capabilities than requested. Timers are exempt, as calling them throws anyway
+    /// without a macrotask queue.
+    pub fn restrict(&self, cx: &Context) -> PyResult<()> {
+        let mut required = Vec::new();
+        let mut optional = Vec::new();
+        if !self.net {
+            required.extend(NET_GLOBALS);
+        }
+        if !self.fs {
+            required.extend(FS_GLOBALS);
+        }
+        if !self.timers {
+            optional.extend(TIMER_GLOBALS);
+        }
+
+        if !required.is_empty() || !optional.is_empty() {
+            let source = format!(
+                "({RESTRICT_GLOBALS})({}, {});",
+                serde_json::to_string(&required).unwrap(),
+                serde_json::to_string(&optional).unwrap(),
+            );
+            Script::compile_and_evaluate(cx, "capabilities.js".as_ref(), &source)
+                .to_js_err(cx)
+                .map_err(|err| {
+                    PyRuntimeError::new_err(format!("Failed to restrict capabilities: {err}"))
+                })?;
+        }
+
+        if !self.eval {
+            unsafe { JS_SetSecurityCallbacks(cx.as_ptr(), &DENY_EVAL_CALLBACKS) };
+        }
+        Ok(())
+    }
+}
+
+/// The standard modules of spiderfire, unless modules are disallowed.
+pub struct AllowedModules(Option<Modules>);
+
+impl StandardModules for AllowedModules {
+    fn init(self, cx: &Context, global: &Object) -> bool {
+        self.0.is_none_or(|modules| modules.init(cx, global))
+    }
+
+    fn init_globals(self, cx: &Context, global: &Object) -> bool {
+        self.0
+            .is_none_or(|modules| modules.init_globals(cx, global))
+    }
+}
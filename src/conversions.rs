@@ -0,0 +1,540 @@
+use crate::awaitables::promise_from_awaitable;
+use crate::errors::ErrorHandling;
+use crate::traits::ExtendLifetime;
+use crate::types::bigint::PyJSBigInt;
+use crate::types::callable::{PyCallable, VarArgs};
+use crate::types::function::PyJSFunction;
+use crate::types::object::PyJSObject;
+use crate::types::promise::PyJSPromise;
+use crate::types::symbol::PySymbol;
+use crate::types::value::PyJSValue;
+use ion::conversions::{FromValue, ToValue};
+use ion::{
+    Array as JSArray, BigInt as JSBigInt, Context, Function as JSFunction, Object as JSObject,
+    OwnedKey, Value as JSValue,
+};
+use mozjs::jsapi::{
+    ClippedTime, DateGetMsecSinceEpoch, ESClass, JS_GetArrayBufferViewType,
+    JS_GetTypedArrayLength, JS_IsArrayBufferObject, JS_IsTypedArrayObject,
+    JSObject as RawJSObject, NewDateObject, Type,
+};
+use mozjs::rooted;
+use mozjs::typedarray::{ArrayBuffer, CreateWith, Uint8Array, Uint8ClampedArray};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{
+    PyBool, PyByteArray, PyBytes, PyDict, PyFloat, PyInt, PyList, PyMapping, PySequence, PySet,
+    PyString, PyTuple,
+};
+use pyo3_stub_gen::derive::gen_stub_pyclass_enum;
+use std::ptr;
+
+/// Largest integer that can be represented exactly by a JavaScript number.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Default nesting limit used when converting JavaScript values into Python objects.
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// Decides what happens to JavaScript values that have no native Python counterpart,
+/// such as functions, symbols, promises or class instances wrapped by proxies.
+#[gen_stub_pyclass_enum]
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[pyclass(
+    unsendable,
+    name = "ConversionFallback",
+    module = "js_exec.js_exec",
+    eq,
+    eq_int
+)]
+pub enum PyConversionFallback {
+    /// Raise a `TypeError`.
+    #[default]
+    Error = 0,
+    /// Keep the value as a `JSValue`.
+    Wrap = 1,
+    /// Replace the value with `None`.
+    Null = 2,
+}
+
+/// Recursively converts [JSValue]s into native Python objects.
+///
+/// Objects currently being converted are tracked so that cyclic structures are reported
+/// instead of recursing until the depth limit is reached.
+pub struct JSToPy<'cx, 'py> {
+    cx: &'cx Context,
+    py: Python<'py>,
+    max_depth: usize,
+    fallback: PyConversionFallback,
+    ancestors: Vec<*mut RawJSObject>,
+}
+
+impl<'cx, 'py> JSToPy<'cx, 'py> {
+    pub fn new(
+        cx: &'cx Context,
+        py: Python<'py>,
+        max_depth: usize,
+        fallback: PyConversionFallback,
+    ) -> Self {
+        JSToPy {
+            cx,
+            py,
+            max_depth,
+            fallback,
+            ancestors: Vec::new(),
+        }
+    }
+
+    /// Converts a [JSValue] and everything reachable from it.
+    pub fn convert(&mut self, value: &JSValue) -> PyResult<Bound<'py, PyAny>> {
+        let py = self.py;
+        let handle = value.handle();
+
+        if handle.is_null_or_undefined() {
+            Ok(py.None().into_bound(py))
+        } else if handle.is_boolean() {
+            Ok(handle.to_boolean().into_pyobject(py)?.to_owned().into_any())
+        } else if handle.is_int32() {
+            Ok(handle.to_int32().into_pyobject(py)?.into_any())
+        } else if handle.is_double() {
+            Ok(handle.to_double().into_pyobject(py)?.into_any())
+        } else if handle.is_string() {
+            let string = String::from_value(self.cx, value, true, ())
+                .to_value_err("Failed to convert to String")?;
+            Ok(string.into_pyobject(py)?.into_any())
+        } else if handle.is_bigint() {
+            let bigint = JSBigInt::from(self.cx.root(handle.to_bigint()));
+            self.convert_bigint(&bigint)
+        } else if handle.is_object() {
+            let object = value.to_object(self.cx);
+            self.convert_object(value, &object)
+        } else {
+            self.unsupported(value, "symbol")
+        }
+    }
+
+    fn convert_bigint(&self, bigint: &JSBigInt) -> PyResult<Bound<'py, PyAny>> {
+        let py = self.py;
+        if let Some(int) = bigint.to_i64() {
+            return Ok(int.into_pyobject(py)?.into_any());
+        }
+        let digits = bigint
+            .to_string(self.cx, 10)
+            .map(|s| s.to_owned(self.cx))
+            .transpose()
+            .to_value_err("Failed to convert BigInt to string")?
+            .unwrap_or_default();
+        py.get_type::<PyInt>().call1((digits,))
+    }
+
+    fn convert_object(
+        &mut self,
+        value: &JSValue,
+        object: &JSObject,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let raw = object.handle().get();
+        if self.ancestors.contains(&raw) {
+            return Err(PyValueError::new_err(
+                "Cyclic reference detected while converting JavaScript object",
+            ));
+        }
+        if self.ancestors.len() >= self.max_depth {
+            return Err(PyValueError::new_err(format!(
+                "Maximum conversion depth of {} exceeded",
+                self.max_depth
+            )));
+        }
+
+        self.ancestors.push(raw);
+        let result = self.convert_object_inner(value, object);
+        self.ancestors.pop();
+        result
+    }
+
+    fn convert_object_inner(
+        &mut self,
+        value: &JSValue,
+        object: &JSObject,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let cx = self.cx;
+        let raw = object.handle().get();
+
+        match object.get_builtin_class(cx) {
+            ESClass::Object | ESClass::Arguments => self.convert_plain_object(object),
+            ESClass::Array => self.convert_array_like(object),
+            ESClass::Map => {
+                let entries = array_from(cx, value)?;
+                let dict = PyDict::new(self.py);
+                for entry in self.array_elements(&entries)? {
+                    let pair = entry.to_object(cx);
+                    let key = self.get_converted(&pair, 0)?;
+                    let value = self.get_converted(&pair, 1)?;
+                    dict.set_item(key, value)?;
+                }
+                Ok(dict.into_any())
+            }
+            ESClass::Set => {
+                let entries = array_from(cx, value)?;
+                let set = PySet::empty(self.py)?;
+                for entry in self.array_elements(&entries)? {
+                    set.add(self.convert(&entry)?)?;
+                }
+                Ok(set.into_any())
+            }
+            ESClass::Date => {
+                let mut millis = f64::NAN;
+                let valid = unsafe {
+                    DateGetMsecSinceEpoch(cx.as_ptr(), object.handle().into(), &mut millis)
+                };
+                if !valid || millis.is_nan() {
+                    return Err(PyValueError::new_err("Cannot convert an invalid Date"));
+                }
+                let datetime = self.py.import("datetime")?;
+                let utc = datetime.getattr("timezone")?.getattr("utc")?;
+                datetime
+                    .getattr("datetime")?
+                    .call_method1("fromtimestamp", (millis / 1000.0, utc))
+            }
+            ESClass::Boolean | ESClass::Number | ESClass::String | ESClass::BigInt => {
+                match object.unbox_primitive(cx) {
+                    Some(primitive) => self.convert(&primitive),
+                    None => self.unsupported(value, "boxed primitive"),
+                }
+            }
+            ESClass::ArrayBuffer if unsafe { JS_IsArrayBufferObject(raw) } => {
+                let buffer = ArrayBuffer::from(raw)
+                    .map_err(|_| PyValueError::new_err("Failed to read ArrayBuffer"))?;
+                Ok(PyBytes::new(self.py, &buffer.to_vec()).into_any())
+            }
+            ESClass::Other if unsafe { JS_IsTypedArrayObject(raw) } => {
+                self.convert_typed_array(object)
+            }
+            ESClass::Function => self.unsupported(value, "function"),
+            ESClass::Promise => self.unsupported(value, "promise"),
+            ESClass::Error => self.unsupported(value, "error"),
+            _ => self.unsupported(value, "object"),
+        }
+    }
+
+    fn convert_plain_object(&mut self, object: &JSObject) -> PyResult<Bound<'py, PyAny>> {
+        let cx = self.cx;
+        let dict = PyDict::new(self.py);
+        let keys = object
+            .keys(cx, None)
+            .map(|key| key.to_owned_key(cx))
+            .collect::<Result<Vec<_>, _>>()
+            .to_value_err("Failed to get owned keys")?;
+
+        for key in keys {
+            let value = match &key {
+                OwnedKey::Int(index) => object.get(cx, *index),
+                OwnedKey::String(name) => object.get(cx, name.as_str()),
+                // Symbol keys have no meaningful representation as dictionary keys.
+                OwnedKey::Symbol(_) | OwnedKey::Void => continue,
+            }
+            .to_value_err("Failed to get value")?;
+
+            let key = match key {
+                OwnedKey::Int(index) => index.to_string(),
+                OwnedKey::String(name) => name,
+                OwnedKey::Symbol(_) | OwnedKey::Void => unreachable!(),
+            };
+            let value = match value {
+                Some(value) => self.convert(&value)?,
+                None => self.py.None().into_bound(self.py),
+            };
+            dict.set_item(key, value)?;
+        }
+        Ok(dict.into_any())
+    }
+
+    fn convert_array_like(&mut self, object: &JSObject) -> PyResult<Bound<'py, PyAny>> {
+        let list = PyList::empty(self.py);
+        for element in self.array_elements(object)? {
+            list.append(self.convert(&element)?)?;
+        }
+        Ok(list.into_any())
+    }
+
+    fn convert_typed_array(&mut self, object: &JSObject) -> PyResult<Bound<'py, PyAny>> {
+        let raw = object.handle().get();
+        let bytes = match unsafe { JS_GetArrayBufferViewType(raw) } {
+            Type::Uint8 => Uint8Array::from(raw).map(|array| array.to_vec()),
+            Type::Uint8Clamped => Uint8ClampedArray::from(raw).map(|array| array.to_vec()),
+            _ => {
+                // Wider element types are exposed as a list of numbers (or ints for BigInt arrays).
+                let list = PyList::empty(self.py);
+                let length = unsafe { JS_GetTypedArrayLength(raw) };
+                for index in 0..length {
+                    list.append(self.get_converted(object, index as u32)?)?;
+                }
+                return Ok(list.into_any());
+            }
+        };
+        let bytes = bytes.map_err(|_| PyValueError::new_err("Failed to read typed array"))?;
+        Ok(PyBytes::new(self.py, &bytes).into_any())
+    }
+
+    /// Collects the elements of an array-like object, treating holes as `undefined`.
+    fn array_elements(&self, object: &JSObject) -> PyResult<Vec<JSValue<'cx>>> {
+        let cx = self.cx;
+        let length = object
+            .get(cx, "length")
+            .to_value_err("Failed to get length")?
+            .filter(|length| length.handle().is_number())
+            .map(|length| length.handle().to_number() as u32)
+            .unwrap_or(0);
+
+        (0..length)
+            .map(|index| {
+                object
+                    .get(cx, index)
+                    .map(|value| value.unwrap_or_else(|| JSValue::undefined(cx)))
+                    .to_value_err("Failed to get element")
+            })
+            .collect()
+    }
+
+    fn get_converted(&mut self, object: &JSObject, index: u32) -> PyResult<Bound<'py, PyAny>> {
+        match object.get(self.cx, index).to_value_err("Failed to get element")? {
+            Some(value) => self.convert(&value),
+            None => Ok(self.py.None().into_bound(self.py)),
+        }
+    }
+
+    fn unsupported(&self, value: &JSValue, kind: &str) -> PyResult<Bound<'py, PyAny>> {
+        let py = self.py;
+        match self.fallback {
+            PyConversionFallback::Error => Err(PyTypeError::new_err(format!(
+                "Cannot convert JavaScript {} to a Python object",
+                kind
+            ))),
+            PyConversionFallback::Wrap => {
+                let value = JSValue::from_value(self.cx, value, true, ())
+                    .to_value_err("Failed to convert to JSValue")?;
+                Ok(Bound::new(py, PyJSValue::from(value.extend_lifetime()))?.into_any())
+            }
+            PyConversionFallback::Null => Ok(py.None().into_bound(py)),
+        }
+    }
+}
+
+/// Calls `Array.from` on the given value, used to snapshot the entries of a `Map` or `Set`.
+fn array_from<'cx>(cx: &'cx Context, value: &JSValue) -> PyResult<JSObject<'cx>> {
+    let array_constructor = JSObject::global(cx)
+        .get(cx, "Array")
+        .to_runtime_err("Failed to get Array")?
+        .map(|array| array.to_object(cx))
+        .ok_or_else(|| PyValueError::new_err("Array constructor is not available"))?;
+    let from = array_constructor
+        .get(cx, "from")
+        .to_runtime_err("Failed to get Array.from")?
+        .and_then(|from| JSFunction::from_object(cx, &from.to_object(cx)))
+        .ok_or_else(|| PyValueError::new_err("Array.from is not available"))?;
+
+    let argument =
+        JSValue::from_value(cx, value, true, ()).to_value_err("Failed to convert to JSValue")?;
+    let entries = from
+        .call(cx, &array_constructor, &[argument])
+        .to_runtime_err("Failed to call Array.from")?;
+    Ok(entries.to_object(cx))
+}
+
+/// Recursively converts native Python objects into [JSValue]s.
+///
+/// Existing wrappers such as `JSValue` or `JSObject` are passed through unchanged,
+/// awaitables become promises and Python callables become JavaScript functions.
+pub struct PyToJS<'cx> {
+    cx: &'cx Context,
+    max_depth: usize,
+    ancestors: Vec<*mut pyo3::ffi::PyObject>,
+}
+
+impl<'cx> PyToJS<'cx> {
+    pub fn new(cx: &'cx Context, max_depth: usize) -> Self {
+        PyToJS {
+            cx,
+            max_depth,
+            ancestors: Vec::new(),
+        }
+    }
+
+    /// Converts a Python object and everything reachable from it.
+    pub fn convert(&mut self, ob: &Bound<'_, PyAny>) -> PyResult<JSValue<'cx>> {
+        let cx = self.cx;
+
+        if let Some(value) = self.convert_wrapper(ob)? {
+            return Ok(value);
+        }
+
+        if ob.is_none() {
+            Ok(JSValue::null(cx))
+        } else if let Ok(boolean) = ob.downcast::<PyBool>() {
+            Ok(JSValue::bool(cx, boolean.is_true()))
+        } else if ob.is_instance_of::<PyInt>() {
+            self.convert_int(ob)
+        } else if let Ok(float) = ob.downcast::<PyFloat>() {
+            Ok(JSValue::f64(cx, float.value()))
+        } else if let Ok(string) = ob.downcast::<PyString>() {
+            Ok(JSValue::string(cx, &string.to_cow()?))
+        } else if let Ok(bytes) = ob.downcast::<PyBytes>() {
+            self.convert_bytes(bytes.as_bytes())
+        } else if let Ok(bytes) = ob.downcast::<PyByteArray>() {
+            self.convert_bytes(&bytes.to_vec())
+        } else if is_instance_of(ob, "datetime", "datetime")? {
+            let seconds: f64 = ob.call_method0("timestamp")?.extract()?;
+            let date = unsafe {
+                NewDateObject(
+                    cx.as_ptr(),
+                    ClippedTime {
+                        t: (seconds * 1000.0).round(),
+                    },
+                )
+            };
+            Ok(JSValue::object(cx, &JSObject::from(cx.root(date))))
+        } else if is_instance_of(ob, "decimal", "Decimal")? {
+            let number: f64 = ob.call_method0("__float__")?.extract()?;
+            Ok(JSValue::f64(cx, number))
+        } else if let Ok(dict) = ob.downcast::<PyDict>() {
+            self.with_ancestor(ob, |this| this.convert_mapping(dict.as_mapping()))
+        } else if ob.is_instance_of::<PyList>() || ob.is_instance_of::<PyTuple>() {
+            let sequence = ob.downcast::<PySequence>()?;
+            self.with_ancestor(ob, |this| this.convert_sequence(sequence))
+        } else if let Ok(mapping) = ob.downcast::<PyMapping>() {
+            self.with_ancestor(ob, |this| this.convert_mapping(mapping))
+        } else if is_awaitable(ob)? {
+            let promise = promise_from_awaitable(ob.py(), ob)?;
+            Ok(promise.borrow(ob.py()).0.get()?.as_value(cx))
+        } else if ob.is_callable() {
+            let callable = ob.extract::<PyCallable<'_, VarArgs, PyObject>>()?;
+            let name = callable.name().unwrap_or_default();
+            Ok(callable.to_function(cx, &name, 0).as_value(cx))
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "Cannot convert Python object of type '{}' to a JavaScript value",
+                ob.get_type().name()?
+            )))
+        }
+    }
+
+    /// Unwraps values that already live in the JavaScript runtime.
+    fn convert_wrapper(&self, ob: &Bound<'_, PyAny>) -> PyResult<Option<JSValue<'cx>>> {
+        let cx = self.cx;
+        let value = if let Ok(value) = ob.downcast::<PyJSValue>() {
+            JSValue::from_value(cx, value.borrow().0.get()?, true, ())
+                .to_value_err("Failed to convert to JSValue")?
+        } else if let Ok(object) = ob.downcast::<PyJSObject>() {
+            JSValue::object(cx, object.borrow().0.get()?)
+        } else if let Ok(function) = ob.downcast::<PyJSFunction>() {
+            function.borrow().0.get()?.as_value(cx)
+        } else if let Ok(promise) = ob.downcast::<PyJSPromise>() {
+            promise.borrow().0.get()?.as_value(cx)
+        } else if let Ok(bigint) = ob.downcast::<PyJSBigInt>() {
+            JSValue::bigint(cx, bigint.borrow().0.get()?)
+        } else if let Ok(symbol) = ob.downcast::<PySymbol>() {
+            JSValue::symbol(cx, symbol.borrow().0.get()?)
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(value))
+    }
+
+    fn convert_int(&self, ob: &Bound<'_, PyAny>) -> PyResult<JSValue<'cx>> {
+        let cx = self.cx;
+        match ob.extract::<i64>() {
+            Ok(int) if i32::try_from(int).is_ok() => Ok(JSValue::i32(cx, int as i32)),
+            Ok(int) if int.abs() <= MAX_SAFE_INTEGER => Ok(JSValue::f64(cx, int as f64)),
+            // Integers outside the safe range would silently lose precision as numbers.
+            _ => {
+                let digits = ob.str()?.to_cow()?.into_owned();
+                let bigint = JSBigInt::from_string(cx, &digits)
+                    .to_value_err("Failed to create BigInt from int")?;
+                Ok(JSValue::bigint(cx, &bigint))
+            }
+        }
+    }
+
+    fn convert_bytes(&self, bytes: &[u8]) -> PyResult<JSValue<'cx>> {
+        let cx = self.cx;
+        rooted!(in(cx.as_ptr()) let mut array = ptr::null_mut::<RawJSObject>());
+        unsafe { Uint8Array::create(cx.as_ptr(), CreateWith::Slice(bytes), array.handle_mut()) }
+            .map_err(|_| PyValueError::new_err("Failed to create Uint8Array"))?;
+        Ok(JSValue::object(cx, &JSObject::from(cx.root(array.get()))))
+    }
+
+    fn convert_mapping(&mut self, mapping: &Bound<'_, PyMapping>) -> PyResult<JSValue<'cx>> {
+        let cx = self.cx;
+        let object = JSObject::new(cx);
+        for item in mapping.items()?.iter() {
+            let (key, value): (Bound<'_, PyAny>, Bound<'_, PyAny>) = item.extract()?;
+            let key = if let Ok(key) = key.downcast::<PyString>() {
+                key.to_cow()?.into_owned()
+            } else if key.is_instance_of::<PyInt>() || key.is_instance_of::<PyFloat>() {
+                key.str()?.to_cow()?.into_owned()
+            } else {
+                return Err(PyTypeError::new_err(format!(
+                    "Cannot use Python object of type '{}' as a JavaScript property key",
+                    key.get_type().name()?
+                )));
+            };
+            let value = self.convert(&value)?;
+            if !object.set(cx, key.as_str(), &value) {
+                return Err(PyValueError::new_err(format!("Failed to set property '{}'", key)));
+            }
+        }
+        Ok(JSValue::object(cx, &object))
+    }
+
+    fn convert_sequence(&mut self, sequence: &Bound<'_, PySequence>) -> PyResult<JSValue<'cx>> {
+        let cx = self.cx;
+        let array = JSArray::new(cx);
+        for (index, item) in sequence.try_iter()?.enumerate() {
+            let value = self.convert(&item?)?;
+            if !array.set(cx, index as u32, &value) {
+                return Err(PyValueError::new_err(format!("Failed to set element {}", index)));
+            }
+        }
+        Ok(array.as_value(cx))
+    }
+
+    fn with_ancestor<F>(&mut self, ob: &Bound<'_, PyAny>, f: F) -> PyResult<JSValue<'cx>>
+    where
+        F: FnOnce(&mut Self) -> PyResult<JSValue<'cx>>,
+    {
+        let raw = ob.as_ptr();
+        if self.ancestors.contains(&raw) {
+            return Err(PyValueError::new_err(
+                "Cyclic reference detected while converting Python object",
+            ));
+        }
+        if self.ancestors.len() >= self.max_depth {
+            return Err(PyValueError::new_err(format!(
+                "Maximum conversion depth of {} exceeded",
+                self.max_depth
+            )));
+        }
+
+        self.ancestors.push(raw);
+        let result = f(self);
+        self.ancestors.pop();
+        result
+    }
+}
+
+/// Checks whether `ob` is an instance of `module.name` without importing unused modules.
+fn is_instance_of(ob: &Bound<'_, PyAny>, module: &str, name: &str) -> PyResult<bool> {
+    let py = ob.py();
+    let modules = py.import("sys")?.getattr("modules")?;
+    match modules.get_item(module) {
+        Ok(module) => ob.is_instance(&module.getattr(name)?),
+        // A type from a module that was never imported cannot have instances.
+        Err(_) => Ok(false),
+    }
+}
+
+/// Checks whether an object can be awaited, like `inspect.isawaitable`.
+fn is_awaitable(ob: &Bound<'_, PyAny>) -> PyResult<bool> {
+    ob.py()
+        .import("inspect")?
+        .call_method1("isawaitable", (ob,))?
+        .is_truthy()
+}
@@ -1,5 +1,81 @@
+use crate::traits::ExtendLifetime;
+use crate::types::diagnostic::PyTypeScriptDiagnostic;
+use crate::types::sourcemap::original_position;
+use crate::types::stack_frame::PyStackFrame;
+use crate::types::value::PyJSValue;
+use crate::typescript::format_diagnostics;
+use ion::format::{Config, format_value};
+use ion::module::{ModuleError, ModuleErrorKind};
+use ion::stack::Location;
+use ion::{Context, Error, ErrorKind, ErrorReport, Exception, Object, Value};
+use js_runtime::cache::map::find_sourcemap;
+use pyo3::create_exception;
 use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
+use sourcemap::SourceMap;
+use std::collections::HashMap;
+use std::path::Path;
+
+create_exception!(
+    js_exec.js_exec,
+    JSError,
+    PyRuntimeError,
+    "Raised when JavaScript code throws an error or fails to compile."
+);
+create_exception!(
+    js_exec.js_exec,
+    JSSyntaxError,
+    JSError,
+    "Raised for a JavaScript `SyntaxError`."
+);
+create_exception!(
+    js_exec.js_exec,
+    JSTypeError,
+    JSError,
+    "Raised for a JavaScript `TypeError`."
+);
+create_exception!(
+    js_exec.js_exec,
+    JSRangeError,
+    JSError,
+    "Raised for a JavaScript `RangeError`."
+);
+create_exception!(
+    js_exec.js_exec,
+    JSReferenceError,
+    JSError,
+    "Raised for a JavaScript `ReferenceError`."
+);
+create_exception!(
+    js_exec.js_exec,
+    JSCompileError,
+    JSError,
+    "Raised when a module fails to compile or link."
+);
+create_exception!(
+    js_exec.js_exec,
+    TypeScriptError,
+    JSCompileError,
+    "Raised when TypeScript source fails to compile."
+);
+create_exception!(
+    js_exec.js_exec,
+    JSTimeoutError,
+    JSError,
+    "Raised when JavaScript code is interrupted because it exceeded its time limit."
+);
+create_exception!(
+    js_exec.js_exec,
+    JSRuntimeClosedError,
+    PyRuntimeError,
+    "Raised when a runtime, or a value created by it, is used after the runtime was closed."
+);
+create_exception!(
+    js_exec.js_exec,
+    JSThrownValue,
+    JSError,
+    "Raised when JavaScript code throws a value which is not an `Error`, e.g. `throw 42`."
+);
 
 /// Creates a PyValueError with a formatted message
 ///
@@ -19,6 +95,245 @@ pub fn runtime_error<E: std::fmt::Debug>(msg: &str, error: E) -> PyErr {
     PyRuntimeError::new_err(format!("{}: {:?}", msg, error))
 }
 
+/// Creates the [JSError] subclass matching an [ErrorReport].
+///
+/// The exception message is the formatted report, and the thrown value, message,
+/// location and stack frames are available as the `value`, `message`, `filename`,
+/// `line`, `column` and `stack` attributes.
+///
+/// Locations in files with a registered source map are rewritten to their original positions,
+/// and the positions in the executed code are kept in the `generated_filename`,
+/// `generated_line` and `generated_column` attributes.
+///
+/// # Arguments
+/// * `cx` - The context the error was thrown in
+/// * `report` - The report of the thrown exception
+/// * `compiling` - Whether the error was raised while compiling or linking a module
+pub fn js_error(cx: &Context, mut report: ErrorReport, compiling: bool) -> PyErr {
+    let generated_location = exception_location(&report);
+    let generated_stack: Vec<Location> = report
+        .stack
+        .as_ref()
+        .map(|stack| {
+            stack
+                .records
+                .iter()
+                .map(|record| record.location.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    remap_report(&mut report);
+    let formatted = report.format(cx);
+
+    let (error, message, value) = match &report.exception {
+        Exception::Error(error) => {
+            let py_err = match error.kind {
+                ErrorKind::Syntax => JSSyntaxError::new_err(formatted),
+                ErrorKind::Type => JSTypeError::new_err(formatted),
+                ErrorKind::Range => JSRangeError::new_err(formatted),
+                ErrorKind::Reference => JSReferenceError::new_err(formatted),
+                ErrorKind::Compile => JSCompileError::new_err(formatted),
+                _ if compiling => JSCompileError::new_err(formatted),
+                _ => JSError::new_err(formatted),
+            };
+            let value = error
+                .object
+                .map(|object| Value::object(cx, &Object::from(cx.root(object))));
+            (py_err, error.message.clone(), value)
+        }
+        Exception::Other(value) => {
+            let value = Value::from(cx.root(*value));
+            let message = format_value(cx, Config::default(), &value).to_string();
+            (JSThrownValue::new_err(formatted), message, Some(value))
+        }
+    };
+
+    let stack: Vec<PyStackFrame> = report
+        .stack
+        .as_ref()
+        .map(|stack| {
+            let records = stack.records.iter().zip(&generated_stack);
+            records
+                .map(|(record, generated)| PyStackFrame::new(record, generated))
+                .collect()
+        })
+        .unwrap_or_default();
+    let (filename, line, column) = split_location(exception_location(&report));
+    let (generated_filename, generated_line, generated_column) = split_location(generated_location);
+    let attributes = ErrorAttributes {
+        value: value.map(|value| PyJSValue::from(value.extend_lifetime())),
+        message,
+        filename,
+        line,
+        column,
+        generated_filename,
+        generated_line,
+        generated_column,
+        stack,
+    };
+
+    Python::with_gil(|py| match attributes.set(py, &error) {
+        Ok(()) => error,
+        Err(err) => err,
+    })
+}
+
+/// Attributes of a [JSError] which describe the error, set on its instance.
+struct ErrorAttributes {
+    value: Option<PyJSValue>,
+    message: String,
+    filename: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    generated_filename: Option<String>,
+    generated_line: Option<u32>,
+    generated_column: Option<u32>,
+    stack: Vec<PyStackFrame>,
+}
+
+impl ErrorAttributes {
+    /// Sets the attributes on the instance of `error`.
+    fn set(self, py: Python<'_>, error: &PyErr) -> PyResult<()> {
+        let instance = error.value(py);
+        instance.setattr("value", self.value)?;
+        instance.setattr("message", self.message)?;
+        instance.setattr("filename", self.filename)?;
+        instance.setattr("line", self.line)?;
+        instance.setattr("column", self.column)?;
+        instance.setattr("generated_filename", self.generated_filename)?;
+        instance.setattr("generated_line", self.generated_line)?;
+        instance.setattr("generated_column", self.generated_column)?;
+        instance.setattr("stack", self.stack)
+    }
+}
+
+/// Returns the location of the exception of a report.
+/// Thrown values carry no location of their own, so this falls back to the innermost frame.
+fn exception_location(report: &ErrorReport) -> Option<Location> {
+    let location = match &report.exception {
+        Exception::Error(error) => error.location.clone(),
+        Exception::Other(_) => None,
+    };
+    location.or_else(|| {
+        let stack = report.stack.as_ref()?;
+        stack.records.first().map(|record| record.location.clone())
+    })
+}
+
+/// Splits a location into the values of the `filename`, `line` and `column` attributes.
+fn split_location(location: Option<Location>) -> (Option<String>, Option<u32>, Option<u32>) {
+    match location {
+        Some(location) => (
+            Some(location.file),
+            Some(location.lineno),
+            Some(location.column),
+        ),
+        None => (None, None, None),
+    }
+}
+
+/// Rewrites the locations of a report to their original positions, using the source maps
+/// registered for their files, such as those of TypeScript modules.
+fn remap_report(report: &mut ErrorReport) {
+    let mut sourcemaps: HashMap<String, Option<SourceMap>> = HashMap::new();
+    let mut remap = |location: &mut Location| {
+        let sourcemap = sourcemaps
+            .entry(location.file.clone())
+            .or_insert_with(|| find_sourcemap(Path::new(&location.file)));
+        let position = sourcemap
+            .as_ref()
+            .and_then(|sourcemap| original_position(sourcemap, location.lineno, location.column));
+        if let Some(position) = position {
+            location.file = position.source;
+            location.lineno = position.line;
+            location.column = position.column;
+        }
+    };
+
+    if let Exception::Error(Error {
+        location: Some(location),
+        ..
+    }) = &mut report.exception
+    {
+        remap(location);
+    }
+    if let Some(stack) = &mut report.stack {
+        for record in &mut stack.records {
+            remap(&mut record.location);
+        }
+    }
+}
+
+/// Creates a [TypeScriptError] for the diagnostics of a failed compilation.
+///
+/// Every diagnostic is listed in the exception message, and they are available as the
+/// `diagnostics` attribute. The location attributes are those of the first diagnostic.
+pub fn typescript_error(diagnostics: Vec<PyTypeScriptDiagnostic>) -> PyErr {
+    let error = TypeScriptError::new_err(format_diagnostics(&diagnostics));
+    let (message, filename, line, column) = match diagnostics.first() {
+        Some(first) => (
+            first.message.clone(),
+            Some(first.filename.clone()),
+            Some(first.line),
+            Some(first.column),
+        ),
+        None => (String::new(), None, None, None),
+    };
+    // No JavaScript was generated, so there is no generated position.
+    let attributes = ErrorAttributes {
+        value: None,
+        message,
+        filename,
+        line,
+        column,
+        generated_filename: None,
+        generated_line: None,
+        generated_column: None,
+        stack: Vec::new(),
+    };
+
+    Python::with_gil(|py| {
+        let result = attributes
+            .set(py, &error)
+            .and_then(|()| error.value(py).setattr("diagnostics", diagnostics));
+        match result {
+            Ok(()) => error,
+            Err(err) => err,
+        }
+    })
+}
+
+/// Creates the [JSError] subclass for the reason of a rejected promise.
+pub fn rejection_error(cx: &Context, reason: &Value) -> PyErr {
+    let exception = Exception::from_value(cx, reason);
+    let report = ErrorReport::from_exception_with_error_stack(cx, exception);
+    js_error(cx, report, false)
+}
+
+/// Creates a [JSError] for an exception which was not reported, such as an uncatchable
+/// exception terminating the script.
+pub fn uncatchable_error() -> PyErr {
+    JSError::new_err("Uncatchable exception was thrown")
+}
+
+/// Converts a Python exception raised inside a callback into a JavaScript [Exception],
+/// so that it can be caught by the calling JavaScript code.
+///
+/// [JSError]s which carry the originally thrown value rethrow that value unchanged.
+pub fn to_js_exception(error: PyErr) -> Exception {
+    Python::with_gil(|py| {
+        if error.is_instance_of::<JSError>(py) {
+            let value = error.value(py).getattr("value").ok();
+            if let Some(value) = value.as_ref().and_then(|v| v.downcast::<PyJSValue>().ok()) {
+                if let Ok(value) = value.borrow().0.get() {
+                    return Exception::Other(value.get());
+                }
+            }
+        }
+        Exception::Error(Error::new(error.to_string(), Some(ErrorKind::Normal)))
+    })
+}
+
 // Extension trait for Results to make error handling more fluent
 pub trait ErrorHandling<T, E> {
     /// Converts the error to a PyValueError with the specified message
@@ -37,3 +352,36 @@ impl<T, E: std::fmt::Debug> ErrorHandling<T, E> for Result<T, E> {
         self.map_err(|e| runtime_error(msg, e))
     }
 }
+
+// Extension trait for Results of JavaScript operations
+pub trait JSErrorHandling<T> {
+    /// Converts the error to the matching [JSError] subclass
+    fn to_js_err(self, cx: &Context) -> PyResult<T>;
+}
+
+impl<T> JSErrorHandling<T> for Result<T, ErrorReport> {
+    fn to_js_err(self, cx: &Context) -> PyResult<T> {
+        self.map_err(|report| js_error(cx, report, false))
+    }
+}
+
+impl<T> JSErrorHandling<T> for Result<T, Option<ErrorReport>> {
+    fn to_js_err(self, cx: &Context) -> PyResult<T> {
+        self.map_err(|report| match report {
+            Some(report) => js_error(cx, report, false),
+            None => uncatchable_error(),
+        })
+    }
+}
+
+impl<T> JSErrorHandling<T> for Result<T, ModuleError> {
+    fn to_js_err(self, cx: &Context) -> PyResult<T> {
+        self.map_err(|error| {
+            let compiling = matches!(
+                error.kind,
+                ModuleErrorKind::Compilation | ModuleErrorKind::Instantiation
+            );
+            js_error(cx, error.report, compiling)
+        })
+    }
+}
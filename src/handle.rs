@@ -0,0 +1,101 @@
+use crate::errors::JSRuntimeClosedError;
+use crate::runtime::{RootId, current_generation, register_root, unregister_root, with_js_cx};
+use crate::traits::{HeapRoot, Rootable};
+use pyo3::prelude::*;
+use std::hash::{Hash, Hasher};
+use std::mem::ManuallyDrop;
+
+/// A JavaScript thing held by a Python wrapper, tied to the runtime that created it.
+///
+/// Each runtime has a generation, which is unique within the process. A [Handle] remembers the
+/// generation of the runtime it was created in, and refuses access once that runtime has been
+/// closed, even if another runtime has been created since.
+///
+/// The GC thing of the value is copied into a [HeapRoot] owned by the [Handle], which is
+/// registered as a persistent root while the [Handle] exists, so it is neither collected nor
+/// left dangling when the GC moves it.
+#[derive(Debug)]
+pub struct Handle<T: Rootable> {
+    value: T,
+    generation: u64,
+    root: Option<(RootId, HeapRoot)>,
+}
+
+impl<T: Rootable> Handle<T> {
+    /// Wraps a value of the runtime of the current thread.
+    pub fn new(mut value: T) -> Handle<T> {
+        let root = value.root().map(|heap| (register_root(heap.slot()), heap));
+        Handle {
+            value,
+            generation: current_generation(),
+            root,
+        }
+    }
+
+    /// Returns the value if the runtime which created it is still open.
+    ///
+    /// # Errors
+    /// Raises [JSRuntimeClosedError] if the runtime has been closed.
+    pub fn get(&self) -> PyResult<&T> {
+        if self.is_alive() {
+            Ok(&self.value)
+        } else {
+            Err(closed_error())
+        }
+    }
+
+    /// Unwraps the value if the runtime which created it is still open.
+    /// The value is rooted in the context rather than as a persistent root.
+    ///
+    /// # Errors
+    /// Raises [JSRuntimeClosedError] if the runtime has been closed.
+    pub fn into_inner(self) -> PyResult<T> {
+        self.get()?;
+        let mut handle = ManuallyDrop::new(self);
+        if let Some((root, heap)) = handle.root.take() {
+            with_js_cx(|cx| handle.value.reroot(cx));
+            unregister_root(root);
+            drop(heap);
+        }
+        // SAFETY: The handle is never dropped, so the value is only moved out once.
+        Ok(unsafe { std::ptr::read(&handle.value) })
+    }
+
+    /// Checks if the runtime which created the value is still open.
+    pub fn is_alive(&self) -> bool {
+        self.generation != 0 && self.generation == current_generation()
+    }
+}
+
+impl<T: Rootable> Drop for Handle<T> {
+    fn drop(&mut self) {
+        if let Some((root, heap)) = self.root.take() {
+            unregister_root(root);
+            if !self.is_alive() {
+                // Dropping a heap runs write barriers of the GC, which is gone along with the
+                // runtime, so the heap is leaked instead.
+                std::mem::forget(heap);
+            }
+        }
+    }
+}
+
+impl<T: Rootable + PartialEq> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.generation == other.generation && self.value == other.value
+    }
+}
+
+impl<T: Rootable + Eq> Eq for Handle<T> {}
+
+impl<T: Rootable + Hash> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.generation.hash(state);
+        self.value.hash(state);
+    }
+}
+
+/// Creates the error raised when a value of a closed runtime is used.
+pub fn closed_error() -> PyErr {
+    JSRuntimeClosedError::new_err("The runtime which created this value has been closed")
+}
@@ -0,0 +1,178 @@
+use crate::errors::JSTimeoutError;
+use mozjs::jsapi::{JS_AddInterruptCallback, JS_RequestInterruptCallback, JSContext};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+thread_local! {
+    /// Deadline of the innermost call with a timeout running on this thread.
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    /// Set by the interrupt callback when it terminates execution because of the deadline.
+    static TIMED_OUT: Cell<bool> = const { Cell::new(false) };
+    /// Watchdog of the runtime of this thread, which requests interrupts at deadlines.
+    static WATCHDOG: RefCell<Option<Watchdog>> = const { RefCell::new(None) };
+}
+
+/// Installs the interrupt callback which enforces timeouts on the given context, and starts the
+/// watchdog thread which requests interrupts of it.
+///
+/// # Safety
+/// `cx` must be a valid context of the current thread, and [stop_watchdog] must be called
+/// before it is destroyed.
+pub unsafe fn install_interrupt_callback(cx: *mut JSContext) {
+    unsafe { JS_AddInterruptCallback(cx, Some(interrupt_callback)) };
+    let watchdog = Watchdog::start(InterruptHandle(cx));
+    WATCHDOG.with(|cell| cell.replace(Some(watchdog)));
+}
+
+/// Stops the watchdog thread of the runtime of this thread, waiting for it to exit.
+pub fn stop_watchdog() {
+    let watchdog = WATCHDOG.with(|cell| cell.take());
+    drop(watchdog);
+}
+
+/// Terminates execution with an uncatchable exception once the deadline has passed.
+unsafe extern "C" fn interrupt_callback(_: *mut JSContext) -> bool {
+    let expired = DEADLINE.get().is_some_and(|deadline| Instant::now() >= deadline);
+    if expired {
+        TIMED_OUT.set(true);
+    }
+    !expired
+}
+
+/// Pointer to a context, which is only used to request an interrupt from the watchdog thread.
+struct InterruptHandle(*mut JSContext);
+
+// SAFETY: `JS_RequestInterruptCallback` is the only function called with the pointer,
+// and it is designed to be called from any thread.
+unsafe impl Send for InterruptHandle {}
+
+/// State shared between a runtime and its watchdog thread.
+#[derive(Default)]
+struct WatchdogState {
+    /// Deadline at which an interrupt is requested, or [None] while the watchdog is disarmed.
+    deadline: Option<Instant>,
+    /// Set when the runtime is closed, which makes the watchdog thread exit.
+    stopped: bool,
+}
+
+/// Long-lived thread which requests an interrupt of a runtime once the armed deadline passes.
+struct Watchdog {
+    state: Arc<(Mutex<WatchdogState>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    fn start(handle: InterruptHandle) -> Watchdog {
+        let state = Arc::new((Mutex::new(WatchdogState::default()), Condvar::new()));
+        let shared = Arc::clone(&state);
+        let thread = thread::spawn(move || {
+            // Moves the whole handle, as the raw pointer inside is not `Send` by itself.
+            let handle = handle;
+            let (lock, armed) = &*shared;
+            let mut state = lock.lock().unwrap();
+            while !state.stopped {
+                state = match state.deadline {
+                    None => armed.wait(state).unwrap(),
+                    Some(deadline) => {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            state.deadline = None;
+                            unsafe { JS_RequestInterruptCallback(handle.0) };
+                            state
+                        } else {
+                            armed.wait_timeout(state, deadline - now).unwrap().0
+                        }
+                    }
+                };
+            }
+        });
+        Watchdog {
+            state,
+            thread: Some(thread),
+        }
+    }
+
+    /// Replaces the deadline of the watchdog, disarming it if `deadline` is [None].
+    fn arm(&self, deadline: Option<Instant>) {
+        let (lock, armed) = &*self.state;
+        lock.lock().unwrap().deadline = deadline;
+        armed.notify_one();
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        let (lock, armed) = &*self.state;
+        lock.lock().unwrap().stopped = true;
+        armed.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Arms the watchdog of the runtime of this thread with a deadline.
+fn arm_watchdog(deadline: Option<Instant>) {
+    WATCHDOG.with(|cell| {
+        if let Some(watchdog) = cell.borrow().as_ref() {
+            watchdog.arm(deadline);
+        }
+    });
+}
+
+/// Converts a timeout in seconds into a [Duration].
+pub fn parse_timeout(timeout: Option<f64>) -> PyResult<Option<Duration>> {
+    timeout
+        .map(|timeout| {
+            Duration::try_from_secs_f64(timeout).map_err(|_| {
+                PyValueError::new_err("timeout must be a non-negative number of seconds")
+            })
+        })
+        .transpose()
+}
+
+/// Runs `f`, interrupting any JavaScript code it executes on `cx` once `timeout` has elapsed.
+///
+/// The watchdog thread of the runtime is armed with the deadline and requests an interrupt once
+/// it passes, and the interrupt callback
+/// terminates execution. The error returned by `f` is then replaced with a [JSTimeoutError].
+/// Nested calls are bounded by the earliest deadline, and the runtime stays usable afterwards.
+pub fn with_timeout<F, R>(cx: *mut JSContext, timeout: Option<Duration>, f: F) -> PyResult<R>
+where
+    F: FnOnce() -> PyResult<R>,
+{
+    let Some(timeout) = timeout else {
+        return f();
+    };
+
+    let outer = DEADLINE.get();
+    let deadline = Instant::now() + timeout;
+    let deadline = outer.map_or(deadline, |outer| outer.min(deadline));
+    DEADLINE.set(Some(deadline));
+    arm_watchdog(Some(deadline));
+
+    let result = f();
+
+    arm_watchdog(outer);
+    DEADLINE.set(outer);
+
+    let timed_out = TIMED_OUT.replace(false);
+    if outer.is_some_and(|outer| Instant::now() >= outer) {
+        // The enclosing call has timed out as well, so it is terminated as soon as it resumes
+        // executing JavaScript code, and reports a timeout even if this error is caught.
+        TIMED_OUT.set(true);
+        unsafe { JS_RequestInterruptCallback(cx) };
+    }
+
+    match result {
+        Err(_) if timed_out => Err(JSTimeoutError::new_err(format!(
+            "Execution timed out after {:.3}s",
+            timeout.as_secs_f64()
+        ))),
+        result => result,
+    }
+}
@@ -1,8 +1,16 @@
+mod awaitables;
+mod capabilities;
+mod conversions;
 mod errors;
+mod handle;
+mod interrupt;
 mod loader;
+mod pending_work;
+mod pool;
 pub mod runtime;
 mod traits;
 mod types;
+mod typescript;
 
 use crate::runtime::with_js_cx;
 use pyo3::prelude::*;
@@ -21,7 +29,7 @@ use pyo3_stub_gen::{define_stub_info_gatherer, module_variable};
 /// ## Features
 ///
 /// - Execute JavaScript code directly from Python
-/// - TypeScript support out of the box (not fully implemented yet)
+/// - TypeScript and JSX support out of the box, with source-mapped errors
 /// - Full access to JavaScript's type system including primitives and objects
 /// - Support for JavaScript modules
 /// - Safe handling of JavaScript values in Python
@@ -60,12 +68,14 @@ use pyo3_stub_gen::{define_stub_info_gatherer, module_variable};
 /// The JavaScript runtime is thread-local. Each thread must create its own Runtime
 /// instance, and only one Runtime can exist per thread.
 #[pymodule]
-fn js_exec(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+fn js_exec(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Register functions
     m.add_function(wrap_pyfunction!(r#typeof, m)?)?;
 
     // Register classes
     m.add_class::<runtime::PythonJSRuntime>()?;
+    m.add_class::<runtime::PyEventLoopStatus>()?;
+    m.add_class::<pool::PyRuntimePool>()?;
     m.add_class::<types::value::PyJSValue>()?;
     m.add_class::<types::object::PyJSObject>()?;
     m.add_class::<types::context::PyJSContext>()?;
@@ -81,12 +91,27 @@ fn js_exec(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<types::function::PyJSFunction>()?;
     m.add_class::<types::property_key::PyPropertyKey>()?;
     m.add_class::<types::sourcemap::PySourceMap>()?;
+    m.add_class::<types::sourcemap::PyOriginalPosition>()?;
     m.add_class::<types::module::PyJSModule>()?;
     m.add_class::<types::promise::PyJSPromise>()?;
     m.add_class::<types::promise::PyJSPromiseState>()?;
+    m.add_class::<conversions::PyConversionFallback>()?;
+    m.add_class::<types::stack_frame::PyStackFrame>()?;
+    m.add_class::<types::heap_stats::PyHeapStats>()?;
+    m.add_class::<types::realm::PyRealm>()?;
+    m.add_class::<types::diagnostic::PyTypeScriptDiagnostic>()?;
 
     // Register custom error types
-    // m.add("JSRuntimeError", py.get_type::<errors::JSRuntimeError>())?;
+    m.add("JSError", py.get_type::<errors::JSError>())?;
+    m.add("JSSyntaxError", py.get_type::<errors::JSSyntaxError>())?;
+    m.add("JSTypeError", py.get_type::<errors::JSTypeError>())?;
+    m.add("JSRangeError", py.get_type::<errors::JSRangeError>())?;
+    m.add("JSReferenceError", py.get_type::<errors::JSReferenceError>())?;
+    m.add("JSCompileError", py.get_type::<errors::JSCompileError>())?;
+    m.add("JSThrownValue", py.get_type::<errors::JSThrownValue>())?;
+    m.add("JSTimeoutError", py.get_type::<errors::JSTimeoutError>())?;
+    m.add("JSRuntimeClosedError", py.get_type::<errors::JSRuntimeClosedError>())?;
+    m.add("TypeScriptError", py.get_type::<errors::TypeScriptError>())?;
 
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
     Ok(())
@@ -95,24 +120,26 @@ fn js_exec(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
 /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Operators/typeof#description
 #[gen_stub_pyfunction(module = "js_exec.js_exec")]
 #[pyfunction(name = "typeof")]
-pub fn r#typeof(value: &types::value::PyJSValue) -> String {
-    with_js_cx(|cx| {
-        if value.is_undefined() {
+pub fn r#typeof(value: &types::value::PyJSValue) -> PyResult<String> {
+    let value = value.0.get()?;
+    let handle = value.handle();
+    Ok(with_js_cx(|cx| {
+        if handle.is_undefined() {
             "undefined".to_string()
-        } else if value.is_null() {
+        } else if handle.is_null() {
             "object".to_string()
-        } else if value.is_boolean() {
+        } else if handle.is_boolean() {
             "boolean".to_string()
-        } else if value.is_number() {
+        } else if handle.is_number() {
             "number".to_string()
-        } else if value.is_bigint() {
+        } else if handle.is_bigint() {
             "bigint".to_string()
-        } else if value.is_string() {
+        } else if handle.is_string() {
             "string".to_string()
-        } else if value.is_symbol() {
+        } else if handle.is_symbol() {
             "symbol".to_string()
-        } else if value.is_object() {
-            let object = value.0.to_object(cx);
+        } else if handle.is_object() {
+            let object = value.to_object(cx);
             let class = object.get_builtin_class(cx);
             match class {
                 mozjs::jsapi::ESClass::Function => "function".to_string(),
@@ -121,7 +148,7 @@ pub fn r#typeof(value: &types::value::PyJSValue) -> String {
         } else {
             "object".to_string()
         }
-    })
+    }))
 }
 
 module_variable!("js_exec.js_exec", "__version__", String);
@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::loader::normalize;
+
+/// Maps specifiers to addresses, ordered from the most to the least specific specifier.
+type SpecifierMap = Vec<(String, String)>;
+
+/// An [import map](https://html.spec.whatwg.org/multipage/webappapis.html#import-maps) used to
+/// remap module specifiers before they are resolved by the [Loader](super::Loader).
+///
+/// Addresses and path-like specifiers are file paths rather than URLs: `/` denotes the root of
+/// the filesystem, `./` and `../` are relative to the base directory of the import map and
+/// `file://` URLs are converted into paths.
+#[derive(Clone, Debug, Default)]
+pub struct ImportMap {
+    base: PathBuf,
+    imports: SpecifierMap,
+    scopes: Vec<(String, SpecifierMap)>,
+}
+
+impl ImportMap {
+    /// Parses an import map from its JSON representation.
+    /// Relative specifiers, scopes and addresses are resolved against `base`.
+    pub fn from_json(json: &str, base: &Path) -> Result<ImportMap, String> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| format!("Invalid import map JSON: {e}"))?;
+        let Value::Object(map) = value else {
+            return Err(String::from("Import map must be a JSON object"));
+        };
+
+        let imports = match map.get("imports") {
+            Some(Value::Object(imports)) => parse_specifier_map(imports, base)?,
+            Some(_) => return Err(String::from("Import map \"imports\" must be an object")),
+            None => Vec::new(),
+        };
+
+        let mut scopes = match map.get("scopes") {
+            Some(Value::Object(scopes)) => scopes
+                .iter()
+                .map(|(scope, imports)| match imports {
+                    Value::Object(imports) => {
+                        let scope = normalize_address(scope, base)
+                            .ok_or_else(|| format!("Invalid scope \"{scope}\""))?;
+                        Ok((scope, parse_specifier_map(imports, base)?))
+                    }
+                    _ => Err(format!("Imports of scope \"{scope}\" must be an object")),
+                })
+                .collect::<Result<Vec<_>, String>>()?,
+            Some(_) => return Err(String::from("Import map \"scopes\" must be an object")),
+            None => Vec::new(),
+        };
+        scopes.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+
+        Ok(ImportMap {
+            base: base.to_path_buf(),
+            imports,
+            scopes,
+        })
+    }
+
+    /// Resolves a specifier imported by the module at `referrer` through the import map.
+    /// Returns [None] if no entry of the import map applies to the specifier.
+    pub fn resolve(&self, specifier: &str, referrer: Option<&Path>) -> Option<PathBuf> {
+        if self.imports.is_empty() && self.scopes.is_empty() {
+            return None;
+        }
+
+        let referrer = referrer.map(|referrer| normalize(&self.base.join(referrer)));
+        let normalized = if is_path_like(specifier) {
+            let parent = referrer
+                .as_deref()
+                .and_then(Path::parent)
+                .unwrap_or(&self.base);
+            normalize_address(specifier, parent)?
+        } else {
+            specifier.to_owned()
+        };
+
+        if let Some(referrer) = referrer.as_ref().map(|r| r.to_string_lossy()) {
+            let scoped = self
+                .scopes
+                .iter()
+                .filter(|(scope, _)| {
+                    *scope == referrer
+                        || (scope.ends_with('/') && referrer.starts_with(scope.as_str()))
+                })
+                .find_map(|(_, imports)| resolve_imports(&normalized, imports));
+            if scoped.is_some() {
+                return scoped;
+            }
+        }
+        resolve_imports(&normalized, &self.imports)
+    }
+}
+
+fn parse_specifier_map(map: &Map<String, Value>, base: &Path) -> Result<SpecifierMap, String> {
+    let mut imports = map
+        .iter()
+        .map(|(specifier, address)| {
+            if specifier.is_empty() {
+                return Err(String::from("Import map specifiers must not be empty"));
+            }
+            let specifier = if is_path_like(specifier) {
+                normalize_address(specifier, base)
+                    .ok_or_else(|| format!("Invalid specifier \"{specifier}\""))?
+            } else {
+                specifier.clone()
+            };
+
+            let address = address
+                .as_str()
+                .and_then(|address| normalize_address(address, base))
+                .ok_or_else(|| format!("Invalid address for \"{specifier}\": {address}"))?;
+            if specifier.ends_with('/') && !address.ends_with('/') {
+                return Err(format!(
+                    "Address \"{address}\" for \"{specifier}\" must end with a trailing slash"
+                ));
+            }
+            Ok((specifier, address))
+        })
+        .collect::<Result<SpecifierMap, String>>()?;
+    imports.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+    Ok(imports)
+}
+
+fn resolve_imports(specifier: &str, imports: &SpecifierMap) -> Option<PathBuf> {
+    imports.iter().find_map(|(key, address)| {
+        if key == specifier {
+            Some(PathBuf::from(address))
+        } else if key.ends_with('/') {
+            let rest = specifier.strip_prefix(key.as_str())?;
+            let resolved = normalize(&Path::new(address).join(rest));
+            // Prefix mappings must not escape the mapped directory.
+            resolved.starts_with(address).then_some(resolved)
+        } else {
+            None
+        }
+    })
+}
+
+fn is_path_like(specifier: &str) -> bool {
+    specifier.starts_with("./")
+        || specifier.starts_with("../")
+        || specifier.starts_with("file://")
+        || Path::new(specifier).is_absolute()
+        || specifier.starts_with('/')
+}
+
+/// Converts a path-like address into a normalised path, keeping its trailing slash.
+fn normalize_address(address: &str, base: &Path) -> Option<String> {
+    if !is_path_like(address) {
+        return None;
+    }
+
+    let path = if address.starts_with("file://") {
+        Url::parse(address).ok()?.to_file_path().ok()?
+    } else {
+        base.join(address)
+    };
+    let mut normalized = normalize(&path).to_string_lossy().into_owned();
+    if address.ends_with('/') && !normalized.ends_with('/') {
+        normalized.push('/');
+    }
+    Some(normalized)
+}
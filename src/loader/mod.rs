@@ -0,0 +1,404 @@
+pub mod import_map;
+mod node;
+pub mod vfs;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::ffi::OsStr;
+use std::fs::read_to_string;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use dunce::canonicalize;
+use ion::module::{Module, ModuleData, ModuleLoader, ModuleRequest, ModuleType};
+use ion::{Context, Error, ErrorKind, ErrorReport, Object, Result, Value};
+use js_runtime::config::Config;
+use mozjs::jsapi::{GetModuleEnvironment, Heap, JSObject};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use url::Url;
+
+use crate::capabilities::Capabilities;
+use crate::conversions::{DEFAULT_MAX_DEPTH, PyToJS};
+use crate::errors::JSErrorHandling;
+use crate::runtime::register_root;
+use crate::traits::RootSlot;
+
+use crate::loader::import_map::ImportMap;
+use crate::loader::vfs::VirtualFileSystem;
+use crate::typescript::{ModuleFormat, TypeScriptOptions, compile_module};
+
+#[derive(Debug, Eq, Hash, PartialEq, Clone)]
+pub enum Specifier {
+    Path(PathBuf),
+    NodeModule(String),
+}
+
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct ModuleKey {
+    pub(crate) specifier: Specifier,
+    pub(crate) kind: ModuleType,
+}
+
+/// Modules loaded by the [Loader] or defined by the runtime, keyed by their specifier and type.
+///
+/// Modules are held as persistent roots, so the GC keeps them alive and updates them when they
+/// are moved. Clones share the same modules.
+#[derive(Clone, Default)]
+pub struct ModuleMap(Rc<RefCell<HashMap<ModuleKey, Box<Heap<*mut JSObject>>>>>);
+
+impl ModuleMap {
+    /// Returns the module registered under the key.
+    pub fn get<'cx>(&self, cx: &'cx Context, key: &ModuleKey) -> Option<Module<'cx>> {
+        let modules = self.0.borrow();
+        let module = modules.get(key)?;
+        Some(Module(Object::from(cx.root(module.get()))))
+    }
+
+    /// Returns `true` if a module is registered under the key.
+    pub fn contains(&self, key: &ModuleKey) -> bool {
+        self.0.borrow().contains_key(key)
+    }
+
+    /// Registers a module under the key.
+    /// Returns `false` if a module is already registered under it.
+    pub fn insert(&self, key: ModuleKey, module: *mut JSObject) -> bool {
+        match self.0.borrow_mut().entry(key) {
+            Entry::Vacant(entry) => {
+                let module = entry.insert(Heap::boxed(module));
+                register_root(RootSlot::Object(module.get_unsafe()));
+                true
+            }
+            Entry::Occupied(_) => false,
+        }
+    }
+
+    /// Defines a synthetic module at the given normalised path, whose named exports are the
+    /// items of `exports`. The values are converted once, when the module is defined.
+    /// Returns `false` if a module is already registered at the path.
+    pub fn define_synthetic(
+        &self,
+        cx: &Context,
+        path: &Path,
+        exports: &Bound<'_, PyDict>,
+    ) -> PyResult<bool> {
+        let key = ModuleKey {
+            specifier: Specifier::Path(path.to_path_buf()),
+            kind: ModuleType::JavaScript,
+        };
+        if self.contains(&key) {
+            return Ok(false);
+        }
+
+        let names = exports
+            .keys()
+            .iter()
+            .map(|name| name.extract::<String>())
+            .collect::<PyResult<Vec<_>>>()?;
+        let name = path.to_string_lossy();
+        let (module, _) =
+            Module::compile_and_evaluate(cx, &name, None, &synthetic_module_source(&names))
+                .to_js_err(cx)?;
+
+        // The bindings of the module are initialised by its evaluation, and can then be set
+        // through its environment.
+        let environment = unsafe { GetModuleEnvironment(cx.as_ptr(), module.0.handle().into()) };
+        let environment = Object::from(cx.root(environment));
+        let mut converter = PyToJS::new(cx, DEFAULT_MAX_DEPTH);
+        for (index, value) in exports.values().iter().enumerate() {
+            let value = converter.convert(&value)?;
+            if !environment.set(cx, &format!("export{index}"), &value) {
+                return Err(ErrorReport::new_with_exception_stack(cx)).to_js_err(cx);
+            }
+        }
+        Ok(self.insert(key, module.0.handle().get()))
+    }
+}
+
+#[derive(Default)]
+pub struct Loader {
+    modules: ModuleMap,
+    import_map: ImportMap,
+    virtual_modules: VirtualFileSystem,
+    capabilities: Capabilities,
+    typescript: TypeScriptOptions,
+}
+
+impl Loader {
+    /// Creates a [Loader] which registers loaded modules in `modules`, remaps specifiers through
+    /// the given [ImportMap] and resolves modules registered in the [VirtualFileSystem] before
+    /// modules on disk.
+    /// Imports and reads of files which the [Capabilities] disallow fail.
+    /// TypeScript and JSX files are compiled with the JSX mode and pragmas of `typescript`.
+    pub fn new(
+        modules: ModuleMap,
+        import_map: ImportMap,
+        virtual_modules: VirtualFileSystem,
+        capabilities: Capabilities,
+        typescript: TypeScriptOptions,
+    ) -> Loader {
+        Loader {
+            modules,
+            import_map,
+            virtual_modules,
+            capabilities,
+            typescript,
+        }
+    }
+
+    fn resolve_specifier(&self, specifier: String, data: Option<&ModuleData>) -> Specifier {
+        let referrer = data.and_then(|d| d.path.as_ref()).map(Path::new);
+        if let Some(path) = self.import_map.resolve(&specifier, referrer) {
+            return Specifier::Path(path);
+        }
+
+        if specifier.starts_with("./") || specifier.starts_with("../") {
+            let path = if let Some(parent_path) = data.and_then(|d| d.path.as_ref()) {
+                Path::new(parent_path)
+                    .parent()
+                    .unwrap_or_else(|| Path::new("."))
+                    .join(&specifier)
+            } else {
+                // If no parent path, use current directory
+                Path::new(".").join(&specifier)
+            };
+            return Specifier::Path(path);
+        }
+
+        if Path::new(&specifier).is_absolute() {
+            return Specifier::Path(PathBuf::from(specifier));
+        }
+
+        Specifier::NodeModule(specifier)
+    }
+
+    /// Loads, compiles and registers the module at the given path, unless it is already loaded.
+    fn resolve_path<'cx>(
+        &mut self,
+        cx: &'cx Context,
+        path: PathBuf,
+        kind: ModuleType,
+    ) -> Result<Module<'cx>> {
+        let key = ModuleKey {
+            specifier: Specifier::Path(path.clone()),
+            kind,
+        };
+        let path = path.as_path();
+        let name = path.to_str().unwrap();
+
+        if let Some(module) = self.modules.get(cx, &key) {
+            return Ok(module);
+        }
+
+        let module = if let Some(script) = self.virtual_modules.read(path) {
+            Module::compile_and_evaluate(cx, name, Some(path), &script).map(|(module, _)| module)
+        } else {
+            if let Some(reason) = self.capabilities.check_read(name) {
+                return Err(Error::new(reason, None));
+            }
+            let script = read_to_string(path)
+                .map_err(|_| Error::new(format!("Unable to read module: {key:?}"), None))?;
+
+            match kind {
+                ModuleType::JavaScript => {
+                    let script = self
+                        .transpile(path, script)
+                        .map_err(|message| Error::new(message, Some(ErrorKind::Syntax)))?;
+                    Module::compile_and_evaluate(cx, name, Some(path), &script)
+                        .map(|(module, _)| module)
+                }
+                ModuleType::Json => Module::compile_and_evaluate(cx, name, Some(path), &script)
+                    .map(|(module, _)| module),
+            }
+        };
+
+        match module {
+            Ok(module) => {
+                let request = ModuleRequest::new(cx, name);
+                self.register(cx, module.0.handle().get(), &request)?;
+                Ok(module)
+            }
+            Err(_) => Err(Error::new(
+                format!("Unable to compile module: {key:?}"),
+                None,
+            )),
+        }
+    }
+
+    /// Converts the source of a module file into JavaScript module source according to its
+    /// extension. TypeScript and JSX are compiled and their source maps registered, and CommonJS
+    /// modules are wrapped to export their `module.exports` as the default export.
+    fn transpile(&self, path: &Path, source: String) -> std::result::Result<String, String> {
+        let extension = path.extension().and_then(OsStr::to_str).unwrap_or_default();
+        let typescript = Config::global().typescript;
+        let compile = |jsx: bool, module: ModuleFormat| {
+            compile_module(path, &source, &self.typescript.for_source(jsx, module))
+        };
+
+        match extension {
+            "ts" | "mts" if typescript => compile(false, ModuleFormat::EsNext),
+            "tsx" if typescript => compile(true, ModuleFormat::EsNext),
+            "cts" if typescript => compile(false, ModuleFormat::CommonJs)
+                .map(|script| commonjs_module_source(path, &script)),
+            "jsx" => compile(true, ModuleFormat::EsNext),
+            "cjs" => Ok(commonjs_module_source(path, &source)),
+            _ => Ok(source),
+        }
+    }
+}
+
+impl ModuleLoader for Loader {
+    fn resolve<'cx>(
+        &mut self,
+        cx: &'cx Context,
+        private: &Value,
+        request: &ModuleRequest,
+    ) -> Result<Module<'cx>> {
+        let specifier = request.specifier(cx).to_owned(cx)?;
+        if let Some(reason) = self.capabilities.check_import(&specifier) {
+            return Err(Error::new(reason, None));
+        }
+        let data = ModuleData::from_private(cx, private);
+        let kind = request.kind(cx);
+
+        let path = match self.resolve_specifier(specifier, data.as_ref()) {
+            Specifier::Path(path) => path,
+            Specifier::NodeModule(specifier) => {
+                // Standard modules are registered under their bare specifier.
+                let key = ModuleKey {
+                    specifier: Specifier::Path(PathBuf::from(&specifier)),
+                    kind,
+                };
+                if let Some(module) = self.modules.get(cx, &key) {
+                    return Ok(module);
+                }
+                if let Some(path) = self.virtual_modules.locate(Path::new(&specifier)) {
+                    return self.resolve_path(cx, path, kind);
+                }
+                // Resolving packages probes the disk, which would reveal whether files exist.
+                if let Some(reason) = self.capabilities.check_read(&specifier) {
+                    return Err(Error::new(reason, None));
+                }
+
+                let base = data
+                    .as_ref()
+                    .and_then(|d| d.path.as_ref())
+                    .and_then(|path| Path::new(path).parent())
+                    .unwrap_or_else(|| Path::new("."));
+                let base = canonicalize(base).unwrap_or_else(|_| base.to_path_buf());
+                node::resolve(&specifier, &base).ok_or_else(|| {
+                    Error::new(
+                        format!(
+                            "Cannot find module \"{specifier}\" in node_modules from {}",
+                            base.display()
+                        ),
+                        None,
+                    )
+                })?
+            }
+        };
+
+        // Synthetic modules are registered under their normalised path.
+        let key = ModuleKey {
+            specifier: Specifier::Path(normalize(&path)),
+            kind,
+        };
+        if let Some(module) = self.modules.get(cx, &key) {
+            return Ok(module);
+        }
+
+        let path = self.virtual_modules.locate(&path).unwrap_or(path);
+        self.resolve_path(cx, path, kind)
+    }
+
+    fn register(
+        &mut self,
+        cx: &Context,
+        module: *mut JSObject,
+        request: &ModuleRequest,
+    ) -> Result<()> {
+        let specifier = Specifier::Path(PathBuf::from(request.specifier(cx).to_owned(cx)?));
+        let kind = ModuleType::JavaScript;
+        let key = ModuleKey { specifier, kind };
+        if self.modules.insert(key, module) {
+            Ok(())
+        } else {
+            Err(Error::new("Module already exists", None))
+        }
+    }
+
+    fn metadata(&self, cx: &Context, private: &Value, meta: &Object) -> Result<()> {
+        let data = ModuleData::from_private(cx, private);
+
+        if let Some(data) = data {
+            if let Some(path) = data.path.as_ref() {
+                // Virtual modules do not exist on disk and cannot be canonicalised, and the disk
+                // is not probed at all without filesystem access.
+                let path = if self.capabilities.fs {
+                    canonicalize(path).or_else(|_| std::path::absolute(path))?
+                } else {
+                    std::path::absolute(path)?
+                };
+                let url = Url::from_file_path(path).unwrap();
+                if !meta.set_as(cx, "url", url.as_str()) {
+                    return Err(Error::none());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Wraps the source of a CommonJS module in a module which exports its `module.exports` as the
+/// default export, or its `default` export if it was compiled from an ES module and sets
+/// `__esModule`. The wrapper starts on the first line, so line numbers of the source are kept.
+///
+/// Modules are only loaded through imports, so `require` throws an error when called.
+fn commonjs_module_source(path: &Path, source: &str) -> String {
+    let message = format!(
+        "require() is not supported in CommonJS module {}, use import instead",
+        path.display()
+    );
+    // JSON strings are valid JavaScript string literals.
+    let message = serde_json::to_string(&message).unwrap();
+    format!(
+        "const module = {{ exports: {{}} }}; \
+         const require = () => {{ throw new Error({message}); }}; \
+         (function (exports, require, module) {{ {source}\n}}).call(\
+         module.exports, module.exports, require, module);\n\
+         export default module.exports && module.exports.__esModule \
+         ? module.exports.default : module.exports;\n"
+    )
+}
+
+/// Generates the source of a synthetic module, which exports a binding under each of the given
+/// names. The bindings are set through the environment of the module once it is evaluated.
+fn synthetic_module_source(names: &[String]) -> String {
+    let mut source = String::new();
+    for (index, name) in names.iter().enumerate() {
+        // JSON strings are valid JavaScript string literals, which can be used as export names.
+        let name = serde_json::to_string(name).unwrap();
+        source.push_str(&format!(
+            "let export{index};\nexport {{ export{index} as {name} }};\n"
+        ));
+    }
+    source
+}
+
+/// Lexically normalises a path by removing `.` components and resolving `..` components.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
@@ -0,0 +1,171 @@
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+/// Conditions matched against conditional `exports`, in the order they are declared by the package.
+const CONDITIONS: &[&str] = &["import", "module", "default"];
+
+/// Extensions tried when a file is referenced without one.
+const EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "json"];
+
+/// Resolves a bare specifier such as `lodash` or `@scope/pkg/sub` in the style of Node.js.
+///
+/// `node_modules` directories are searched from `base` up to the root of the filesystem.
+/// Within the first package found, the `exports` field of its `package.json` is used if present,
+/// falling back to the `module` and `main` fields and finally `index.js`.
+///
+/// Returns [None] when the specifier cannot be resolved.
+pub fn resolve(specifier: &str, base: &Path) -> Option<PathBuf> {
+    let (name, subpath) = split_specifier(specifier)?;
+    let package = base
+        .ancestors()
+        .map(|dir| dir.join("node_modules").join(name))
+        .find(|dir| dir.is_dir())?;
+    resolve_package(&package, &subpath)
+}
+
+/// Splits a bare specifier into the package name and the subpath within the package.
+/// The subpath is `.` for the package itself and `./<rest>` otherwise.
+fn split_specifier(specifier: &str) -> Option<(&str, String)> {
+    let separator = if specifier.starts_with('@') {
+        let scope = specifier.find('/')?;
+        specifier[scope + 1..].find('/').map(|index| scope + 1 + index)
+    } else {
+        specifier.find('/')
+    };
+
+    let (name, rest) = match separator {
+        Some(index) => (&specifier[..index], &specifier[index + 1..]),
+        None => (specifier, ""),
+    };
+    if name.is_empty() || name.starts_with('.') || name.contains('\\') {
+        return None;
+    }
+
+    let subpath = if rest.is_empty() {
+        String::from(".")
+    } else {
+        format!("./{rest}")
+    };
+    Some((name, subpath))
+}
+
+fn resolve_package(package: &Path, subpath: &str) -> Option<PathBuf> {
+    let manifest = read_to_string(package.join("package.json"))
+        .ok()
+        .and_then(|json| serde_json::from_str::<Value>(&json).ok());
+
+    if let Some(exports) = manifest.as_ref().and_then(|manifest| manifest.get("exports")) {
+        // Packages with exports only expose the subpaths they list.
+        let target = resolve_exports(exports, subpath)?;
+        let path = package.join(target);
+        return path.is_file().then_some(path);
+    }
+
+    if subpath == "." {
+        let entry = manifest.as_ref().and_then(|manifest| {
+            ["module", "main"]
+                .iter()
+                .filter_map(|field| manifest.get(field)?.as_str())
+                .find_map(|entry| resolve_file(&package.join(entry)))
+        });
+        entry.or_else(|| resolve_file(&package.join("index")))
+    } else {
+        resolve_file(&package.join(subpath))
+    }
+}
+
+/// Resolves a path to a file, trying known extensions and `index` files of directories.
+fn resolve_file(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return Some(path.to_path_buf());
+    }
+
+    let with_extension = EXTENSIONS.iter().find_map(|extension| {
+        let mut file = path.as_os_str().to_owned();
+        file.push(".");
+        file.push(extension);
+        let file = PathBuf::from(file);
+        file.is_file().then_some(file)
+    });
+    with_extension.or_else(|| {
+        path.is_dir()
+            .then(|| {
+                EXTENSIONS
+                    .iter()
+                    .map(|extension| path.join(format!("index.{extension}")))
+                    .find(|file| file.is_file())
+            })
+            .flatten()
+    })
+}
+
+/// Resolves a subpath against the `exports` field of a package, returning the target relative
+/// to the package directory.
+fn resolve_exports(exports: &Value, subpath: &str) -> Option<String> {
+    let subpaths = match exports {
+        Value::Object(map) if map.keys().any(|key| key.starts_with('.')) => map,
+        // Sugar for `{ ".": exports }`
+        _ => return (subpath == ".").then(|| resolve_target(exports, None)).flatten(),
+    };
+
+    if let Some(target) = subpaths.get(subpath) {
+        return resolve_target(target, None);
+    }
+
+    let (key, replacement) = best_pattern_match(subpaths, subpath)?;
+    let target = &subpaths[key];
+    if key.ends_with('/') {
+        // Legacy directory exports, such as `"./features/": "./src/features/"`
+        resolve_target(target, None).map(|target| format!("{target}{replacement}"))
+    } else {
+        resolve_target(target, Some(replacement))
+    }
+}
+
+/// Finds the `exports` key with the longest prefix matching the subpath, returning the key and
+/// the part of the subpath matched by `*` or following the trailing `/`.
+fn best_pattern_match<'s>(
+    subpaths: &'s Map<String, Value>,
+    subpath: &'s str,
+) -> Option<(&'s str, &'s str)> {
+    subpaths
+        .keys()
+        .filter_map(|key| {
+            if let Some((prefix, suffix)) = key.split_once('*') {
+                let matched = subpath.strip_prefix(prefix)?.strip_suffix(suffix)?;
+                Some((key.as_str(), prefix.len(), matched))
+            } else if key.ends_with('/') {
+                let matched = subpath.strip_prefix(key.as_str())?;
+                Some((key.as_str(), key.len(), matched))
+            } else {
+                None
+            }
+        })
+        .max_by_key(|(_, prefix, _)| *prefix)
+        .map(|(key, _, matched)| (key, matched))
+}
+
+/// Resolves a target of `exports`, which may be a path, a list of fallbacks or a set of conditions.
+fn resolve_target(target: &Value, replacement: Option<&str>) -> Option<String> {
+    match target {
+        Value::String(target) => {
+            if !target.starts_with("./") || target.split('/').any(|segment| segment == "..") {
+                return None;
+            }
+            Some(match replacement {
+                Some(replacement) => target.replace('*', replacement),
+                None => target.clone(),
+            })
+        }
+        Value::Array(targets) => targets
+            .iter()
+            .find_map(|target| resolve_target(target, replacement)),
+        Value::Object(conditions) => conditions
+            .iter()
+            .filter(|(condition, _)| CONDITIONS.contains(&condition.as_str()))
+            .find_map(|(_, target)| resolve_target(target, replacement)),
+        _ => None,
+    }
+}
@@ -0,0 +1,91 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use crate::loader::normalize;
+use crate::typescript::{ModuleFormat, TypeScriptOptions, compile_module};
+
+/// Kind of source registered as a virtual module.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VirtualModuleKind {
+    JavaScript,
+    Json,
+    TypeScript,
+}
+
+impl FromStr for VirtualModuleKind {
+    type Err = String;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        match kind {
+            "js" => Ok(VirtualModuleKind::JavaScript),
+            "json" => Ok(VirtualModuleKind::Json),
+            "ts" => Ok(VirtualModuleKind::TypeScript),
+            _ => Err(format!(
+                "Unknown module kind \"{kind}\", expected \"js\", \"json\" or \"ts\""
+            )),
+        }
+    }
+}
+
+impl VirtualModuleKind {
+    /// Converts the source of a module of this kind into JavaScript module source, compiling
+    /// TypeScript with the options of the runtime.
+    pub fn to_javascript(
+        self,
+        path: &Path,
+        source: String,
+        typescript: &TypeScriptOptions,
+    ) -> Result<String, String> {
+        match self {
+            VirtualModuleKind::JavaScript => Ok(source),
+            VirtualModuleKind::Json => {
+                serde_json::from_str::<serde_json::Value>(&source)
+                    .map_err(|e| format!("Invalid JSON module: {e}"))?;
+                Ok(format!("export default (\n{source}\n);"))
+            }
+            VirtualModuleKind::TypeScript => compile_module(
+                path,
+                &source,
+                &typescript.for_source(false, ModuleFormat::EsNext),
+            ),
+        }
+    }
+}
+
+/// In-memory modules which are resolved by the [Loader](super::Loader) as if they were files.
+///
+/// Modules are keyed by their lexically normalised path, so relative imports between virtual
+/// modules resolve in the same way as between files. Clones share the same modules.
+#[derive(Clone, Debug, Default)]
+pub struct VirtualFileSystem {
+    modules: Rc<RefCell<HashMap<PathBuf, String>>>,
+}
+
+impl VirtualFileSystem {
+    /// Registers the JavaScript source of a module at the given path.
+    /// Returns `false` if a module is already registered at that path.
+    pub fn register(&self, path: &Path, source: String) -> bool {
+        match self.modules.borrow_mut().entry(normalize(path)) {
+            Entry::Vacant(entry) => {
+                entry.insert(source);
+                true
+            }
+            Entry::Occupied(_) => false,
+        }
+    }
+
+    /// Returns the normalised path of the module if one is registered at the given path.
+    pub fn locate(&self, path: &Path) -> Option<PathBuf> {
+        let path = normalize(path);
+        self.modules.borrow().contains_key(&path).then_some(path)
+    }
+
+    /// Returns the source of the module registered at the given normalised path.
+    pub fn read(&self, path: &Path) -> Option<String> {
+        self.modules.borrow().get(path).cloned()
+    }
+}
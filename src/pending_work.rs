@@ -0,0 +1,140 @@
+use crate::errors::JSErrorHandling;
+use crate::handle::Handle;
+use crate::traits::ExtendLifetime;
+use ion::script::Script;
+use ion::{Context, Function, Object};
+use pyo3::prelude::*;
+use std::cell::RefCell;
+use std::time::Duration;
+
+/// Interval in milliseconds at which the event loop is polled while network requests are in
+/// flight, as their completion is only noticed by polling.
+const REQUEST_POLL_INTERVAL: u32 = 10;
+
+/// Wraps the timer globals and `fetch` to keep track of the work they schedule. Evaluates to a
+/// function which returns the number of milliseconds until some of the tracked work may be
+/// ready, or -1 if none is pending.
+const TRACK_PENDING_WORK: &str = r#"((requestPollInterval) => {
+    const due = new Map();
+    let nextMacrotask = 0;
+    let requests = 0;
+
+    const track = (name, repeat) => {
+        const schedule = globalThis[name];
+        if (typeof schedule !== "function") {
+            return;
+        }
+        globalThis[name] = function (callback, delay = 0, ...args) {
+            if (typeof callback !== "function") {
+                return Reflect.apply(schedule, this, [callback, delay, ...args]);
+            }
+            const interval = Math.max(Number(delay) || 0, 0);
+            let id;
+            const wrapped = function (...callbackArgs) {
+                if (repeat) {
+                    due.set(id, Date.now() + interval);
+                } else {
+                    due.delete(id);
+                }
+                return Reflect.apply(callback, this, callbackArgs);
+            };
+            id = Reflect.apply(schedule, this, [wrapped, delay, ...args]);
+            due.set(id, Date.now() + interval);
+            return id;
+        };
+    };
+    const untrack = (name) => {
+        const clear = globalThis[name];
+        if (typeof clear !== "function") {
+            return;
+        }
+        globalThis[name] = function (id) {
+            due.delete(id);
+            return Reflect.apply(clear, this, [id]);
+        };
+    };
+
+    track("setTimeout", false);
+    track("setInterval", true);
+    untrack("clearTimeout");
+    untrack("clearInterval");
+
+    const queueMacrotask = globalThis.queueMacrotask;
+    if (typeof queueMacrotask === "function") {
+        globalThis.queueMacrotask = function (callback) {
+            if (typeof callback !== "function") {
+                return Reflect.apply(queueMacrotask, this, [callback]);
+            }
+            const key = `macrotask-${nextMacrotask++}`;
+            due.set(key, Date.now());
+            return Reflect.apply(queueMacrotask, this, [function (...args) {
+                due.delete(key);
+                return Reflect.apply(callback, this, args);
+            }]);
+        };
+    }
+
+    let fetch;
+    try {
+        fetch = globalThis.fetch;
+    } catch {
+        // Network requests are not allowed in this runtime.
+    }
+    if (typeof fetch === "function") {
+        globalThis.fetch = function (...args) {
+            requests++;
+            let request;
+            try {
+                request = Reflect.apply(fetch, this, args);
+            } catch (error) {
+                requests--;
+                throw error;
+            }
+            return Promise.resolve(request).finally(() => requests--);
+        };
+    }
+
+    return () => {
+        let delay = due.size > 0 ? Math.max(Math.min(...due.values()) - Date.now(), 0) : -1;
+        if (requests > 0) {
+            delay = delay < 0 ? requestPollInterval : Math.min(delay, requestPollInterval);
+        }
+        return delay;
+    };
+})"#;
+
+thread_local! {
+    /// Function returned by [TRACK_PENDING_WORK] for the runtime of this thread.
+    static TRACKER: RefCell<Option<Handle<Function<'static>>>> = const { RefCell::new(None) };
+}
+
+/// Starts keeping track of the timers and network requests of the runtime of this thread, so
+/// that [next_work_delay] can tell when the event loop has work to do.
+pub fn track_pending_work(cx: &Context) -> PyResult<()> {
+    let source = format!("{TRACK_PENDING_WORK}({REQUEST_POLL_INTERVAL});");
+    let tracker =
+        Script::compile_and_evaluate(cx, "pending_work.js".as_ref(), &source).to_js_err(cx)?;
+    let tracker = Function::from_object(cx, &tracker.to_object(cx))
+        .expect("TRACK_PENDING_WORK evaluates to a function");
+    let tracker = Handle::new(tracker.extend_lifetime());
+    TRACKER.with(|cell| cell.replace(Some(tracker)));
+    Ok(())
+}
+
+/// Returns how long until some of the timers or network requests of the runtime may be ready,
+/// or [None] if none of them are pending or they are not tracked.
+///
+/// Work scheduled in other realms, and futures other than network requests, are not tracked.
+pub fn next_work_delay(cx: &Context) -> Option<Duration> {
+    TRACKER.with(|cell| {
+        let tracker = cell.borrow();
+        let tracker = tracker.as_ref()?.get().ok()?;
+        let delay = tracker
+            .call(cx, &Object::global(cx), &[])
+            .to_js_err(cx)
+            .ok()?;
+        let delay = delay.handle();
+        (delay.is_number() && delay.to_number() >= 0.0)
+            .then(|| Duration::from_secs_f64(delay.to_number() / 1000.0))
+    })
+}
@@ -0,0 +1,413 @@
+use crate::conversions::{DEFAULT_MAX_DEPTH, JSToPy, PyConversionFallback, PyToJS};
+use crate::errors::{JSError, JSErrorHandling, JSTimeoutError, rejection_error};
+use crate::handle::Handle;
+use crate::interrupt::{parse_timeout, with_timeout};
+use crate::runtime::{
+    PyEventLoopStatus, PythonJSRuntime, default_timeout, run_event_loop_bounded, with_js_cx,
+};
+use crate::traits::ExtendLifetime;
+use crate::types::value::PyJSValue;
+use ion::conversions::{FromValue, ToValue};
+use ion::script::Script;
+use ion::{Context, Function, Object, Promise, Value};
+use mozjs::jsapi::PromiseState;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Imports a module and calls one of its exported functions. Compiled once by each worker.
+const CALL_EXPORT: &str = r#"(async (specifier, name, ...args) => {
+    const module = await import(specifier);
+    if (typeof module[name] !== "function") {
+        throw new TypeError(`${name} is not a function exported by ${specifier}`);
+    }
+    return module[name](...args);
+})"#;
+
+/// Work submitted to a [PyRuntimePool].
+enum Task {
+    /// Evaluates a script.
+    Script { source: String, filename: String },
+    /// Calls a function exported by a module.
+    Call {
+        module: String,
+        function: String,
+        args: Vec<PyObject>,
+    },
+}
+
+/// A [Task] together with the future which receives its result.
+struct Job {
+    task: Task,
+    timeout: Option<Duration>,
+    future: PyObject,
+}
+
+impl Job {
+    /// Runs the task on the runtime of the current thread and completes the future.
+    /// `call_export` is the function compiled from [CALL_EXPORT] by the worker.
+    fn run(self, call_export: &Handle<Function<'static>>) {
+        let Job {
+            task,
+            timeout,
+            future,
+        } = self;
+        let running = Python::with_gil(|py| {
+            future
+                .call_method0(py, "set_running_or_notify_cancel")
+                .and_then(|running| running.is_truthy(py))
+                .is_ok_and(|running| running)
+        });
+        if !running {
+            // The future was cancelled while the job was queued.
+            return;
+        }
+
+        let result = execute(task, timeout, call_export);
+        Python::with_gil(|py| {
+            let outcome = match result {
+                Ok(value) => future.call_method1(py, "set_result", (value,)),
+                Err(error) => {
+                    let error = detach_error(py, error);
+                    future.call_method1(py, "set_exception", (error.into_value(py),))
+                }
+            };
+            if let Err(error) = outcome {
+                error.write_unraisable(py, Some(future.bind(py)));
+            }
+        });
+    }
+}
+
+/// Runs a task and converts its result into a plain Python value. Promises are awaited by
+/// running the event loop, and the whole task is limited to `timeout`.
+fn execute(
+    task: Task,
+    timeout: Option<Duration>,
+    call_export: &Handle<Function<'static>>,
+) -> PyResult<PyObject> {
+    let call_export = call_export.get()?;
+    let timeout = timeout.or_else(default_timeout);
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let cx = with_js_cx(|cx| cx.as_ptr());
+    let value = with_timeout(cx, timeout, || {
+        with_js_cx(|cx| {
+            let value = match task {
+                Task::Script { source, filename } => {
+                    Script::compile_and_evaluate(cx, filename.as_ref(), &source).to_js_err(cx)?
+                }
+                Task::Call {
+                    module,
+                    function,
+                    args,
+                } => {
+                    let mut arguments = vec![module.as_value(cx), function.as_value(cx)];
+                    Python::with_gil(|py| {
+                        let mut converter = PyToJS::new(cx, DEFAULT_MAX_DEPTH);
+                        for arg in &args {
+                            arguments.push(converter.convert(arg.bind(py))?);
+                        }
+                        Ok::<_, PyErr>(())
+                    })?;
+                    call_export
+                        .call(cx, &Object::global(cx), &arguments)
+                        .to_js_err(cx)?
+                }
+            };
+            Ok(value.extend_lifetime())
+        })
+    })?;
+
+    let value = settle(value, deadline)?;
+    Python::with_gil(|py| {
+        with_js_cx(|cx| {
+            JSToPy::new(cx, py, DEFAULT_MAX_DEPTH, PyConversionFallback::Error)
+                .convert(&value)
+                .map(Bound::unbind)
+        })
+    })
+}
+
+/// Compiles [CALL_EXPORT] into the function which calls module exports for [Task::Call].
+fn compile_call_export(cx: &Context) -> PyResult<Handle<Function<'static>>> {
+    let call_export =
+        Script::compile_and_evaluate(cx, "pool.js".as_ref(), CALL_EXPORT).to_js_err(cx)?;
+    let call_export = Function::from_object(cx, &call_export.to_object(cx))
+        .expect("CALL_EXPORT evaluates to a function");
+    Ok(Handle::new(call_export.extend_lifetime()))
+}
+
+/// Runs the event loop until `value` is settled if it is a promise, and returns its result.
+fn settle(value: Value<'static>, deadline: Option<Instant>) -> PyResult<Value<'static>> {
+    let promise = with_js_cx(|cx| {
+        Promise::from_value(cx, &value, true, ())
+            .ok()
+            .map(ExtendLifetime::extend_lifetime)
+    });
+    let Some(promise) = promise else {
+        return Ok(value);
+    };
+
+    let timeout = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+    let settled = || !matches!(promise.state(), PromiseState::Pending);
+    let status = run_event_loop_bounded(timeout, None, settled)
+        .unwrap_or_else(|| Err(PyRuntimeError::new_err("Event loop is already running")))?;
+    with_js_cx(|cx| match status {
+        PyEventLoopStatus::TimedOut => Err(JSTimeoutError::new_err(format!(
+            "Execution timed out after {:.3}s",
+            timeout.unwrap_or_default().as_secs_f64()
+        ))),
+        PyEventLoopStatus::Idle => Err(JSError::new_err(
+            "Promise was never settled, as the event loop ran out of work",
+        )),
+        PyEventLoopStatus::PromiseSettled => match promise.state() {
+            PromiseState::Rejected => Err(rejection_error(cx, &promise.result(cx))),
+            _ => Ok(promise.result(cx).extend_lifetime()),
+        },
+    })
+}
+
+/// Replaces the thrown value attached to a [JSError] with a plain Python value, as the
+/// `JSValue` cannot be used outside of the thread of its runtime.
+fn detach_error(py: Python<'_>, error: PyErr) -> PyErr {
+    if error.is_instance_of::<JSError>(py) {
+        let instance = error.value(py);
+        let value = instance
+            .getattr("value")
+            .ok()
+            .and_then(|value| value.downcast_into::<PyJSValue>().ok())
+            .and_then(|value| {
+                value
+                    .borrow()
+                    .to_py(py, DEFAULT_MAX_DEPTH, PyConversionFallback::Null)
+                    .ok()
+            });
+        if let Err(error) = instance.setattr("value", value) {
+            return error;
+        }
+    }
+    error
+}
+
+/// Runs jobs from the shared queue on a dedicated runtime until the pool is shut down.
+fn run_worker(
+    options: Option<Py<PyDict>>,
+    jobs: Arc<Mutex<Receiver<Job>>>,
+    ready: Sender<PyResult<()>>,
+) {
+    let started = Python::with_gil(|py| {
+        let runtime = py
+            .get_type::<PythonJSRuntime>()
+            .call((), options.as_ref().map(|options| options.bind(py)))?
+            .unbind();
+        let call_export = with_js_cx(compile_call_export)?;
+        Ok::<_, PyErr>((runtime, call_export))
+    });
+    let (runtime, call_export) = match started {
+        Ok(started) => {
+            let _ = ready.send(Ok(()));
+            started
+        }
+        Err(error) => {
+            let _ = ready.send(Err(error));
+            return;
+        }
+    };
+
+    loop {
+        // The lock is released before running the job, so that other workers can take jobs.
+        let job = jobs.lock().expect("job queue lock poisoned").recv();
+        match job {
+            Ok(job) => job.run(&call_export),
+            Err(_) => break,
+        }
+    }
+    drop(call_export);
+    Python::with_gil(|_| drop(runtime));
+}
+
+/// A pool of threads which each own a JavaScript runtime.
+///
+/// Unlike [Runtime](PythonJSRuntime) and JavaScript values, which can only be used on the
+/// thread that created them, the pool can be shared by any number of Python threads. Work is
+/// submitted as source code or module exports, and results are delivered through
+/// `concurrent.futures.Future`s as plain Python values.
+///
+/// Runtimes of the pool do not share any state, so globals set by one task are only visible
+/// to later tasks which happen to run on the same runtime.
+#[gen_stub_pyclass]
+#[pyclass(frozen, name = "RuntimePool", module = "js_exec.js_exec")]
+pub struct PyRuntimePool {
+    size: usize,
+    jobs: Mutex<Option<Sender<Job>>>,
+    workers: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl PyRuntimePool {
+    /// Queues a task and returns the future which receives its result.
+    fn enqueue(&self, py: Python<'_>, task: Task, timeout: Option<f64>) -> PyResult<PyObject> {
+        let timeout = parse_timeout(timeout)?;
+        let future = py
+            .import("concurrent.futures")?
+            .getattr("Future")?
+            .call0()?;
+        let job = Job {
+            task,
+            timeout,
+            future: future.clone().unbind(),
+        };
+        let jobs = self.jobs.lock().expect("job queue lock poisoned");
+        match &*jobs {
+            Some(jobs) if jobs.send(job).is_ok() => Ok(future.unbind()),
+            _ => Err(PyRuntimeError::new_err(
+                "Cannot submit work to a pool which has been shut down",
+            )),
+        }
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyRuntimePool {
+    /// Creates a pool of `size` threads, each with its own runtime.
+    ///
+    /// # Arguments
+    /// * `size` - Number of threads and runtimes
+    /// * `options` - Keyword arguments for the [Runtime](PythonJSRuntime) of each thread
+    ///
+    /// # Errors
+    /// Raises [ValueError] if `size` is 0, and the error of the first runtime which fails to
+    /// be created.
+    #[new]
+    #[pyo3(signature = (size = 1, **options))]
+    pub fn new(py: Python<'_>, size: usize, options: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        if size == 0 {
+            return Err(PyValueError::new_err("size must be at least 1"));
+        }
+        let (sender, receiver) = channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let mut workers = Vec::with_capacity(size);
+        let mut readiness = Vec::with_capacity(size);
+        for index in 0..size {
+            let options = options.map(|options| options.clone().unbind());
+            let receiver = Arc::clone(&receiver);
+            let (ready, ready_receiver) = channel();
+            let worker = thread::Builder::new()
+                .name(format!("js-exec-pool-{index}"))
+                .spawn(move || run_worker(options, receiver, ready))
+                .map_err(|error| {
+                    PyRuntimeError::new_err(format!("Failed to spawn worker thread: {error}"))
+                })?;
+            workers.push(worker);
+            readiness.push(ready_receiver);
+        }
+
+        let pool = PyRuntimePool {
+            size,
+            jobs: Mutex::new(Some(sender)),
+            workers: Mutex::new(workers),
+        };
+        // Workers need the GIL to create their runtimes.
+        let ready = py.allow_threads(|| {
+            readiness
+                .iter()
+                .map(|ready| {
+                    ready.recv().unwrap_or_else(|_| {
+                        Err(PyRuntimeError::new_err("Worker thread exited unexpectedly"))
+                    })
+                })
+                .collect::<PyResult<Vec<()>>>()
+        });
+        if let Err(error) = ready {
+            pool.shutdown(py, true);
+            return Err(error);
+        }
+        Ok(pool)
+    }
+
+    /// Number of threads and runtimes of the pool.
+    #[getter]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Evaluates a script on one of the runtimes.
+    ///
+    /// # Arguments
+    /// * `source` - JavaScript source code to execute
+    /// * `filename` - Name to use in error messages (defaults to "inline.js")
+    /// * `timeout` - Time limit in seconds, which defaults to the timeout of the runtimes
+    ///
+    /// # Returns
+    /// A `concurrent.futures.Future` of the result of the script, converted into a plain
+    /// Python value. Promises are awaited first.
+    #[pyo3(signature = (source, filename = "inline.js", timeout = None))]
+    pub fn submit(
+        &self,
+        py: Python<'_>,
+        source: String,
+        filename: String,
+        timeout: Option<f64>,
+    ) -> PyResult<PyObject> {
+        self.enqueue(py, Task::Script { source, filename }, timeout)
+    }
+
+    /// Calls a function exported by a module on one of the runtimes.
+    ///
+    /// # Arguments
+    /// * `module` - Specifier of the module, resolved like a dynamic `import()`
+    /// * `function` - Name of the exported function
+    /// * `args` - Arguments of the call, which are converted with [JSValue::from_py]
+    /// * `timeout` - Time limit in seconds, which defaults to the timeout of the runtimes
+    ///
+    /// # Returns
+    /// A `concurrent.futures.Future` of the return value of the function, converted into a
+    /// plain Python value. Promises are awaited first.
+    #[pyo3(signature = (module, function, args = Vec::new(), timeout = None))]
+    pub fn call(
+        &self,
+        py: Python<'_>,
+        module: String,
+        function: String,
+        args: Vec<PyObject>,
+        timeout: Option<f64>,
+    ) -> PyResult<PyObject> {
+        let task = Task::Call {
+            module,
+            function,
+            args,
+        };
+        self.enqueue(py, task, timeout)
+    }
+
+    /// Stops accepting work and shuts the runtimes down once the queued work is done.
+    ///
+    /// # Arguments
+    /// * `wait` - Wait until the queued work is done and the threads have exited
+    #[pyo3(signature = (wait = true))]
+    pub fn shutdown(&self, py: Python<'_>, wait: bool) {
+        self.jobs.lock().expect("job queue lock poisoned").take();
+        let workers = std::mem::take(&mut *self.workers.lock().expect("workers lock poisoned"));
+        if wait {
+            py.allow_threads(|| {
+                for worker in workers {
+                    let _ = worker.join();
+                }
+            });
+        }
+    }
+
+    pub fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    pub fn __exit__(&self, py: Python<'_>, _args: &Bound<'_, PyTuple>) {
+        self.shutdown(py, true);
+    }
+}
@@ -1,44 +1,155 @@
-use crate::errors::ErrorHandling;
-use crate::traits::ExtendLifetime;
+use crate::capabilities::Capabilities;
+use crate::errors::{
+    ErrorHandling, JSErrorHandling, JSRuntimeClosedError, js_error, typescript_error,
+};
+use crate::interrupt::{install_interrupt_callback, parse_timeout, stop_watchdog, with_timeout};
+use crate::loader::{Loader, ModuleKey, ModuleMap, Specifier, normalize};
+use crate::loader::import_map::ImportMap;
+use crate::loader::vfs::{VirtualFileSystem, VirtualModuleKind};
+use crate::pending_work::track_pending_work;
+use crate::traits::{ExtendLifetime, RootSlot};
+use crate::types::heap_stats::PyHeapStats;
 use crate::types::module::PyJSModule;
-use crate::types::promise::PyJSPromise;
+use crate::types::promise::{PyJSPromise, wake_drivers};
+use crate::types::realm::PyRealm;
 use crate::types::sourcemap::PySourceMap;
 use crate::types::value::PyJSValue;
-use ion::Context as JSContext;
-use ion::module::Module;
+use crate::typescript::{ModuleFormat, TypeScriptOptions, compile_typescript};
+use ion::{Context as JSContext, ErrorReport};
+use ion::module::{Module, ModuleType};
 use ion::script::Script;
 use js_runtime::config::{CONFIG, Config, LogLevel};
 use js_runtime::{Runtime as JSRuntime, RuntimeBuilder as JSRuntimeBuilder};
-use modules::Modules;
-use mozjs::rust::{JSEngine, Runtime as RustRuntime};
+use mozjs::jsapi::{
+    GCOptions, GCReason, JS_AddExtraGCRootsTracer, JS_GC, JS_SetGCParameter, JS_SetGCZeal,
+    JSGCParamKey, JSTracer, NonIncrementalGC, PrepareForFullGC,
+};
+use mozjs::rust::{JSEngine, JSEngineHandle, Runtime as RustRuntime};
 use ouroboros::self_referencing;
-use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
-use std::cell::RefCell;
+use pyo3::types::{PyDict, PyString, PyTuple};
+use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pyclass_enum, gen_stub_pymethods};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::future::{Future, poll_fn};
+use std::path::Path;
+use std::pin::pin;
+use std::ptr;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::Poll;
+use std::time::Duration;
 use tokio::runtime::Runtime as TokioRuntime;
+use tokio::task::LocalSet;
+
+/// Highest GC zeal mode supported by SpiderMonkey.
+const MAX_GC_ZEAL_MODE: u8 = 25;
+
+/// Handle of the JavaScript engine, which is shared by the runtimes of all threads.
+static ENGINE: OnceLock<Result<JSEngineHandle, String>> = OnceLock::new();
+
+/// Generation of the next runtime to be created. Generations start at 1, as 0 means closed.
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+/// Number of the next TypeScript source evaluated without a filename.
+static NEXT_INLINE_TYPESCRIPT: AtomicU64 = AtomicU64::new(1);
 
 thread_local! {
     static JS_RUNTIME_CONTEXT: RefCell<Option<JSRuntimeContext>> = const { RefCell::new(None) };
+    /// Generation of the runtime of this thread, or 0 if there is none.
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+    /// GC things held by Python wrappers, which are traced as roots by every GC.
+    static PERSISTENT_ROOTS: RefCell<HashMap<RootId, RootSlot>> = RefCell::new(HashMap::new());
+    static NEXT_ROOT: Cell<RootId> = const { Cell::new(0) };
+    static EVENT_LOOP_RUNNING: Cell<bool> = const { Cell::new(false) };
     static TOKIO_RT: TokioRuntime = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
         .expect("failed to create a new tokio runtime");
+    /// Local tasks spawned by the runtime, such as the futures of its future queue.
+    static LOCAL_SET: LocalSet = LocalSet::new();
+}
+
+/// Returns the generation of the runtime of the current thread, or 0 if there is none.
+pub fn current_generation() -> u64 {
+    GENERATION.get()
+}
+
+/// Identifier of a GC thing in the persistent root registry of the current thread.
+pub type RootId = u64;
+
+/// Registers a GC thing as a persistent root, which keeps it alive and updates its slot when
+/// the GC moves it, until it is unregistered with [unregister_root].
+pub fn register_root(slot: RootSlot) -> RootId {
+    let id = NEXT_ROOT.get();
+    NEXT_ROOT.set(id + 1);
+    PERSISTENT_ROOTS.with(|roots| roots.borrow_mut().insert(id, slot));
+    id
+}
+
+/// Removes a GC thing from the persistent root registry.
+/// Unknown identifiers, such as those of a closed runtime, are ignored.
+pub fn unregister_root(id: RootId) {
+    let _ = PERSISTENT_ROOTS.try_with(|roots| roots.borrow_mut().remove(&id));
+}
+
+/// Returns the number of GC things in the persistent root registry.
+pub fn persistent_root_count() -> usize {
+    PERSISTENT_ROOTS.with(|roots| roots.borrow().len())
+}
+
+/// Traces the persistent roots of the current thread. Installed as an extra roots tracer.
+unsafe extern "C" fn trace_persistent_roots(trc: *mut JSTracer, _data: *mut c_void) {
+    // The slots are owned by the runtime context, which is being destroyed at thread exit.
+    if JS_RUNTIME_CONTEXT.try_with(|_| ()).is_err() {
+        return;
+    }
+    let _ = PERSISTENT_ROOTS.try_with(|roots| {
+        for slot in roots.borrow().values() {
+            unsafe { slot.trace(trc) };
+        }
+    });
+}
+
+/// Closes the runtime of the current thread if it has the given generation.
+///
+/// Returns [Err] if JavaScript code is running, as the runtime is still in use.
+fn close_runtime(generation: u64) -> PyResult<()> {
+    if generation == 0 || GENERATION.get() != generation {
+        return Ok(());
+    }
+    JS_RUNTIME_CONTEXT.with(|cell| {
+        let mut context = cell.try_borrow_mut().map_err(|_| {
+            PyRuntimeError::new_err("Cannot close the runtime while it is running JavaScript code")
+        })?;
+        GENERATION.set(0);
+        // The watchdog must not request interrupts of the context once it is destroyed.
+        stop_watchdog();
+        // The slots of the roots are freed along with the runtime context.
+        PERSISTENT_ROOTS.with(|roots| roots.borrow_mut().clear());
+        *context = None;
+        Ok(())
+    })
 }
 
 /// Executes a function with access to the JavaScript runtime.
 ///
+/// The runtime is only borrowed immutably, so this can be re-entered from Python callbacks
+/// invoked by JavaScript code while an outer call is still running.
+///
 /// # Panics
 ///
 /// Panics if the runtime has not been initialized for the current thread.
 pub fn with_js_runtime<F, R>(f: F) -> R
 where
-    F: FnOnce(&mut JSRuntime) -> R,
+    F: FnOnce(&JSRuntime) -> R,
 {
     JS_RUNTIME_CONTEXT.with(|cell| {
-        let mut maybe_runtime = cell.borrow_mut();
-        if let Some(rt) = &mut *maybe_runtime {
-            rt.with_js_runtime_mut(f)
+        let maybe_runtime = cell.borrow();
+        if let Some(rt) = &*maybe_runtime {
+            f(rt.borrow_js_runtime())
         } else {
             panic!("JavaScript runtime not initialized for current thread");
         }
@@ -60,11 +171,276 @@ where
     })
 }
 
+/// Executes a function within the local task set of the current thread, so it can spawn local
+/// tasks, which are run while the event loop is driven.
+pub fn within_local_set<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    LOCAL_SET.with(|local_set| {
+        let _guard = local_set.enter();
+        f()
+    })
+}
+
+/// Runs a future to completion on the tokio runtime of the current thread, running local
+/// tasks while it is pending.
+fn block_on<F: Future>(tokio_rt: &TokioRuntime, future: F) -> F::Output {
+    LOCAL_SET.with(|local_set| local_set.block_on(tokio_rt, future))
+}
+
+/// Checks if the event loop is being driven on the current thread.
+pub fn event_loop_running() -> bool {
+    EVENT_LOOP_RUNNING.get()
+}
+
+/// Drives the event loop with the given function, unless the event loop is already being
+/// driven further up the stack, such as from a callback invoked by the event loop.
+///
+/// Returns [None] if the event loop is already running.
+fn enter_event_loop<F, R>(f: F) -> Option<R>
+where
+    F: FnOnce(&JSRuntime, &TokioRuntime) -> R,
+{
+    if EVENT_LOOP_RUNNING.replace(true) {
+        return None;
+    }
+    let result = with_js_runtime(|rt| TOKIO_RT.with(|tokio_rt| f(rt, tokio_rt)));
+    EVENT_LOOP_RUNNING.set(false);
+    Some(result)
+}
+
+/// Runs the work of the event loop which is ready, without waiting for timers or futures.
+///
+/// Returns `true` if the event loop has no pending work left. Returns `false` without doing
+/// anything if the event loop is already running, as its outer driver will make progress.
+pub fn poll_event_loop() -> PyResult<bool> {
+    enter_event_loop(|rt, tokio_rt| {
+        block_on(tokio_rt, async {
+            // Local tasks only run while the future is pending, so they run before the poll.
+            tokio::task::yield_now().await;
+            // A zero timeout polls the event loop exactly once.
+            match tokio::time::timeout(Duration::ZERO, rt.run_event_loop()).await {
+                Ok(result) => result.to_js_err(rt.cx()).map(|_| true),
+                Err(_) => Ok(false),
+            }
+        })
+    })
+    .unwrap_or(Ok(false))
+}
+
+/// Outcome of running the event loop with [PythonJSRuntime::run_event_loop] or
+/// [PythonJSRuntime::run_until_settled].
+#[gen_stub_pyclass_enum]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[pyclass(
+    unsendable,
+    name = "EventLoopStatus",
+    module = "js_exec.js_exec",
+    eq,
+    eq_int
+)]
+pub enum PyEventLoopStatus {
+    /// The event loop has no pending work left.
+    Idle = 0,
+    /// The timeout or the tick limit was reached while work was still pending.
+    TimedOut = 1,
+    /// The awaited promise was settled.
+    PromiseSettled = 2,
+}
+
+/// Runs the event loop until it is idle, `settled` returns `true`, `timeout` elapses or
+/// `max_ticks` iterations of the event loop have run.
+///
+/// Returns [None] if the event loop is already running.
+pub(crate) fn run_event_loop_bounded<F>(
+    timeout: Option<Duration>,
+    max_ticks: Option<u64>,
+    settled: F,
+) -> Option<PyResult<PyEventLoopStatus>>
+where
+    F: Fn() -> bool,
+{
+    enter_event_loop(|rt, tokio_rt| {
+        // Tasks which are still running at the deadline are interrupted.
+        with_timeout(rt.cx().as_ptr(), timeout, || {
+            block_on(tokio_rt, drive_event_loop(rt, timeout, max_ticks, settled))
+                .to_js_err(rt.cx())
+        })
+    })
+}
+
+async fn drive_event_loop<F>(
+    rt: &JSRuntime<'_>,
+    timeout: Option<Duration>,
+    max_ticks: Option<u64>,
+    settled: F,
+) -> Result<PyEventLoopStatus, Option<ErrorReport>>
+where
+    F: Fn() -> bool,
+{
+    let mut event_loop = pin!(rt.run_event_loop());
+    let mut ticks = 0;
+    let run = poll_fn(|wcx| {
+        if settled() {
+            return Poll::Ready(Ok(PyEventLoopStatus::PromiseSettled));
+        }
+        if max_ticks.is_some_and(|max_ticks| ticks >= max_ticks) {
+            return Poll::Ready(Ok(PyEventLoopStatus::TimedOut));
+        }
+        ticks += 1;
+        match event_loop.as_mut().poll(wcx) {
+            Poll::Ready(result) => Poll::Ready(result.map(|_| {
+                if settled() {
+                    PyEventLoopStatus::PromiseSettled
+                } else {
+                    PyEventLoopStatus::Idle
+                }
+            })),
+            Poll::Pending if settled() => Poll::Ready(Ok(PyEventLoopStatus::PromiseSettled)),
+            Poll::Pending => Poll::Pending,
+        }
+    });
+
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, run)
+            .await
+            .unwrap_or(Ok(PyEventLoopStatus::TimedOut)),
+        None => run.await,
+    }
+}
+
+/// Executes a function with access to the virtual modules of the runtime.
+///
+/// # Panics
+///
+/// Panics if the runtime has not been initialized for the current thread.
+pub fn with_virtual_modules<F, R>(f: F) -> R
+where
+    F: FnOnce(&VirtualFileSystem) -> R,
+{
+    JS_RUNTIME_CONTEXT.with(|cell| {
+        let maybe_runtime = cell.borrow();
+        if let Some(rt) = &*maybe_runtime {
+            f(rt.borrow_virtual_modules())
+        } else {
+            panic!("JavaScript runtime not initialized for current thread");
+        }
+    })
+}
+
+/// Executes a function with access to the modules loaded or defined in the runtime.
+///
+/// # Panics
+///
+/// Panics if the runtime has not been initialized for the current thread.
+pub fn with_modules<F, R>(f: F) -> R
+where
+    F: FnOnce(&ModuleMap) -> R,
+{
+    JS_RUNTIME_CONTEXT.with(|cell| {
+        let maybe_runtime = cell.borrow();
+        if let Some(rt) = &*maybe_runtime {
+            f(rt.borrow_modules())
+        } else {
+            panic!("JavaScript runtime not initialized for current thread");
+        }
+    })
+}
+
+/// Runs `f` with an execution time limit of `timeout` seconds, or the default timeout of the
+/// runtime if it is [None]. JavaScript code still running at the deadline is interrupted and
+/// a [JSTimeoutError](crate::errors::JSTimeoutError) is raised.
+///
+/// # Panics
+///
+/// Panics if the runtime has not been initialized for the current thread.
+pub fn with_execution_timeout<F, R>(timeout: Option<f64>, f: F) -> PyResult<R>
+where
+    F: FnOnce() -> PyResult<R>,
+{
+    let timeout = parse_timeout(timeout)?.or_else(default_timeout);
+    let cx = with_js_cx(|cx| cx.as_ptr());
+    let result = with_timeout(cx, timeout, f);
+    // The code may have scheduled work or settled promises which are awaited from asyncio.
+    wake_drivers();
+    result
+}
+
+/// Returns the default timeout of the runtime, which applies to calls without their own.
+///
+/// # Panics
+///
+/// Panics if the runtime has not been initialized for the current thread.
+pub fn default_timeout() -> Option<Duration> {
+    JS_RUNTIME_CONTEXT.with(|cell| {
+        let maybe_runtime = cell.borrow();
+        match &*maybe_runtime {
+            Some(rt) => *rt.borrow_timeout(),
+            None => panic!("JavaScript runtime not initialized for current thread"),
+        }
+    })
+}
+
+/// Returns the options with which the runtime compiles TypeScript and JSX.
+///
+/// # Panics
+///
+/// Panics if the runtime has not been initialized for the current thread.
+pub fn typescript_options() -> TypeScriptOptions {
+    JS_RUNTIME_CONTEXT.with(|cell| {
+        let maybe_runtime = cell.borrow();
+        match &*maybe_runtime {
+            Some(rt) => rt.borrow_typescript().clone(),
+            None => panic!("JavaScript runtime not initialized for current thread"),
+        }
+    })
+}
+
+/// Parses an [ImportMap] given either as JSON text or as a JSON-serialisable Python object.
+fn parse_import_map(import_map: &Bound<'_, PyAny>) -> PyResult<ImportMap> {
+    let json = match import_map.downcast::<PyString>() {
+        Ok(json) => json.to_cow()?.into_owned(),
+        Err(_) => import_map
+            .py()
+            .import("json")?
+            .call_method1("dumps", (import_map,))?
+            .extract()?,
+    };
+    let base = std::env::current_dir().to_runtime_err("Failed to get current directory")?;
+    ImportMap::from_json(&json, &base).map_err(PyValueError::new_err)
+}
+
+/// Returns a handle of the JavaScript engine, initializing it on first use.
+///
+/// The engine can only be initialized once per process, so it is never shut down. This allows
+/// runtimes to be created on several threads, and again after a runtime has been dropped.
+fn engine_handle() -> PyResult<JSEngineHandle> {
+    ENGINE
+        .get_or_init(|| {
+            JSEngine::init()
+                .map(|engine| {
+                    let handle = engine.handle();
+                    std::mem::forget(engine);
+                    handle
+                })
+                .map_err(|error| format!("{error:?}"))
+        })
+        .clone()
+        .map_err(|error| {
+            PyRuntimeError::new_err(format!("Failed to initialize JS engine: {error}"))
+        })
+}
+
 /// Self-referential structure containing all components needed for JavaScript execution.
 #[self_referencing]
 pub struct JSRuntimeContext {
-    engine: JSEngine,
     runtime: RustRuntime,
+    pub(crate) modules: ModuleMap,
+    pub(crate) virtual_modules: VirtualFileSystem,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) capabilities: Capabilities,
+    pub(crate) typescript: TypeScriptOptions,
     pub(crate) js_context: JSContext,
     #[covariant]
     #[borrows(mut js_context)]
@@ -73,13 +449,31 @@ pub struct JSRuntimeContext {
 
 /// Python wrapper for JavaScript runtime functionality.
 ///
-/// # Warning
-/// The runtime must be kept alive until the program ends.
-/// If the runtime is dropped and an attempt is made to use it or any
-/// JavaScript variables or objects created within it, an error will occur.
+/// The runtime is closed when it is dropped or when [close](PythonJSRuntime::close) is called.
+/// Using the runtime or any JavaScript value created within it afterwards raises
+/// [JSRuntimeClosedError].
+///
+/// `script`, `typescript` and `log_level` are shared by all runtimes of the process, so those
+/// created after the first one must use the same values.
 #[gen_stub_pyclass]
-#[pyclass(name = "Runtime", module = "js_exec.js_exec")]
-pub struct PythonJSRuntime;
+#[pyclass(unsendable, name = "Runtime", module = "js_exec.js_exec")]
+pub struct PythonJSRuntime {
+    generation: u64,
+    /// Whether the runtime is closed when this object is dropped, which is unset for the
+    /// objects returned by [empty](PythonJSRuntime::empty).
+    owned: bool,
+}
+
+impl PythonJSRuntime {
+    /// Checks that the runtime has not been closed.
+    fn check_open(&self) -> PyResult<()> {
+        if self.generation != 0 && self.generation == GENERATION.get() {
+            Ok(())
+        } else {
+            Err(JSRuntimeClosedError::new_err("The runtime has been closed"))
+        }
+    }
+}
 
 #[gen_stub_pymethods]
 #[pymethods]
@@ -95,19 +489,89 @@ impl PythonJSRuntime {
     /// * `script` - Enable support for JavaScript scripts
     /// * `typescript` - Enable support for TypeScript
     /// * `log_level` - Set the log level (0: None, 1: Info, 2: Warn, 3: Error, 4: Debug)
+    /// * `import_map` - An [import map](https://html.spec.whatwg.org/multipage/webappapis.html#import-maps)
+    ///   given as JSON text or as a dictionary, used to remap module specifiers.
+    ///   Relative paths within it are resolved against the current working directory.
+    /// * `timeout` - Default time limit in seconds for evaluating scripts and modules and for
+    ///   calling functions, after which a [JSTimeoutError] is raised
+    /// * `max_heap_bytes` - Limit of the GC heap in bytes. Allocations beyond it fail with an
+    ///   uncatchable out of memory error
+    /// * `max_nursery_bytes` - Maximum size in bytes of the nursery, where new objects are
+    ///   allocated before being promoted by a minor collection
+    /// * `gc_zeal` - [GC zeal mode](https://firefox-source-docs.mozilla.org/js/gc.html) for
+    ///   debugging, such as 2 to collect every `gc_zeal_frequency` allocations. Requires an
+    ///   engine built with zeal support
+    /// * `gc_zeal_frequency` - Number of allocations between collections of the zeal mode
+    /// * `allow_fs` - Allow loading module files from disk and importing the `fs` module
+    /// * `allow_net` - Allow network requests with `fetch`
+    /// * `allow_timers` - Allow timers such as `setTimeout`. Disallowing them also disables the
+    ///   macrotask queue
+    /// * `allow_modules` - Allow importing modules, including the standard modules. Modules
+    ///   registered with [register_module](PythonJSRuntime::register_module) are refused as well
+    /// * `allow_eval` - Allow compiling strings with `eval` and the `Function` constructor
+    /// * `jsx` - How JSX in imported `.tsx` and `.jsx` files is compiled, either `"preserve"`,
+    ///   `"react"` or `"react-jsx"`
+    /// * `jsx_factory` - Function called for JSX elements in the `"react"` mode, such as `"h"`
+    /// * `jsx_fragment` - Component of JSX fragments in the `"react"` mode, such as `"Fragment"`
+    /// * `jsx_import_source` - Module which the `"react-jsx"` mode imports `jsx-runtime` from,
+    ///   such as `"preact"`
+    ///
+    /// Imported modules are compiled according to their extension: `.ts`, `.mts`, `.cts` and
+    /// `.tsx` files as TypeScript if `typescript` is enabled, and `.jsx` files as JavaScript with
+    /// JSX. `.cjs` and `.cts` files are CommonJS modules, whose `module.exports` is their default
+    /// export.
+    ///
+    /// Disallowed globals throw an `Error` when accessed, and disallowed imports fail to resolve.
     ///
     /// # Returns
     /// A new PythonJSRuntime instance
+    ///
+    /// # Errors
+    /// Raises [ValueError] if `script`, `typescript` or `log_level` differ from those of the
+    /// first runtime of the process.
     #[new]
-    #[pyo3(signature = (microtask_queue = true, macrotask_queue = true, script = false, typescript = true, log_level = 0))]
+    #[pyo3(signature = (microtask_queue = true, macrotask_queue = true, script = false, typescript = true, log_level = 0, import_map = None, timeout = None, max_heap_bytes = None, max_nursery_bytes = None, gc_zeal = None, gc_zeal_frequency = 100, allow_fs = true, allow_net = true, allow_timers = true, allow_modules = true, allow_eval = true, jsx = "react", jsx_factory = None, jsx_fragment = None, jsx_import_source = None))]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         microtask_queue: bool,
         macrotask_queue: bool,
         script: bool,
         typescript: bool,
         log_level: u32,
+        import_map: Option<&Bound<'_, PyAny>>,
+        timeout: Option<f64>,
+        max_heap_bytes: Option<u32>,
+        max_nursery_bytes: Option<u32>,
+        gc_zeal: Option<u8>,
+        gc_zeal_frequency: u32,
+        allow_fs: bool,
+        allow_net: bool,
+        allow_timers: bool,
+        allow_modules: bool,
+        allow_eval: bool,
+        jsx: &str,
+        jsx_factory: Option<&str>,
+        jsx_fragment: Option<&str>,
+        jsx_import_source: Option<&str>,
     ) -> PyResult<Self> {
-        JS_RUNTIME_CONTEXT.with(|cell| {
+        let timeout = parse_timeout(timeout)?;
+        let typescript_options =
+            TypeScriptOptions::new("esnext", Some(jsx), "esnext", false, false)?
+                .with_jsx_pragmas(jsx_factory, jsx_fragment, jsx_import_source)?;
+        let capabilities = Capabilities {
+            fs: allow_fs,
+            net: allow_net,
+            timers: allow_timers,
+            modules: allow_modules,
+            eval: allow_eval,
+        };
+        if gc_zeal.is_some_and(|mode| mode > MAX_GC_ZEAL_MODE) {
+            return Err(PyValueError::new_err(format!(
+                "gc_zeal must be between 0 and {MAX_GC_ZEAL_MODE}"
+            )));
+        }
+        let import_map = import_map.map(parse_import_map).transpose()?.unwrap_or_default();
+        let generation = JS_RUNTIME_CONTEXT.with(|cell| {
             let mut maybe_runtime = cell.borrow_mut();
             if let Some(_rt) = &*maybe_runtime {
                 Err(PyRuntimeError::new_err(
@@ -115,49 +579,108 @@ impl PythonJSRuntime {
                 ))
             } else {
                 // First-time initialization for this thread.
-                CONFIG.get_or_init(|| Config {
-                    log_level: match log_level {
-                        1 => LogLevel::Info,
-                        2 => LogLevel::Warn,
-                        3 => LogLevel::Error,
-                        4 => LogLevel::Debug,
-                        _ => LogLevel::None,
-                    },
+                let log_level = match log_level {
+                    1 => LogLevel::Info,
+                    2 => LogLevel::Warn,
+                    3 => LogLevel::Error,
+                    4 => LogLevel::Debug,
+                    _ => LogLevel::None,
+                };
+                // The configuration is shared by the runtimes of all threads, and can only be
+                // set by the first one.
+                let config = CONFIG.get_or_init(|| Config {
+                    log_level,
                     script,
                     typescript,
                 });
-                let engine = JSEngine::init().to_runtime_err("Failed to initialize JS engine")?;
-                let engine_handle = engine.handle();
-                let runtime = RustRuntime::new(engine_handle);
+                if config.log_level != log_level
+                    || config.script != script
+                    || config.typescript != typescript
+                {
+                    return Err(PyValueError::new_err(
+                        "log_level, script and typescript are shared by all runtimes of the \
+                         process, and must be the same as for the first runtime",
+                    ));
+                }
+                let runtime = RustRuntime::new(engine_handle()?);
                 if runtime.cx().is_null() {
                     return Err(PyRuntimeError::new_err("Failed to create JS runtime"));
                 }
+                unsafe {
+                    install_interrupt_callback(runtime.cx());
+                    JS_AddExtraGCRootsTracer(
+                        runtime.cx(),
+                        Some(trace_persistent_roots),
+                        ptr::null_mut(),
+                    );
+                    if let Some(max_heap_bytes) = max_heap_bytes {
+                        JS_SetGCParameter(
+                            runtime.cx(),
+                            JSGCParamKey::JSGC_MAX_BYTES,
+                            max_heap_bytes,
+                        );
+                    }
+                    if let Some(max_nursery_bytes) = max_nursery_bytes {
+                        JS_SetGCParameter(
+                            runtime.cx(),
+                            JSGCParamKey::JSGC_MAX_NURSERY_BYTES,
+                            max_nursery_bytes,
+                        );
+                    }
+                    if let Some(gc_zeal) = gc_zeal {
+                        JS_SetGCZeal(runtime.cx(), gc_zeal, gc_zeal_frequency);
+                    }
+                }
                 let js_context = JSContext::from_runtime(&runtime);
                 let mut rt_builder = JSRuntimeBuilder::<_, _>::new();
                 if microtask_queue {
                     rt_builder = rt_builder.microtask_queue();
                 }
-                if macrotask_queue {
+                if macrotask_queue && capabilities.timers {
                     rt_builder = rt_builder.macrotask_queue();
                 }
 
+                let modules = ModuleMap::default();
+                let virtual_modules = VirtualFileSystem::default();
                 let builder = JSRuntimeContextBuilder {
                     runtime,
-                    engine,
+                    modules: modules.clone(),
+                    virtual_modules: virtual_modules.clone(),
+                    timeout,
+                    capabilities,
+                    typescript: typescript_options.clone(),
                     js_context,
                     js_runtime_builder: |js_context: &mut JSContext| {
                         rt_builder
-                            .modules(crate::loader::Loader::default())
-                            .standard_modules(Modules)
+                            .modules(Loader::new(
+                                modules,
+                                import_map,
+                                virtual_modules,
+                                capabilities,
+                                typescript_options,
+                            ))
+                            .standard_modules(capabilities.standard_modules())
                             .build(js_context)
                     },
                 };
                 let new_context = builder.build();
+                capabilities.restrict(new_context.borrow_js_runtime().cx())?;
                 *maybe_runtime = Some(new_context);
-                Ok(())
+                let generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
+                GENERATION.set(generation);
+                Ok(generation)
             }
         })?;
-        Ok(PythonJSRuntime)
+        if macrotask_queue && capabilities.timers {
+            if let Err(error) = with_js_cx(track_pending_work) {
+                close_runtime(generation)?;
+                return Err(error);
+            }
+        }
+        Ok(PythonJSRuntime {
+            generation,
+            owned: true,
+        })
     }
 
     /// Compiles and evaluates a script with a given filename, and returns its return value.
@@ -169,76 +692,392 @@ impl PythonJSRuntime {
     /// # Safety
     /// This method uses unsafe code to transmute the JavaScript value's lifetime to 'static.
     /// This is safe because:
-    /// 1. The JSRuntimeContext is stored in a thread_local and lives until the program ends
-    /// 2. The value returned by this function cannot outlive its creating context
-    /// 3. The runtime can only be dropped when the program ends
-    /// 4. JavaScript values are only valid within their creating context
+    /// 1. The returned value is registered as a persistent root, so the GC keeps it alive and
+    ///    updates it when the value is moved
+    /// 2. The returned value raises [JSRuntimeClosedError] once its runtime has been closed
     ///
     /// # Arguments
     /// * `source` - JavaScript source code to execute
     /// * `filename` - Name to use in error messages (defaults to "inline.js")
+    /// * `timeout` - Time limit in seconds, which defaults to the timeout of the runtime
     ///
     /// # Returns
     /// The result of evaluating the JavaScript code as a JSValue
     ///
     /// # Errors
-    /// Returns an error if the compilation or evaluation fails
-    #[pyo3(signature = (source, filename = "inline.js"))]
-    pub fn compile_and_evaluate_script(&self, source: &str, filename: &str) -> PyResult<PyJSValue> {
-        with_js_cx(|cx| {
-            let result = Script::compile_and_evaluate(cx, filename.as_ref(), source)
-                .map_err(|e| PyRuntimeError::new_err(e.format(cx)))?;
-
-            Ok(PyJSValue::from(result.extend_lifetime()))
+    /// Raises a [JSError] subclass if the compilation or evaluation fails,
+    /// and [JSTimeoutError] if the evaluation exceeds the time limit
+    #[pyo3(signature = (source, filename = "inline.js", timeout = None))]
+    pub fn compile_and_evaluate_script(
+        &self,
+        source: &str,
+        filename: &str,
+        timeout: Option<f64>,
+    ) -> PyResult<PyJSValue> {
+        self.check_open()?;
+        with_execution_timeout(timeout, || {
+            with_js_cx(|cx| {
+                let result =
+                    Script::compile_and_evaluate(cx, filename.as_ref(), source).to_js_err(cx)?;
+
+                Ok(PyJSValue::from(result.extend_lifetime()))
+            })
         })
     }
 
     /// Compiles and evaluates a [Module] with the given source and filename.
     /// On success, returns the compiled module object and a promise. The promise resolves with the return value of the module.
     /// The promise is a byproduct of enabling top-level await.
-    #[pyo3(signature = (source, filename = "inline.js", path = Some("inline.js")))]
+    ///
+    /// Synchronous evaluation is limited to `timeout` seconds, which defaults to the timeout
+    /// of the runtime, after which a [JSTimeoutError] is raised.
+    #[pyo3(signature = (source, filename = "inline.js", path = Some("inline.js"), timeout = None))]
     pub fn compile_and_evaluate_module(
         &self,
         source: &str,
         filename: &str,
         path: Option<&str>,
+        timeout: Option<f64>,
     ) -> PyResult<(PyJSModule, Option<PyJSPromise>)> {
-        with_js_cx(|cx| {
-            let (module, promise) =
-                Module::compile_and_evaluate(cx, filename, path.map(AsRef::as_ref), source)
-                    .map_err(|e| PyRuntimeError::new_err(e.format(cx)))?;
-
-            Ok((
-                module.extend_lifetime().into(),
-                promise.map(|p| p.extend_lifetime().into()),
-            ))
+        self.check_open()?;
+        with_execution_timeout(timeout, || {
+            with_js_cx(|cx| {
+                let (module, promise) =
+                    Module::compile_and_evaluate(cx, filename, path.map(AsRef::as_ref), source)
+                        .to_js_err(cx)?;
+
+                Ok((
+                    module.extend_lifetime().into(),
+                    promise.map(|p| p.extend_lifetime().into()),
+                ))
+            })
+        })
+    }
+
+    /// Registers an in-memory module which can be imported as if it were a file at `specifier`.
+    ///
+    /// Relative imports within the module are resolved relative to `specifier`, and may refer to
+    /// other registered modules or files on disk.
+    ///
+    /// # Arguments
+    /// * `specifier` - Path under which the module is imported, such as `gen/util.js`
+    /// * `source` - Source code of the module
+    /// * `kind` - Language of the source: `"js"`, `"json"` or `"ts"`, which is compiled with the
+    ///   TypeScript options of the runtime
+    ///
+    /// # Errors
+    /// Raises [ValueError] if the kind is unknown, the source cannot be compiled
+    /// or a module is already registered for the specifier.
+    #[pyo3(signature = (specifier, source, kind = "js"))]
+    pub fn register_module(&self, specifier: &str, source: String, kind: &str) -> PyResult<()> {
+        self.check_open()?;
+        let kind = kind.parse::<VirtualModuleKind>().map_err(PyValueError::new_err)?;
+        let path = normalize(Path::new(specifier));
+        let source = kind
+            .to_javascript(&path, source, &typescript_options())
+            .map_err(PyValueError::new_err)?;
+        let key = ModuleKey {
+            specifier: Specifier::Path(path.clone()),
+            kind: ModuleType::JavaScript,
+        };
+        let defined = with_modules(|modules| modules.contains(&key));
+        with_virtual_modules(|modules| {
+            if !defined && modules.register(&path, source) {
+                Ok(())
+            } else {
+                Err(PyValueError::new_err(format!(
+                    "Module \"{specifier}\" is already registered"
+                )))
+            }
         })
     }
 
-    /// TODO: Full support for typescript
-    #[pyo3(signature = (source, filename = "inline.js"))]
+    /// Defines a synthetic module named `name`, whose named exports are the items of `exports`.
+    ///
+    /// The module is importable from JavaScript without any file, e.g. with
+    /// `import { query } from "py:db"`. Values are converted with [JSValue::from_py] when the
+    /// module is defined, so callables become JavaScript functions, and later changes to
+    /// `exports` are not reflected in the module.
+    ///
+    /// # Errors
+    /// Raises [TypeError] if a key of `exports` is not a string and [ValueError] if a module is
+    /// already registered under `name`.
+    pub fn define_synthetic_module(&self, name: &str, exports: &Bound<'_, PyDict>) -> PyResult<()> {
+        self.check_open()?;
+        let path = normalize(Path::new(name));
+        let registered = with_virtual_modules(|modules| modules.locate(&path).is_none())
+            && with_modules(|modules| {
+                with_js_cx(|cx| modules.define_synthetic(cx, &path, exports))
+            })?;
+        if registered {
+            Ok(())
+        } else {
+            Err(PyValueError::new_err(format!(
+                "Module \"{name}\" is already registered"
+            )))
+        }
+    }
+
+    /// Compiles TypeScript into JavaScript.
+    ///
+    /// # Arguments
+    /// * `source` - TypeScript source to compile
+    /// * `filename` - Name of the file, used in diagnostics and the source map
+    /// * `target` - ECMAScript version of the output, from `"es2015"` to `"es2022"` or `"esnext"`.
+    ///   Syntax is not compiled down, so sources using newer syntax than the target are rejected
+    /// * `jsx` - How JSX is compiled, either `"preserve"`, `"react"` or `"react-jsx"`.
+    ///   JSX is only parsed if this is set.
+    /// * `module` - Module format of the output, either `"esnext"` or `"commonjs"`
+    /// * `strip_only` - Only allow TypeScript syntax which can be erased, rejecting enums,
+    ///   namespaces and parameter properties
+    /// * `emit_decorators` - Compile legacy (experimental) decorators and emit their metadata.
+    ///   Otherwise decorators are kept as is
+    /// * `jsx_factory` - Function called for JSX elements in the `"react"` mode, such as `"h"`
+    /// * `jsx_fragment` - Component of JSX fragments in the `"react"` mode, such as `"Fragment"`
+    /// * `jsx_import_source` - Module which the `"react-jsx"` mode imports `jsx-runtime` from,
+    ///   such as `"preact"`
+    ///
+    /// # Returns
+    /// The compiled JavaScript and its source map.
+    ///
+    /// # Errors
+    /// Raises [ValueError] for an unknown option value or a JSX pragma which does not apply to
+    /// the JSX mode, and [TypeScriptError] with the diagnostics of the compilation if the source
+    /// cannot be compiled.
+    #[pyo3(signature = (source, filename = "inline.js", target = "esnext", jsx = None, module = "esnext", strip_only = false, emit_decorators = false, jsx_factory = None, jsx_fragment = None, jsx_import_source = None))]
+    #[allow(clippy::too_many_arguments)]
     pub fn compile_typescript(
         &self,
         source: &str,
         filename: &str,
+        target: &str,
+        jsx: Option<&str>,
+        module: &str,
+        strip_only: bool,
+        emit_decorators: bool,
+        jsx_factory: Option<&str>,
+        jsx_fragment: Option<&str>,
+        jsx_import_source: Option<&str>,
     ) -> PyResult<(String, PySourceMap)> {
-        let (compiled_js, sourcemap) = js_runtime::typescript::compile_typescript(filename, source)
-            .to_runtime_err("Failed to compile TypeScript")?;
+        let options = TypeScriptOptions::new(target, jsx, module, strip_only, emit_decorators)?
+            .with_jsx_pragmas(jsx_factory, jsx_fragment, jsx_import_source)?;
+        let (compiled_js, sourcemap) =
+            compile_typescript(filename, source, &options).map_err(typescript_error)?;
         Ok((compiled_js, PySourceMap::from(sourcemap)))
     }
 
-    pub fn run_event_loop(&self) -> PyResult<()> {
-        with_js_runtime(|rt| {
-            TOKIO_RT.with(|tokio_rt| {
-                tokio_rt.block_on(async {
-                    rt.run_event_loop()
-                        .await
-                        .map_err(|e| PyRuntimeError::new_err(e.map(|inner| inner.format(rt.cx()))))
-                })
+    /// Compiles TypeScript and evaluates the output as a script or a module.
+    ///
+    /// The source is compiled with the TypeScript options of the runtime, and JSX is only
+    /// compiled if `filename` ends with `.tsx`. The source map of the compiled JavaScript is
+    /// registered under `filename`, so the locations of errors raised by the evaluation refer to
+    /// lines of the TypeScript source.
+    ///
+    /// # Arguments
+    /// * `source` - TypeScript source code to execute
+    /// * `filename` - Name of the file, used in error messages and to register the source map,
+    ///   which defaults to a unique name such as `inline-1.ts`
+    /// * `as_module` - Whether to evaluate the source as a module rather than a script
+    /// * `timeout` - Time limit in seconds, which defaults to the timeout of the runtime
+    ///
+    /// # Returns
+    /// The result of the script as a JSValue, or the module and the promise of its evaluation
+    /// as returned by `compile_and_evaluate_module`.
+    ///
+    /// # Errors
+    /// Raises [TypeScriptError] if the source cannot be compiled, a [JSError] subclass if the
+    /// evaluation fails, and [JSTimeoutError] if the evaluation exceeds the time limit
+    #[pyo3(signature = (source, filename = None, as_module = false, timeout = None))]
+    pub fn compile_and_evaluate_typescript(
+        &self,
+        py: Python<'_>,
+        source: &str,
+        filename: Option<String>,
+        as_module: bool,
+        timeout: Option<f64>,
+    ) -> PyResult<PyObject> {
+        self.check_open()?;
+        // Source maps are registered by filename, so each source needs a name of its own.
+        let filename = filename.unwrap_or_else(|| {
+            let number = NEXT_INLINE_TYPESCRIPT.fetch_add(1, Ordering::Relaxed);
+            format!("inline-{number}.ts")
+        });
+        let filename = filename.as_str();
+        let path = Path::new(filename);
+        let jsx = path.extension().is_some_and(|extension| extension == "tsx");
+        let options = typescript_options().for_source(jsx, ModuleFormat::EsNext);
+        let (script, sourcemap) =
+            compile_typescript(filename, source, &options).map_err(typescript_error)?;
+        js_runtime::cache::map::save_sourcemap(path, sourcemap);
+
+        with_execution_timeout(timeout, || {
+            with_js_cx(|cx| {
+                if as_module {
+                    let (module, promise) =
+                        Module::compile_and_evaluate(cx, filename, Some(path), &script)
+                            .to_js_err(cx)?;
+                    let module = PyJSModule::from(module.extend_lifetime());
+                    let promise = promise.map(|p| PyJSPromise::from(p.extend_lifetime()));
+                    Ok((module, promise).into_pyobject(py)?.into_any().unbind())
+                } else {
+                    let result = Script::compile_and_evaluate(cx, filename.as_ref(), &script)
+                        .to_js_err(cx)?;
+                    let result = PyJSValue::from(result.extend_lifetime());
+                    Ok(result.into_pyobject(py)?.into_any().unbind())
+                }
             })
         })
     }
 
+    /// Runs the event loop until it has no pending work left.
+    ///
+    /// Each tick runs the ready futures, one macrotask such as a timer callback and then
+    /// the microtasks queued by it.
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum time to run for, in seconds
+    /// * `max_ticks` - Maximum number of ticks of the event loop to run
+    ///
+    /// # Returns
+    /// `EventLoopStatus.Idle` if the event loop has no pending work left, or
+    /// `EventLoopStatus.TimedOut` if the timeout or tick limit was reached first. Pending work
+    /// is kept and continues on the next run.
+    ///
+    /// # Errors
+    /// Raises [ValueError] for an invalid timeout, [RuntimeError] if the event loop is already
+    /// running, a [JSError] subclass if a task of the event loop throws, and [JSTimeoutError]
+    /// if a task is still running when the timeout elapses.
+    #[pyo3(signature = (timeout = None, max_ticks = None))]
+    pub fn run_event_loop(
+        &self,
+        timeout: Option<f64>,
+        max_ticks: Option<u64>,
+    ) -> PyResult<PyEventLoopStatus> {
+        self.check_open()?;
+        let timeout = parse_timeout(timeout)?;
+        run_event_loop_bounded(timeout, max_ticks, || false)
+            .unwrap_or_else(|| Err(PyRuntimeError::new_err("Event loop is already running")))
+    }
+
+    /// Runs the jobs of the microtask queue, such as promise reactions, until it is empty.
+    /// Timers and futures are not run.
+    ///
+    /// # Errors
+    /// Raises [RuntimeError] if the event loop is already running, and a [JSError] subclass
+    /// if a job throws.
+    pub fn run_microtasks(&self) -> PyResult<()> {
+        self.check_open()?;
+        enter_event_loop(|rt, _| {
+            let cx = rt.cx();
+            // Delegates to the microtask queue installed by the runtime.
+            unsafe { mozjs::jsapi::js::RunJobs(cx.as_ptr()) };
+            match ErrorReport::new_with_exception_stack(cx) {
+                Some(report) => Err(js_error(cx, report, false)),
+                None => Ok(()),
+            }
+        })
+        .unwrap_or_else(|| Err(PyRuntimeError::new_err("Event loop is already running")))
+    }
+
+    /// Runs the event loop until the promise is settled.
+    ///
+    /// # Arguments
+    /// * `promise` - The promise to wait for
+    /// * `timeout` - Maximum time to run for, in seconds
+    ///
+    /// # Returns
+    /// `EventLoopStatus.PromiseSettled` once the promise is settled, `EventLoopStatus.Idle` if
+    /// the event loop ran out of work while the promise is still pending, or
+    /// `EventLoopStatus.TimedOut` if the timeout was reached first.
+    ///
+    /// # Errors
+    /// Raises [ValueError] for an invalid timeout, [RuntimeError] if the event loop is already
+    /// running, a [JSError] subclass if a task of the event loop throws, and [JSTimeoutError]
+    /// if a task is still running when the timeout elapses.
+    #[pyo3(signature = (promise, timeout = None))]
+    pub fn run_until_settled(
+        &self,
+        promise: &Bound<'_, PyJSPromise>,
+        timeout: Option<f64>,
+    ) -> PyResult<PyEventLoopStatus> {
+        self.check_open()?;
+        let timeout = parse_timeout(timeout)?;
+        let promise = promise.borrow();
+        let promise = promise.0.get()?;
+        let settled = || !matches!(promise.state(), mozjs::jsapi::PromiseState::Pending);
+        run_event_loop_bounded(timeout, None, settled)
+            .unwrap_or_else(|| Err(PyRuntimeError::new_err("Event loop is already running")))
+    }
+
+    /// Creates a new [Realm](PyRealm) with its own global object, isolated from the globals of
+    /// the runtime and of other realms.
+    ///
+    /// The realm has the same capabilities as the runtime, but no timers and no modules.
+    pub fn new_realm(&self) -> PyResult<PyRealm> {
+        self.check_open()?;
+        let capabilities = JS_RUNTIME_CONTEXT.with(|cell| {
+            let maybe_runtime = cell.borrow();
+            match &*maybe_runtime {
+                Some(rt) => *rt.borrow_capabilities(),
+                None => panic!("JavaScript runtime not initialized for current thread"),
+            }
+        });
+        PyRealm::new(capabilities)
+    }
+
+    /// Returns a snapshot of the memory usage and garbage collection counts of the heap.
+    pub fn heap_stats(&self) -> PyResult<PyHeapStats> {
+        self.check_open()?;
+        Ok(with_js_cx(|cx| unsafe { PyHeapStats::collect(cx.as_ptr()) }))
+    }
+
+    /// Runs a full, non-incremental garbage collection.
+    ///
+    /// # Arguments
+    /// * `shrinking` - Also release unused memory back to the operating system and compact
+    ///   the heap where possible
+    #[pyo3(signature = (shrinking = false))]
+    pub fn gc(&self, shrinking: bool) -> PyResult<()> {
+        self.check_open()?;
+        with_js_cx(|cx| unsafe {
+            if shrinking {
+                PrepareForFullGC(cx.as_ptr());
+                NonIncrementalGC(cx.as_ptr(), GCOptions::Shrink, GCReason::API);
+            } else {
+                JS_GC(cx.as_ptr(), GCReason::API);
+            }
+        });
+        Ok(())
+    }
+
+    /// Closes the runtime, releasing the JavaScript engine resources of the current thread.
+    ///
+    /// Using the runtime or any value created within it afterwards raises
+    /// [JSRuntimeClosedError], and a new runtime can be created on this thread. Closing a
+    /// runtime which is already closed does nothing.
+    ///
+    /// # Errors
+    /// Raises [RuntimeError] if called while JavaScript code of the runtime is running, such
+    /// as from a Python callback.
+    pub fn close(&self) -> PyResult<()> {
+        close_runtime(self.generation)
+    }
+
+    /// Whether the runtime has been closed.
+    #[getter]
+    pub fn closed(&self) -> bool {
+        self.check_open().is_err()
+    }
+
+    pub fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    pub fn __exit__(&self, _args: &Bound<'_, PyTuple>) -> PyResult<()> {
+        self.close()
+    }
+
     #[staticmethod]
     fn is_initialized() -> bool {
         JS_RUNTIME_CONTEXT.with(|cell| {
@@ -248,17 +1087,22 @@ impl PythonJSRuntime {
         })
     }
     
+    /// Returns another reference to the runtime of the current thread, which does not close the
+    /// runtime when it is dropped, unlike the object returned when creating the runtime.
     #[staticmethod]
     fn empty() -> Self {
-        PythonJSRuntime
+        PythonJSRuntime {
+            generation: GENERATION.get(),
+            owned: false,
+        }
     }
 }
 
 impl Drop for PythonJSRuntime {
     fn drop(&mut self) {
-        JS_RUNTIME_CONTEXT.with(|cell| {
-            let mut context = cell.borrow_mut();
-            *context = None;
-        });
+        if self.owned {
+            // A runtime which is still running code stays open, as it cannot be dropped yet.
+            let _ = close_runtime(self.generation);
+        }
     }
 }
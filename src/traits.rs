@@ -1,7 +1,18 @@
 use ion::{
-    BigInt as JSBigInt, Function as JSFunction, Object as JSObject, OwnedKey, Promise as JSPromise,
-    Value as JSValue, module::Module as JSModule,
+    BigInt as JSBigInt, Context, Function as JSFunction, Local, Object as JSObject, OwnedKey,
+    Promise as JSPromise, Value as JSValue, module::Module as JSModule,
 };
+use mozjs::gc::{GCMethods, RootKind};
+use mozjs::glue::{
+    CallBigIntTracer, CallFunctionTracer, CallIdTracer, CallObjectTracer, CallSymbolTracer,
+    CallValueTracer,
+};
+use mozjs::jsapi::{
+    BigInt, Heap, JSFunction as RawFunction, JSObject as RawObject, JSTracer, PropertyKey as RawId,
+    Symbol, Value as RawValue,
+};
+use std::fmt::{self, Debug, Formatter};
+use std::ptr;
 
 /// A trait for extending the lifetime of JavaScript values.
 ///
@@ -12,8 +23,8 @@ use ion::{
 /// 1. The JavaScript runtime is guaranteed to outlive all values derived from it.
 /// 2. The values are never used after the runtime is dropped.
 ///
-/// The implementation in this crate relies on the fact that the JavaScript runtime
-/// is stored in a thread-local and is only dropped when the program exits.
+/// Values held by Python wrappers satisfy this through [Handle](crate::handle::Handle), which
+/// registers them as persistent roots and refuses access once their runtime has been closed.
 ///
 /// Using this trait incorrectly can lead to use-after-free and other memory safety issues.
 pub trait ExtendLifetime {
@@ -23,9 +34,8 @@ pub trait ExtendLifetime {
     ///
     /// # Safety
     ///
-    /// This method is safe to use ONLY because the JavaScript runtime context
-    /// lives for the entire duration of the program, and JavaScript values
-    /// are only valid within their creating context.
+    /// This method is safe to use ONLY if the value is rooted for as long as it is used,
+    /// and is not used after its runtime has been closed.
     fn extend_lifetime(self) -> Self::Output;
 }
 
@@ -98,3 +108,148 @@ impl<'a> ExtendLifetime for JSPromise<'a> {
         unsafe { std::mem::transmute::<JSPromise<'a>, Self::Output>(self) }
     }
 }
+
+/// Location of a GC thing held in the persistent root registry of the runtime.
+#[derive(Clone, Copy, Debug)]
+pub enum RootSlot {
+    Value(*mut RawValue),
+    Object(*mut *mut RawObject),
+    Function(*mut *mut RawFunction),
+    BigInt(*mut *mut BigInt),
+    Symbol(*mut *mut Symbol),
+    Id(*mut RawId),
+}
+
+impl RootSlot {
+    /// Traces the GC thing in the slot, which updates the slot if the GC moves the thing.
+    ///
+    /// # Safety
+    /// The slot must be valid, and `trc` must be the tracer of a GC of its runtime.
+    pub unsafe fn trace(self, trc: *mut JSTracer) {
+        let name = c"persistent root".as_ptr();
+        // `Heap<T>` has the layout of `T`, and tracing does not need its write barriers.
+        unsafe {
+            match self {
+                RootSlot::Value(slot) => CallValueTracer(trc, slot.cast::<Heap<_>>(), name),
+                RootSlot::Object(slot) => CallObjectTracer(trc, slot.cast::<Heap<_>>(), name),
+                RootSlot::Function(slot) => CallFunctionTracer(trc, slot.cast::<Heap<_>>(), name),
+                RootSlot::BigInt(slot) => CallBigIntTracer(trc, slot.cast::<Heap<_>>(), name),
+                RootSlot::Symbol(slot) => CallSymbolTracer(trc, slot.cast::<Heap<_>>(), name),
+                RootSlot::Id(slot) => CallIdTracer(trc, slot.cast::<Heap<_>>(), name),
+            }
+        }
+    }
+}
+
+/// GC thing of a value copied into an owned [Heap], which keeps its address while the value is
+/// moved and is traced through the persistent root registry.
+pub enum HeapRoot {
+    Value(Box<Heap<RawValue>>),
+    Object(Box<Heap<*mut RawObject>>),
+    Function(Box<Heap<*mut RawFunction>>),
+    BigInt(Box<Heap<*mut BigInt>>),
+    Symbol(Box<Heap<*mut Symbol>>),
+    Id(Box<Heap<RawId>>),
+}
+
+impl HeapRoot {
+    /// Returns the location of the GC thing in the heap.
+    pub fn slot(&self) -> RootSlot {
+        match self {
+            HeapRoot::Value(heap) => RootSlot::Value(heap.get_unsafe()),
+            HeapRoot::Object(heap) => RootSlot::Object(heap.get_unsafe()),
+            HeapRoot::Function(heap) => RootSlot::Function(heap.get_unsafe()),
+            HeapRoot::BigInt(heap) => RootSlot::BigInt(heap.get_unsafe()),
+            HeapRoot::Symbol(heap) => RootSlot::Symbol(heap.get_unsafe()),
+            HeapRoot::Id(heap) => RootSlot::Id(heap.get_unsafe()),
+        }
+    }
+}
+
+impl Debug for HeapRoot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("HeapRoot").field(&self.slot()).finish()
+    }
+}
+
+/// Copies the GC thing of a [Local] into an owned [Heap], and points the [Local] at the copy.
+///
+/// Any [Local] can be rooted this way, including those which only borrow a handle.
+fn root_in_heap<T: GCMethods + RootKind + Copy>(local: &mut Local<'_, T>) -> Box<Heap<T>> {
+    let heap = Heap::boxed(local.get());
+    // SAFETY: The heap is boxed, so it is not moved, and it is owned by the same handle as the
+    // value, which only drops it once the value no longer points at it.
+    *local = Local::from_heap(unsafe { &*ptr::from_ref::<Heap<T>>(&heap) });
+    heap
+}
+
+/// A trait for JavaScript values which can be held in the persistent root registry.
+pub trait Rootable {
+    /// Copies the GC thing of the value into a [HeapRoot], and points the value at the copy.
+    /// Returns [None] if the value has no GC thing.
+    fn root(&mut self) -> Option<HeapRoot>;
+
+    /// Points the value at a copy of its GC thing rooted in `cx`, so it stays valid once its
+    /// [HeapRoot] is dropped.
+    fn reroot(&mut self, cx: &Context);
+}
+
+/// Implements [Rootable] for a wrapper of a [Local], whose GC thing is held as a [HeapRoot]
+/// variant.
+macro_rules! rootable {
+    ($($ty:ident)::+, $variant:ident) => {
+        impl Rootable for $($ty)::+<'_> {
+            fn root(&mut self) -> Option<HeapRoot> {
+                Some(HeapRoot::$variant(root_in_heap(self)))
+            }
+
+            fn reroot(&mut self, cx: &Context) {
+                let rooted = $($ty)::+::from(cx.root(self.get()));
+                // SAFETY: The value is rooted in the context, which outlives the value.
+                *self = unsafe { std::mem::transmute::<$($ty)::+<'_>, Self>(rooted) };
+            }
+        }
+    };
+}
+
+rootable!(JSValue, Value);
+rootable!(JSObject, Object);
+rootable!(JSPromise, Object);
+rootable!(JSFunction, Function);
+rootable!(JSBigInt, BigInt);
+rootable!(ion::Symbol, Symbol);
+rootable!(ion::PropertyKey, Id);
+
+impl Rootable for JSModule<'_> {
+    fn root(&mut self) -> Option<HeapRoot> {
+        self.0.root()
+    }
+
+    fn reroot(&mut self, cx: &Context) {
+        self.0.reroot(cx);
+    }
+}
+
+impl Rootable for OwnedKey<'_> {
+    fn root(&mut self) -> Option<HeapRoot> {
+        match self {
+            OwnedKey::Symbol(symbol) => symbol.root(),
+            _ => None,
+        }
+    }
+
+    fn reroot(&mut self, cx: &Context) {
+        if let OwnedKey::Symbol(symbol) = self {
+            symbol.reroot(cx);
+        }
+    }
+}
+
+impl Rootable for ion::PropertyDescriptor<'_> {
+    /// Descriptors hold several GC things, which are traced through the rooted descriptor.
+    fn root(&mut self) -> Option<HeapRoot> {
+        None
+    }
+
+    fn reroot(&mut self, _: &Context) {}
+}
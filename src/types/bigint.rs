@@ -1,4 +1,5 @@
 use crate::errors::ErrorHandling;
+use crate::handle::Handle;
 use crate::runtime::with_js_cx;
 use crate::traits::ExtendLifetime;
 use ion::format::Config;
@@ -9,17 +10,18 @@ use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
 
 #[gen_stub_pyclass]
 #[pyclass(unsendable, name = "JSBigInt", module = "js_exec.js_exec")]
-pub struct PyJSBigInt(pub JSBigInt<'static>);
+pub struct PyJSBigInt(pub Handle<JSBigInt<'static>>);
 
-impl From<PyJSBigInt> for JSBigInt<'static> {
-    fn from(value: PyJSBigInt) -> Self {
-        value.0
+impl TryFrom<PyJSBigInt> for JSBigInt<'static> {
+    type Error = PyErr;
+    fn try_from(value: PyJSBigInt) -> PyResult<Self> {
+        value.0.into_inner()
     }
 }
 
 impl From<JSBigInt<'static>> for PyJSBigInt {
     fn from(value: JSBigInt<'static>) -> Self {
-        PyJSBigInt(value)
+        PyJSBigInt(Handle::new(value))
     }
 }
 
@@ -73,32 +75,32 @@ impl PyJSBigInt {
     }
 
     /// Converts a [JSBigInt] to a 64-bit signed integer if possible.
-    pub fn to_i64(&self) -> Option<i64> {
-        self.0.to_i64()
+    pub fn to_i64(&self) -> PyResult<Option<i64>> {
+        Ok(self.0.get()?.to_i64())
     }
 
     /// Converts a [JSBigInt] to a 64-bit unsigned integer if possible.
-    pub fn to_u64(&self) -> Option<u64> {
-        self.0.to_u64()
+    pub fn to_u64(&self) -> PyResult<Option<u64>> {
+        Ok(self.0.get()?.to_u64())
     }
 
     /// Converts a [JSBigInt] to a double.
     /// Returns `Infinity` or `-Infinity` if it does not fit in a double.
-    pub fn to_f64(&self) -> f64 {
-        self.0.to_f64()
+    pub fn to_f64(&self) -> PyResult<f64> {
+        Ok(self.0.get()?.to_f64())
     }
 
     /// Converts a [JSBigInt] to a double if it fits in a double.
-    pub fn fits_f64(&self) -> Option<f64> {
-        self.0.fits_f64()
+    pub fn fits_f64(&self) -> PyResult<Option<f64>> {
+        Ok(self.0.get()?.fits_f64())
     }
 
     /// Converts a [JSBigInt] to a string.
     /// Returns `None` if the radix is not within the range (2..=36).
     pub fn to_string(&self, radix: u8) -> PyResult<Option<String>> {
+        let bigint = self.0.get()?;
         with_js_cx(|cx| {
-            let result = self
-                .0
+            let result = bigint
                 .to_string(cx, radix)
                 .map(|s| s.to_owned(cx))
                 .transpose()
@@ -108,26 +110,27 @@ impl PyJSBigInt {
     }
 
     /// Checks if the [JSBigInt] is negative.
-    pub fn is_negative(&self) -> bool {
-        self.0.is_negative()
+    pub fn is_negative(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.is_negative())
     }
 
     // handle methods
-    pub fn is_null(&self) -> bool {
-        self.0.handle().is_null()
+    pub fn is_null(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_null())
     }
 
-    pub fn is_aligned(&self) -> bool {
-        self.0.handle().is_aligned()
+    pub fn is_aligned(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_aligned())
     }
 
-    pub fn __str__(&self) -> String {
-        with_js_cx(|cx| {
-            format_primitive(cx, Config::default(), &JSValue::bigint(cx, &self.0)).to_string()
-        })
+    pub fn __str__(&self) -> PyResult<String> {
+        let bigint = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            format_primitive(cx, Config::default(), &JSValue::bigint(cx, bigint)).to_string()
+        }))
     }
 
-    pub fn __repr__(&self) -> String {
-        format!("JSBigInt({})", self.__str__())
+    pub fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("JSBigInt({})", self.__str__()?))
     }
 }
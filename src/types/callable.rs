@@ -1,10 +1,19 @@
+use crate::conversions::{DEFAULT_MAX_DEPTH, JSToPy, PyConversionFallback, PyToJS};
+use crate::errors::to_js_exception;
 use crate::types::value::PyJSValue;
+use ion::flags::PropertyFlags;
+use ion::{Arguments, Context, Function as JSFunction, ResultExc, Value as JSValue};
 use maplit::hashset;
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
 use pyo3::{Bound, FromPyObject, PyAny, PyResult};
 use pyo3_stub_gen::{PyStubType, TypeInfo};
 
+/// Marker for callables accepting any number of arguments, i.e. `typing.Callable[..., T]`.
+pub struct VarArgs;
+
 // Generic callable wrapper that encodes signature information in type parameters
-#[allow(dead_code)] // for now
 pub struct PyCallable<'py, Args, Ret> {
     pub inner: Bound<'py, PyAny>,
     _args: std::marker::PhantomData<Args>,
@@ -13,6 +22,12 @@ pub struct PyCallable<'py, Args, Ret> {
 
 impl<'py, Args, Res> FromPyObject<'py> for PyCallable<'py, Args, Res> {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if !ob.is_callable() {
+            return Err(PyTypeError::new_err(format!(
+                "'{}' object is not callable",
+                ob.get_type().name()?
+            )));
+        }
         Ok(PyCallable {
             inner: ob.clone(),
             _args: std::marker::PhantomData,
@@ -21,6 +36,46 @@ impl<'py, Args, Res> FromPyObject<'py> for PyCallable<'py, Args, Res> {
     }
 }
 
+impl<Args, Ret> PyCallable<'_, Args, Ret> {
+    /// Returns the `__name__` of the callable, if it has one.
+    pub fn name(&self) -> Option<String> {
+        self.inner
+            .getattr("__name__")
+            .and_then(|name| name.extract())
+            .ok()
+    }
+
+    /// Creates a [JSFunction] which calls back into the Python callable.
+    ///
+    /// Arguments are converted with [JSToPy], keeping values without a Python counterpart
+    /// as [PyJSValue]s, and the return value is converted with [PyToJS].
+    /// Python exceptions are rethrown as JavaScript errors.
+    ///
+    /// The callable is owned by the function's closure, so it stays alive for as long as
+    /// the JavaScript function is reachable and is released when the function is finalized.
+    pub fn to_function<'cx>(&self, cx: &'cx Context, name: &str, nargs: u32) -> JSFunction<'cx> {
+        let callable = self.inner.clone().unbind();
+        let closure = move |args: &mut Arguments<'_>| -> ResultExc<JSValue<'_>> {
+            let cx = args.cx();
+            Python::with_gil(|py| {
+                let mut converter =
+                    JSToPy::new(cx, py, DEFAULT_MAX_DEPTH, PyConversionFallback::Wrap);
+                let values = (0..args.len())
+                    .filter_map(|index| args.value(index))
+                    .map(|value| converter.convert(value))
+                    .collect::<PyResult<Vec<_>>>()
+                    .map_err(|err| to_js_exception(err))?;
+
+                PyTuple::new(py, values)
+                    .and_then(|values| callable.bind(py).call1(values))
+                    .and_then(|result| PyToJS::new(cx, DEFAULT_MAX_DEPTH).convert(&result))
+                    .map_err(|err| to_js_exception(err))
+            })
+        };
+        JSFunction::from_closure(cx, name, Box::new(closure), nargs, PropertyFlags::empty())
+    }
+}
+
 impl PyStubType for PyCallable<'_, (), String> {
     fn type_output() -> TypeInfo {
         TypeInfo {
@@ -47,3 +102,12 @@ impl PyStubType for PyCallable<'_, PyJSValue, PyResult<PyJSValue>> {
         }
     }
 }
+
+impl PyStubType for PyCallable<'_, VarArgs, PyObject> {
+    fn type_output() -> TypeInfo {
+        TypeInfo {
+            name: "typing.Callable[..., typing.Any]".to_string(),
+            import: hashset! { "typing".into() },
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
+
+/// A problem found while compiling TypeScript.
+#[gen_stub_pyclass]
+#[pyclass(
+    frozen,
+    get_all,
+    name = "TypeScriptDiagnostic",
+    module = "js_exec.js_exec"
+)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PyTypeScriptDiagnostic {
+    /// Code of the matching error of `tsc`, such as `TS1005`, or [None] if it has none.
+    pub code: Option<String>,
+    /// Description of the problem.
+    pub message: String,
+    /// File the problem was found in.
+    pub filename: String,
+    /// 1-based line number.
+    pub line: u32,
+    /// 1-based column number.
+    pub column: u32,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyTypeScriptDiagnostic {
+    pub fn __str__(&self) -> String {
+        let location = format!("{}:{}:{}", self.filename, self.line, self.column);
+        match &self.code {
+            Some(code) => format!("{location} - error {code}: {}", self.message),
+            None => format!("{location} - error: {}", self.message),
+        }
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("TypeScriptDiagnostic({})", self.__str__())
+    }
+}
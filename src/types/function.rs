@@ -1,29 +1,32 @@
-use crate::errors::ErrorHandling;
-use crate::runtime::{with_js_cx, with_js_runtime};
+use crate::errors::{ErrorHandling, JSErrorHandling};
+use crate::handle::Handle;
+use crate::runtime::{with_execution_timeout, with_js_cx, with_js_runtime};
 use crate::traits::ExtendLifetime;
+use crate::types::callable::{PyCallable, VarArgs};
 use crate::types::object::PyJSObject;
-use crate::types::value::PyJSValue;
+use crate::types::value::{JSValueLike, PyJSValue};
 use ion::Function as JSFunction;
 use ion::format::Config;
 use ion::format::function::format_function;
-use pyo3::{PyErr, PyResult, pyclass, pymethods};
+use pyo3::{PyErr, PyObject, PyResult, pyclass, pymethods};
 use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
 
 /// Represents a [JSFunction] within the JavaScript Runtime.
 /// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Functions) for more details.
 #[gen_stub_pyclass]
 #[pyclass(unsendable, name = "JSFunction", module = "js_exec.js_exec")]
-pub struct PyJSFunction(JSFunction<'static>);
+pub struct PyJSFunction(pub Handle<JSFunction<'static>>);
 
-impl From<PyJSFunction> for JSFunction<'static> {
-    fn from(value: PyJSFunction) -> Self {
-        value.0
+impl TryFrom<PyJSFunction> for JSFunction<'static> {
+    type Error = PyErr;
+    fn try_from(value: PyJSFunction) -> PyResult<Self> {
+        value.0.into_inner()
     }
 }
 
 impl From<JSFunction<'static>> for PyJSFunction {
     fn from(value: JSFunction<'static>) -> Self {
-        PyJSFunction(value)
+        PyJSFunction(Handle::new(value))
     }
 }
 
@@ -33,96 +36,138 @@ impl PyJSFunction {
     /// Creates a new [JSFunction] from an object.
     /// Returns [None] if the object is not a function.
     #[staticmethod]
-    pub fn from_object(obj: &PyJSObject) -> Option<Self> {
-        with_js_cx(|cx| JSFunction::from_object(cx, &obj.0).map(|f| f.extend_lifetime()))
-            .map(|v| v.into())
+    pub fn from_object(obj: &PyJSObject) -> PyResult<Option<Self>> {
+        let object = obj.0.get()?;
+        Ok(
+            with_js_cx(|cx| JSFunction::from_object(cx, object).map(|f| f.extend_lifetime()))
+                .map(|v| v.into()),
+        )
+    }
+
+    /// Creates a new [JSFunction] which calls the given Python callable.
+    ///
+    /// JavaScript arguments are converted with [JSValue::to_py], keeping values without a
+    /// Python counterpart as [JSValue]s, and the return value is converted with
+    /// [JSValue::from_py]. Python exceptions are rethrown as JavaScript `Error`s.
+    ///
+    /// `name` defaults to the `__name__` of the callable and `nargs` sets the `length`
+    /// of the function.
+    #[staticmethod]
+    #[pyo3(signature = (callable, name = None, nargs = 0))]
+    pub fn from_py(
+        callable: PyCallable<'_, VarArgs, PyObject>,
+        name: Option<String>,
+        nargs: u32,
+    ) -> Self {
+        let name = name.or_else(|| callable.name()).unwrap_or_default();
+        with_js_cx(|cx| callable.to_function(cx, &name, nargs).extend_lifetime()).into()
     }
 
     /// Converts the [JSFunction] into an [JSObject].
-    pub fn to_object(&self) -> PyJSObject {
-        with_js_cx(|cx| self.0.to_object(cx).extend_lifetime()).into()
+    pub fn to_object(&self) -> PyResult<PyJSObject> {
+        let function = self.0.get()?;
+        Ok(with_js_cx(|cx| function.to_object(cx).extend_lifetime()).into())
     }
 
     /// Converts the [JSFunction] into a [String] in the form of its definition/source.
     #[allow(clippy::inherent_to_string)]
-    pub fn to_string(&self) -> String {
-        with_js_cx(|cx| self.0.to_string(cx))
+    pub fn to_string(&self) -> PyResult<String> {
+        let function = self.0.get()?;
+        Ok(with_js_cx(|cx| function.to_string(cx)))
     }
 
     /// Returns the name of the function.
     /// # Warning
     /// This can cause Access Violation errors if the function is anonymous function.
     pub fn name(&self) -> PyResult<String> {
-        with_js_cx(|cx| self.0.name(cx).to_value_err("Failed to get function name"))
+        let function = self.0.get()?;
+        with_js_cx(|cx| {
+            function
+                .name(cx)
+                .to_value_err("Failed to get function name")
+        })
     }
 
     /// Returns the display name of the function.
     /// Function display names are a non-standard feature.
     /// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Function/displayName) for more details.
     pub fn display_name(&self) -> PyResult<String> {
+        let function = self.0.get()?;
         with_js_cx(|cx| {
-            self.0
+            function
                 .display_name(cx)
                 .to_value_err("Failed to get display name")
         })
     }
     /// Returns the number of arguments of the function.
-    pub fn nargs(&self) -> u16 {
-        self.0.nargs()
+    pub fn nargs(&self) -> PyResult<u16> {
+        Ok(self.0.get()?.nargs())
     }
     /// Returns the length of the source of the function.
-    pub fn length(&self) -> Option<u16> {
-        with_js_cx(|cx| self.0.length(cx))
+    pub fn length(&self) -> PyResult<Option<u16>> {
+        let function = self.0.get()?;
+        Ok(with_js_cx(|cx| function.length(cx)))
     }
 
     /// Calls the [JSFunction] with the given `this` [JSObject] and arguments.
+    /// Arguments that are not [JSValue]s are converted with [JSValue::from_py].
     /// Returns the result of the [JSFunction] as a [JSValue].
-    /// Returns [Err] if the function call fails or an exception occurs.
-    #[pyo3(signature = (args=None, this = None))]
+    /// Raises a [JSError] subclass if the function throws, and [JSTimeoutError] if the call
+    /// runs for longer than `timeout` seconds, which defaults to the timeout of the runtime.
+    #[pyo3(signature = (args=None, this = None, timeout = None))]
     pub fn call(
         &self,
-        args: Option<Vec<PyJSValue>>,
+        args: Option<Vec<JSValueLike>>,
         this: Option<PyJSObject>,
+        timeout: Option<f64>,
     ) -> PyResult<PyJSValue> {
-        with_js_runtime(|rt| {
-            let cx = rt.cx();
-            let args = args.map(|v| v.into_iter().map(|a| a.0).collect::<Vec<_>>());
-            let result = if let Some(this_obj) = this {
-                // Use provided 'this'
-                self.0.call(cx, &this_obj.0, args.as_deref().unwrap_or(&[]))
-            } else {
-                // Use global object as 'this'
-                self.0.call(cx, rt.global(), args.as_deref().unwrap_or(&[]))
-            }
-            .to_runtime_err("Failed to call function")?;
-            Ok::<_, PyErr>(result.extend_lifetime().into())
+        let function = self.0.get()?;
+        let this = this.map(|this| this.0.into_inner()).transpose()?;
+        with_execution_timeout(timeout, || {
+            with_js_runtime(|rt| {
+                let cx = rt.cx();
+                let args = args.map(|v| v.into_iter().map(|a| a.0).collect::<Vec<_>>());
+                let result = if let Some(this_obj) = this {
+                    // Use provided 'this'
+                    function.call(cx, &this_obj, args.as_deref().unwrap_or(&[]))
+                } else {
+                    // Use global object as 'this'
+                    function.call(cx, rt.global(), args.as_deref().unwrap_or(&[]))
+                }
+                .to_js_err(cx)?;
+                Ok::<_, PyErr>(result.extend_lifetime().into())
+            })
         })
     }
+
     /// Checks if the [JSFunction] is the built-in eval function.
-    pub fn is_eval(&self) -> bool {
-        self.0.is_eval()
+    pub fn is_eval(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.is_eval())
     }
 
     /// Checks if the [JSFunction] is a constructor.
-    pub fn is_constructor(&self) -> bool {
-        self.0.is_constructor()
+    pub fn is_constructor(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.is_constructor())
     }
 
     /// Checks if the [JSFunction] is the built-in function constructor.
-    pub fn is_function_constructor(&self) -> bool {
-        self.0.is_function_constructor()
+    pub fn is_function_constructor(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.is_function_constructor())
     }
 
     // handle methods
-    pub fn is_null(&self) -> bool {
-        self.0.handle().is_null()
+    pub fn is_null(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_null())
     }
 
-    pub fn is_aligned(&self) -> bool {
-        self.0.handle().is_aligned()
+    pub fn is_aligned(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_aligned())
     }
 
-    pub fn __str__(&self) -> String {
-        with_js_cx(|cx| format_function(cx, Config::default(), &self.0).to_string())
+    pub fn __str__(&self) -> PyResult<String> {
+        let function = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            format_function(cx, Config::default(), function).to_string()
+        }))
     }
 }
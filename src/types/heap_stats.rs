@@ -0,0 +1,62 @@
+use crate::runtime::persistent_root_count;
+use mozjs::jsapi::{GetGCHeapUsage, JS_GetGCParameter, JSContext, JSGCParamKey};
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
+
+/// Snapshot of the memory usage and garbage collections of the JavaScript heap.
+#[gen_stub_pyclass]
+#[pyclass(frozen, get_all, name = "HeapStats", module = "js_exec.js_exec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PyHeapStats {
+    /// Bytes allocated for GC things such as objects and strings.
+    pub gc_heap_bytes: u64,
+    /// Bytes allocated with malloc and owned by GC things.
+    pub malloc_bytes: u64,
+    /// Limit of the GC heap in bytes.
+    pub max_heap_bytes: u64,
+    /// Number of garbage collections, including minor collections of the nursery.
+    pub gc_count: u64,
+    /// Number of major garbage collections.
+    pub major_gc_count: u64,
+    /// Number of minor garbage collections.
+    pub minor_gc_count: u64,
+    /// Number of JavaScript values currently held by Python wrappers.
+    pub persistent_roots: u64,
+}
+
+impl PyHeapStats {
+    /// Reads the statistics of the heap of `cx`.
+    ///
+    /// # Safety
+    /// `cx` must be a valid context of the current thread.
+    pub unsafe fn collect(cx: *mut JSContext) -> PyHeapStats {
+        let parameter = |key| u64::from(unsafe { JS_GetGCParameter(cx, key) });
+        let usage = unsafe { GetGCHeapUsage(cx) };
+        PyHeapStats {
+            gc_heap_bytes: parameter(JSGCParamKey::JSGC_BYTES),
+            malloc_bytes: usage.mallocBytes as u64,
+            max_heap_bytes: parameter(JSGCParamKey::JSGC_MAX_BYTES),
+            gc_count: parameter(JSGCParamKey::JSGC_NUMBER),
+            major_gc_count: parameter(JSGCParamKey::JSGC_MAJOR_GC_NUMBER),
+            minor_gc_count: parameter(JSGCParamKey::JSGC_MINOR_GC_NUMBER),
+            persistent_roots: persistent_root_count() as u64,
+        }
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyHeapStats {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "HeapStats(gc_heap_bytes={}, malloc_bytes={}, max_heap_bytes={}, gc_count={}, major_gc_count={}, minor_gc_count={}, persistent_roots={})",
+            self.gc_heap_bytes,
+            self.malloc_bytes,
+            self.max_heap_bytes,
+            self.gc_count,
+            self.major_gc_count,
+            self.minor_gc_count,
+            self.persistent_roots
+        )
+    }
+}
@@ -1,8 +1,10 @@
 pub mod bigint;
 pub mod callable;
 pub mod context;
+pub mod diagnostic;
 pub mod es_class;
 pub mod function;
+pub mod heap_stats;
 pub mod iterator_flags;
 pub mod module;
 pub mod object;
@@ -11,6 +13,8 @@ pub mod promise;
 pub mod property_descriptor;
 pub mod property_flags;
 pub mod property_key;
+pub mod realm;
 pub mod sourcemap;
+pub mod stack_frame;
 pub mod symbol;
 pub mod value;
@@ -1,24 +1,46 @@
+use crate::errors::{ErrorHandling, JSErrorHandling};
+use crate::handle::Handle;
 use crate::runtime::with_js_cx;
 use crate::traits::ExtendLifetime;
+use crate::types::object::PyJSObject;
 use crate::types::promise::PyJSPromise;
+use crate::types::value::PyJSValue;
 use ion::module::{Module as JSModule, Module};
+use ion::{Context, Object, OwnedKey};
+use mozjs::jsapi::GetModuleNamespace;
 use pyo3::exceptions::PyRuntimeError;
-use pyo3::{PyResult, pyclass, pymethods};
+use pyo3::{PyErr, PyResult, pyclass, pymethods};
 use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
 
 #[gen_stub_pyclass]
 #[pyclass(unsendable, name = "JSModule", module = "js_exec.js_exec")]
-pub struct PyJSModule(JSModule<'static>);
+pub struct PyJSModule(Handle<JSModule<'static>>);
 
-impl From<PyJSModule> for JSModule<'static> {
-    fn from(value: PyJSModule) -> Self {
-        value.0
+impl TryFrom<PyJSModule> for JSModule<'static> {
+    type Error = PyErr;
+    fn try_from(value: PyJSModule) -> PyResult<Self> {
+        value.0.into_inner()
     }
 }
 
 impl From<JSModule<'static>> for PyJSModule {
     fn from(value: JSModule<'static>) -> Self {
-        PyJSModule(value)
+        PyJSModule(Handle::new(value))
+    }
+}
+
+impl PyJSModule {
+    /// Returns the namespace object of the module, which holds its exports.
+    fn namespace_object<'cx>(&self, cx: &'cx Context) -> PyResult<Object<'cx>> {
+        let module = self.0.get()?;
+        if !module.is_linked() {
+            return Err(PyRuntimeError::new_err("Module has not been linked"));
+        }
+        let namespace = unsafe { GetModuleNamespace(cx.as_ptr(), module.0.handle().into()) };
+        if namespace.is_null() {
+            return Err(PyRuntimeError::new_err("Failed to get module namespace"));
+        }
+        Ok(Object::from(cx.root(namespace)))
     }
 }
 
@@ -38,7 +60,7 @@ impl PyJSModule {
         with_js_cx(|cx| {
             let (module, promise) =
                 Module::compile_and_evaluate(cx, filename, path.map(AsRef::as_ref), source)
-                    .map_err(|e| PyRuntimeError::new_err(e.format(cx)))?;
+                    .to_js_err(cx)?;
 
             Ok((
                 module.extend_lifetime().into(),
@@ -48,7 +70,50 @@ impl PyJSModule {
     }
 
     /// Returns `true` if the module has been linked.
-    pub fn is_linked(&self) -> bool {
-        self.0.is_linked()
+    pub fn is_linked(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.is_linked())
+    }
+
+    /// Returns the namespace object of the module, whose properties are its exports.
+    /// Raises [RuntimeError] if the module has not been linked.
+    pub fn namespace(&self) -> PyResult<PyJSObject> {
+        with_js_cx(|cx| Ok(self.namespace_object(cx)?.extend_lifetime().into()))
+    }
+
+    /// Returns the value of the export with the given name, or [None] if it is not exported.
+    pub fn get_export(&self, name: &str) -> PyResult<Option<PyJSValue>> {
+        with_js_cx(|cx| {
+            let value = self
+                .namespace_object(cx)?
+                .get(cx, name)
+                .to_value_err("Failed to get export")?;
+            Ok(value.map(|v| PyJSValue::from(v.extend_lifetime())))
+        })
+    }
+
+    /// Returns the names of all exports of the module, in sorted order.
+    pub fn export_names(&self) -> PyResult<Vec<String>> {
+        with_js_cx(|cx| {
+            let namespace = self.namespace_object(cx)?;
+            let names = namespace
+                .keys(cx, None)
+                .map(|key| key.to_owned_key(cx))
+                .collect::<Result<Vec<_>, _>>()
+                .to_value_err("Failed to get export names")?;
+            Ok(names
+                .into_iter()
+                .filter_map(|key| match key {
+                    OwnedKey::Int(index) => Some(index.to_string()),
+                    OwnedKey::String(name) => Some(name),
+                    _ => None,
+                })
+                .collect())
+        })
+    }
+
+    /// The `default` export of the module, or [None] if it has no default export.
+    #[getter]
+    pub fn default(&self) -> PyResult<Option<PyJSValue>> {
+        self.get_export("default")
     }
 }
@@ -1,4 +1,5 @@
 use crate::errors::ErrorHandling;
+use crate::handle::Handle;
 use crate::runtime::with_js_cx;
 use crate::traits::ExtendLifetime;
 use crate::types::es_class::PyESClass;
@@ -8,7 +9,7 @@ use crate::types::owned_key::PyOwnedKey;
 use crate::types::property_descriptor::PyPropertyDescriptor;
 use crate::types::property_flags::PyPropertyFlags;
 use crate::types::property_key::{PropertyKeyTypes, PyPropertyKey};
-use crate::types::value::PyJSValue;
+use crate::types::value::{JSValueLike, PyJSValue};
 use ion::conversions::{FromValue, ToValue};
 use ion::format::Config;
 use ion::format::object::format_raw_object;
@@ -21,26 +22,28 @@ use std::collections::HashMap;
 #[gen_stub_pyclass]
 #[pyclass(unsendable, name = "JSObject", module = "js_exec.js_exec")]
 #[derive(Debug)]
-pub struct PyJSObject(pub JSObject<'static>);
+pub struct PyJSObject(pub Handle<JSObject<'static>>);
 
-impl From<PyJSObject> for JSObject<'static> {
-    fn from(value: PyJSObject) -> Self {
-        value.0
+impl TryFrom<PyJSObject> for JSObject<'static> {
+    type Error = PyErr;
+    fn try_from(value: PyJSObject) -> PyResult<Self> {
+        value.0.into_inner()
     }
 }
 
 impl From<JSObject<'static>> for PyJSObject {
     fn from(value: JSObject<'static>) -> Self {
-        PyJSObject(value)
+        PyJSObject(Handle::new(value))
     }
 }
 
 impl<'py> FromPyObject<'py> for PyJSObject {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
         if let Ok(js_val) = ob.downcast::<PyJSObject>() {
+            let borrowed = js_val.borrow();
+            let object = borrowed.0.get()?;
             with_js_cx(|cx| {
-                let borrowed = js_val.borrow();
-                let obj = JSObject::from_value(cx, &borrowed.0.as_value(cx), true, ())
+                let obj = JSObject::from_value(cx, &object.as_value(cx), true, ())
                     .to_value_err("Failed to convert to JSObject")?;
                 Ok(obj.extend_lifetime().into())
             })
@@ -78,26 +81,28 @@ impl PyJSObject {
     }
 
     /// Checks if the [JSObject] has a value at the given key.
-    pub fn has(&self, key: PropertyKeyTypes) -> bool {
-        with_js_cx(|cx| self.0.has(cx, &key))
+    pub fn has(&self, key: PropertyKeyTypes) -> PyResult<bool> {
+        let object = self.0.get()?;
+        Ok(with_js_cx(|cx| object.has(cx, &key)))
     }
     /// Checks if the [JSObject] has its own value at the given key.
     ///
     /// An object owns its properties if they are not inherited from a prototype.
-    pub fn has_own(&self, key: PropertyKeyTypes) -> bool {
-        with_js_cx(|cx| self.0.has_own(cx, &key))
+    pub fn has_own(&self, key: PropertyKeyTypes) -> PyResult<bool> {
+        let object = self.0.get()?;
+        Ok(with_js_cx(|cx| object.has_own(cx, &key)))
     }
 
     /// Gets the [JSValue] at the given key of the [JSObject].
     ///
     /// Returns [None] if there is no value at the given key.
     pub fn get(&self, key: PropertyKeyTypes) -> PyResult<Option<PyJSValue>> {
+        let object = self.0.get()?;
         with_js_cx(|cx| {
-            if self.is_null() {
+            if object.handle().is_null() {
                 return Ok(None);
             }
-            let value = self
-                .0
+            let value = object
                 .get(cx, &key)
                 .map(|opt_value| opt_value.map(|v| PyJSValue::from(v.extend_lifetime())))
                 .to_value_err("Failed to get value")?;
@@ -108,8 +113,9 @@ impl PyJSObject {
     /// Gets the [JSFunction] at the given key of the [JSObject].
     /// Returns [None] if there is no value at the given key.
     pub fn get_function(&self, key: &str) -> PyResult<Option<PyJSFunction>> {
+        let object = self.0.get()?;
         with_js_cx(|cx| {
-            let value = self.0.get(cx, key).to_value_err("Failed to get value")?;
+            let value = object.get(cx, key).to_value_err("Failed to get value")?;
 
             if let Some(value) = value {
                 if !value.handle().is_object() {
@@ -126,8 +132,9 @@ impl PyJSObject {
     /// Gets the descriptor at the given key of the [JSObject].
     /// Returns [None] if the object does not contain the key.
     pub fn get_descriptor(&self, key: PropertyKeyTypes) -> PyResult<Option<PyPropertyDescriptor>> {
+        let object = self.0.get()?;
         with_js_cx(|cx| {
-            self.0
+            object
                 .get_descriptor(cx, &key)
                 .map(|opt_desc| opt_desc.map(|d| PyPropertyDescriptor::from(d.extend_lifetime())))
                 .to_value_err("Failed to get descriptor")
@@ -135,6 +142,7 @@ impl PyJSObject {
     }
 
     /// Sets the [JSValue] at the given key of the [JSObject].
+    /// Values that are not [JSValue]s are converted with [JSValue::from_py].
     ///
     /// Returns `false` if the property cannot be set.
     ///
@@ -142,65 +150,80 @@ impl PyJSObject {
     ///
     /// This function performs non-strict assignment, so if the property is
     /// read-only, nothing happens and no error is thrown.
-    pub fn set(&self, key: PropertyKeyTypes, value: &PyJSValue) -> bool {
-        with_js_cx(|cx| self.0.set(cx, &key, &value.0))
+    pub fn set(&self, key: PropertyKeyTypes, value: JSValueLike) -> PyResult<bool> {
+        let object = self.0.get()?;
+        Ok(with_js_cx(|cx| object.set(cx, &key, &value.0)))
     }
 
     /// Defines the [JSValue] at the given key of the [JSObject] with the given attributes.
     ///
     /// Returns `false` if the property cannot be defined.
-    pub fn define(&self, key: PropertyKeyTypes, value: &PyJSValue, attrs: PyPropertyFlags) -> bool {
-        with_js_cx(|cx| self.0.define(cx, &key, &value.0, attrs.0))
+    pub fn define(
+        &self,
+        key: PropertyKeyTypes,
+        value: &PyJSValue,
+        attrs: PyPropertyFlags,
+    ) -> PyResult<bool> {
+        let object = self.0.get()?;
+        let value = value.0.get()?;
+        Ok(with_js_cx(|cx| object.define(cx, &key, value, attrs.0)))
     }
 
     /// Deletes the [JSValue] at the given index.
     ///
     /// Returns `false` if the element cannot be deleted.
-    pub fn delete(&self, key: PropertyKeyTypes) -> bool {
-        with_js_cx(|cx| self.0.delete(cx, &key))
+    pub fn delete(&self, key: PropertyKeyTypes) -> PyResult<bool> {
+        let object = self.0.get()?;
+        Ok(with_js_cx(|cx| object.delete(cx, &key)))
     }
 
     /// Gets the builtin class of the object as described in the ECMAScript specification.
     ///
     /// Returns [ESClass::Other] for other projects or proxies that cannot be unwrapped.
-    pub fn get_builtin_class(&self) -> PyESClass {
-        with_js_cx(|cx| self.0.get_builtin_class(cx).into())
+    pub fn get_builtin_class(&self) -> PyResult<PyESClass> {
+        let object = self.0.get()?;
+        Ok(with_js_cx(|cx| object.get_builtin_class(cx).into()))
     }
 
     /// Returns the builtin class of the object if it a wrapper around a primitive.
     ///
     /// The boxed types are `Boolean`, `Number`, `String` and `BigInt`
-    pub fn is_boxed_primitive(&self) -> Option<PyESClass> {
-        with_js_cx(|cx| self.0.is_boxed_primitive(cx).map(Into::into))
+    pub fn is_boxed_primitive(&self) -> PyResult<Option<PyESClass>> {
+        let object = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            object.is_boxed_primitive(cx).map(Into::into)
+        }))
     }
 
     /// Unboxes primitive wrappers. See [Self::is_boxed_primitive] for details.
-    pub fn unbox_primitive(&self) -> Option<PyJSValue> {
-        with_js_cx(|cx| {
-            self.0
+    pub fn unbox_primitive(&self) -> PyResult<Option<PyJSValue>> {
+        let object = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            object
                 .unbox_primitive(cx)
                 .map(|v| PyJSValue::from(v.extend_lifetime()))
-        })
+        }))
     }
 
     /// Returns a vector of [PropertyKey] in the [JSObject].
     /// Each key can be a [String], [Symbol] or integer.
     #[pyo3(signature = (flags=None))]
-    pub fn keys(&self, flags: Option<PyIteratorFlags>) -> Vec<PyPropertyKey> {
-        with_js_cx(|cx| {
-            self.0
+    pub fn keys(&self, flags: Option<PyIteratorFlags>) -> PyResult<Vec<PyPropertyKey>> {
+        let object = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            object
                 .keys(cx, flags.map(Into::into))
                 .map(|key| PyPropertyKey::from(key.extend_lifetime()))
                 .collect()
-        })
+        }))
     }
 
     /// Returns a vector of [OwnedKey] in the [JSObject].
     #[pyo3(signature = (flags=None))]
     pub fn keys_owned(&self, flags: Option<PyIteratorFlags>) -> PyResult<Vec<PyOwnedKey>> {
+        let object = self.0.get()?;
         with_js_cx(|cx| {
-            let keys = self
-                .0
+            let keys = object
                 .keys(cx, flags.map(Into::into))
                 .map(|key| key.to_owned_key(cx).map(ExtendLifetime::extend_lifetime))
                 .collect::<Result<Vec<_>, _>>()
@@ -214,8 +237,9 @@ impl PyJSObject {
         &self,
         flags: Option<PyIteratorFlags>,
     ) -> PyResult<HashMap<PyOwnedKey, PyJSValue>> {
+        let object = self.0.get()?;
         with_js_cx(|cx| {
-            self.0
+            object
                 .to_hashmap(cx, flags.map(Into::into))
                 .map(|hashmap| {
                     hashmap
@@ -233,27 +257,31 @@ impl PyJSObject {
 
     /// Converts the [JSObject] to a [JSFunction] if possible.
     /// Returns [None] if the object is not a function.
-    pub fn to_function(&self) -> Option<PyJSFunction> {
-        with_js_cx(|cx| {
-            if self.0.get_builtin_class(cx) == mozjs::jsapi::ESClass::Function {
-                let function = JSFunction::from_object(cx, &self.0);
+    pub fn to_function(&self) -> PyResult<Option<PyJSFunction>> {
+        let object = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            if object.get_builtin_class(cx) == mozjs::jsapi::ESClass::Function {
+                let function = JSFunction::from_object(cx, object);
                 return function.map(|f| PyJSFunction::from(f.extend_lifetime()));
             }
             None
-        })
+        }))
     }
 
     // handle methods
-    pub fn is_null(&self) -> bool {
-        self.0.handle().is_null()
+    pub fn is_null(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_null())
     }
 
-    pub fn is_aligned(&self) -> bool {
-        self.0.handle().is_aligned()
+    pub fn is_aligned(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_aligned())
     }
 
-    pub fn __str__(&self) -> String {
-        with_js_cx(|cx| format_raw_object(cx, Config::default(), &self.0).to_string())
+    pub fn __str__(&self) -> PyResult<String> {
+        let object = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            format_raw_object(cx, Config::default(), object).to_string()
+        }))
     }
 
     /*pub fn __repr__(&self) -> String {
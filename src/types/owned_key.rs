@@ -1,3 +1,4 @@
+use crate::handle::Handle;
 use crate::runtime::with_js_cx;
 use crate::traits::ExtendLifetime;
 use crate::types::symbol::PySymbol;
@@ -18,17 +19,18 @@ use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
     hash
 )]
 #[derive(Eq, Hash, PartialEq)]
-pub struct PyOwnedKey(OwnedKey<'static>);
+pub struct PyOwnedKey(Handle<OwnedKey<'static>>);
 
-impl From<PyOwnedKey> for OwnedKey<'static> {
-    fn from(value: PyOwnedKey) -> Self {
-        value.0
+impl TryFrom<PyOwnedKey> for OwnedKey<'static> {
+    type Error = PyErr;
+    fn try_from(value: PyOwnedKey) -> PyResult<Self> {
+        value.0.into_inner()
     }
 }
 
 impl From<OwnedKey<'static>> for PyOwnedKey {
     fn from(value: OwnedKey<'static>) -> Self {
-        PyOwnedKey(value)
+        PyOwnedKey(Handle::new(value))
     }
 }
 
@@ -38,33 +40,35 @@ impl PyOwnedKey {
     /// Creates a new OwnedKey representing an integer value
     #[staticmethod]
     pub fn int(value: i32) -> Self {
-        PyOwnedKey(OwnedKey::Int(value))
+        OwnedKey::Int(value).into()
     }
 
     /// Creates a new OwnedKey representing a string value
     #[staticmethod]
     pub fn string(value: String) -> Self {
-        PyOwnedKey(OwnedKey::String(value))
+        OwnedKey::String(value).into()
     }
 
     /// Creates a new OwnedKey representing a symbol
     #[staticmethod]
-    pub fn symbol(symbol: &PySymbol) -> Self {
-        with_js_cx(|cx| {
-            let cloned_symbol: ion::Symbol = cx.root(symbol.0.get()).into();
-            PyOwnedKey(OwnedKey::Symbol(cloned_symbol.extend_lifetime()))
-        })
+    pub fn symbol(symbol: &PySymbol) -> PyResult<Self> {
+        let symbol = symbol.0.get()?;
+        Ok(with_js_cx(|cx| {
+            let cloned_symbol: ion::Symbol = cx.root(symbol.get()).into();
+            OwnedKey::Symbol(cloned_symbol.extend_lifetime()).into()
+        }))
     }
 
     /// Creates a new void OwnedKey
     #[staticmethod]
     pub fn void() -> Self {
-        PyOwnedKey(OwnedKey::Void)
+        OwnedKey::Void.into()
     }
 
-    pub fn __str__(&self) -> String {
-        with_js_cx(|cx| {
-            let formatted = format_key(cx, Config::default(), &self.0).to_string();
+    pub fn __str__(&self) -> PyResult<String> {
+        let key = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            let formatted = format_key(cx, Config::default(), key).to_string();
 
             // Safely remove only the surrounding quotes if they exist
             if formatted.starts_with('"') && formatted.ends_with('"') && formatted.len() >= 2 {
@@ -72,11 +76,11 @@ impl PyOwnedKey {
             } else {
                 formatted
             }
-        })
+        }))
     }
 
-    pub fn __repr__(&self) -> String {
-        match &self.0 {
+    pub fn __repr__(&self) -> PyResult<String> {
+        Ok(match self.0.get()? {
             OwnedKey::Int(i) => format!("OwnedKey::Int({})", i),
             OwnedKey::String(s) => format!(r"OwnedKey::String({})", s),
             OwnedKey::Symbol(s) => with_js_cx(|cx| {
@@ -86,6 +90,6 @@ impl PyOwnedKey {
                 )
             }),
             OwnedKey::Void => "OwnedKey::Void".to_string(),
-        }
+        })
     }
 }
@@ -1,31 +1,42 @@
-use crate::errors::ErrorHandling;
-use crate::runtime::with_js_cx;
+use crate::awaitables::{pending_awaitables, promise_from_awaitable};
+use crate::conversions::{DEFAULT_MAX_DEPTH, JSToPy, PyConversionFallback};
+use crate::errors::{ErrorHandling, JSErrorHandling, rejection_error, to_js_exception};
+use crate::handle::Handle;
+use crate::pending_work::next_work_delay;
+use crate::runtime::{event_loop_running, poll_event_loop, with_js_cx};
 use crate::traits::ExtendLifetime;
-use crate::types::value::PyJSValue;
-use ion::Promise as JSPromise;
-use ion::conversions::FromValue;
+use crate::types::value::{JSValueLike, PyJSValue};
+use ion::conversions::{FromValue, ToValue};
+use ion::flags::PropertyFlags;
 use ion::format::Config;
 use ion::format::promise::format_promise;
+use ion::{Arguments, Array, Context, Function, Object, Promise as JSPromise, ResultExc, Value};
 use macros::enum_original_mapping;
 use mozjs::jsapi::PromiseState;
-use pyo3::{PyResult, pyclass, pymethods};
+use pyo3::exceptions::{PyRuntimeError, PyTypeError};
+use pyo3::prelude::*;
+use pyo3::types::PyCFunction;
 use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pyclass_enum, gen_stub_pymethods};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::time::Duration;
 
 /// Represents a [JSPromise] in the JavaScript Runtime.
 /// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Promise) for more details.
 #[gen_stub_pyclass]
 #[pyclass(unsendable, name = "JSPromise", module = "js_exec.js_exec")]
-pub struct PyJSPromise(JSPromise<'static>);
+pub struct PyJSPromise(pub Handle<JSPromise<'static>>);
 
-impl From<PyJSPromise> for JSPromise<'static> {
-    fn from(value: PyJSPromise) -> Self {
-        value.0
+impl TryFrom<PyJSPromise> for JSPromise<'static> {
+    type Error = PyErr;
+    fn try_from(value: PyJSPromise) -> PyResult<Self> {
+        value.0.into_inner()
     }
 }
 
 impl From<JSPromise<'static>> for PyJSPromise {
     fn from(value: JSPromise<'static>) -> Self {
-        PyJSPromise(value)
+        PyJSPromise(Handle::new(value))
     }
 }
 
@@ -45,6 +56,245 @@ pub enum PyJSPromiseState {
     Rejected = 2,
 }
 
+/// Delay between runs of the event loop while an awaited [JSPromise] is pending on work which
+/// is not tracked, such as the futures of the standard modules.
+const UNTRACKED_WORK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Runs the event loop of the current thread from an asyncio event loop while an awaited
+/// [JSPromise] is pending.
+struct Driver {
+    /// Future which the [JSPromise] completes.
+    future: PyObject,
+    event_loop: PyObject,
+    /// Callback which runs the event loop once, scheduled on the asyncio event loop.
+    callback: PyObject,
+    /// Handle of the scheduled run of the callback, if there is one.
+    scheduled: Option<PyObject>,
+}
+
+thread_local! {
+    /// Drivers of the awaited promises of the current thread, by identifier.
+    static DRIVERS: RefCell<HashMap<u64, Driver>> = RefCell::new(HashMap::new());
+    static NEXT_DRIVER: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Starts running the event loop from `event_loop` until `future` is done.
+fn start_driver(future: &Bound<'_, PyAny>, event_loop: &Bound<'_, PyAny>) -> PyResult<()> {
+    let py = future.py();
+    let id = NEXT_DRIVER.get();
+    NEXT_DRIVER.set(id + 1);
+    let callback = PyCFunction::new_closure(py, None, None, move |args, _| drive(args.py(), id))?;
+    let stop = PyCFunction::new_closure(py, None, None, move |args, _| stop_driver(args.py(), id))?;
+    let driver = Driver {
+        future: future.clone().unbind(),
+        event_loop: event_loop.clone().unbind(),
+        callback: callback.into_any().unbind(),
+        scheduled: None,
+    };
+    DRIVERS.with(|drivers| drivers.borrow_mut().insert(id, driver));
+    future.call_method1("add_done_callback", (stop,))?;
+    schedule(py, id, None)
+}
+
+/// Removes the driver `id` once its future is done, cancelling its scheduled run.
+fn stop_driver(py: Python<'_>, id: u64) -> PyResult<()> {
+    let driver = DRIVERS.with(|drivers| drivers.borrow_mut().remove(&id));
+    if let Some(scheduled) = driver.and_then(|driver| driver.scheduled) {
+        scheduled.call_method0(py, "cancel")?;
+    }
+    Ok(())
+}
+
+/// Schedules the next run of the driver `id` after `delay`, or as soon as possible if it is
+/// [None], replacing its scheduled run.
+fn schedule(py: Python<'_>, id: u64, delay: Option<Duration>) -> PyResult<()> {
+    DRIVERS.with(|drivers| {
+        let mut drivers = drivers.borrow_mut();
+        let Some(driver) = drivers.get_mut(&id) else {
+            return Ok(());
+        };
+        if let Some(scheduled) = driver.scheduled.take() {
+            scheduled.call_method0(py, "cancel")?;
+        }
+        let event_loop = driver.event_loop.bind(py);
+        let scheduled = match delay {
+            Some(delay) => {
+                event_loop.call_method1("call_later", (delay.as_secs_f64(), &driver.callback))?
+            }
+            None => event_loop.call_method1("call_soon", (&driver.callback,))?,
+        };
+        driver.scheduled = Some(scheduled.unbind());
+        Ok(())
+    })
+}
+
+/// Runs the event loop once for the driver `id`.
+///
+/// The next run is scheduled for when the timers or network requests of the runtime may be
+/// ready. Drivers are not rescheduled while the event loop is idle or only waits for
+/// awaitables, until [wake_drivers] is called.
+fn drive(py: Python<'_>, id: u64) -> PyResult<()> {
+    let future = DRIVERS.with(|drivers| {
+        let mut drivers = drivers.borrow_mut();
+        let driver = drivers.get_mut(&id)?;
+        driver.scheduled = None;
+        Some(driver.future.clone_ref(py))
+    });
+    let Some(future) = future else {
+        return Ok(());
+    };
+    let future = future.bind(py);
+    // When the event loop is already being driven further up the stack, that driver makes
+    // progress instead.
+    if future.call_method0("done")?.is_truthy()? || event_loop_running() {
+        return Ok(());
+    }
+    let idle = match poll_event_loop() {
+        Ok(idle) => idle,
+        Err(error) => {
+            future.call_method1("set_exception", (error.into_value(py),))?;
+            return Ok(());
+        }
+    };
+    // The driver has been woken while the event loop ran, so it runs again soon anyway.
+    let woken = DRIVERS.with(|drivers| {
+        drivers
+            .borrow()
+            .get(&id)
+            .is_none_or(|driver| driver.scheduled.is_some())
+    });
+    if idle || woken || future.call_method0("done")?.is_truthy()? {
+        return Ok(());
+    }
+    match with_js_cx(next_work_delay) {
+        Some(delay) => schedule(py, id, Some(delay)),
+        // Awaitables wake the drivers once they are done.
+        None if pending_awaitables() > 0 => Ok(()),
+        None => schedule(py, id, Some(UNTRACKED_WORK_INTERVAL)),
+    }
+}
+
+/// Runs the event loop soon for every awaited [JSPromise], as work may have become ready, such
+/// as reactions of promises settled from Python.
+pub fn wake_drivers() {
+    let ids: Vec<u64> = DRIVERS
+        .try_with(|drivers| drivers.borrow().keys().copied().collect())
+        .unwrap_or_default();
+    if ids.is_empty() {
+        return;
+    }
+    Python::with_gil(|py| {
+        for id in ids {
+            // Drivers of closed asyncio event loops cannot run anymore, and are skipped.
+            let _ = schedule(py, id, None);
+        }
+    });
+}
+
+/// Creates a reaction which completes an asyncio future with the outcome of a [JSPromise].
+///
+/// Fulfilled values are converted with [JSToPy], keeping values without a Python counterpart as
+/// [JSValue]s, and rejection reasons are set as [JSError]s.
+fn complete_future<'cx>(
+    cx: &'cx Context,
+    future: &Bound<'_, PyAny>,
+    rejected: bool,
+) -> Function<'cx> {
+    let future = future.clone().unbind();
+    let closure = move |args: &mut Arguments<'_>| -> ResultExc<Value<'_>> {
+        let cx = args.cx();
+        let undefined = Value::undefined(cx);
+        let value = args.value(0).unwrap_or(&undefined);
+        Python::with_gil(|py| {
+            let future = future.bind(py);
+            // The future is already done if the awaiting task was cancelled.
+            if future.call_method0("done")?.is_truthy()? {
+                return Ok(());
+            }
+            let result = if rejected {
+                Err(rejection_error(cx, value))
+            } else {
+                JSToPy::new(cx, py, DEFAULT_MAX_DEPTH, PyConversionFallback::Wrap).convert(value)
+            };
+            match result {
+                Ok(value) => future.call_method1("set_result", (value,)),
+                Err(error) => future.call_method1("set_exception", (error.into_value(py),)),
+            }
+            .map(drop)
+        })
+        .map_err(to_js_exception)?;
+        Ok(Value::undefined(cx))
+    };
+    Function::from_closure(
+        cx,
+        "completeFuture",
+        Box::new(closure),
+        1,
+        PropertyFlags::empty(),
+    )
+}
+
+/// Converts an optional reaction handler into a function value, or `undefined` if it is [None].
+fn reaction<'cx>(cx: &'cx Context, handler: Option<JSValueLike>) -> PyResult<Value<'cx>> {
+    let Some(handler) = handler else {
+        return Ok(Value::undefined(cx));
+    };
+    let value = Value::from_value(cx, &handler.0, true, ())
+        .to_value_err("Failed to convert reaction handler")?;
+    let is_function =
+        value.handle().is_object() && Function::from_object(cx, &value.to_object(cx)).is_some();
+    if !is_function {
+        return Err(PyTypeError::new_err(
+            "Promise reactions must be callables or JSFunctions",
+        ));
+    }
+    Ok(value)
+}
+
+/// Calls the method `name` of `this` and converts its result into a [JSPromise].
+fn call_promise_method(
+    cx: &Context,
+    this: &Object,
+    name: &str,
+    args: &[Value],
+) -> PyResult<PyJSPromise> {
+    let method = this
+        .get(cx, name)
+        .ok()
+        .flatten()
+        .filter(|value| value.handle().is_object())
+        .and_then(|value| Function::from_object(cx, &value.to_object(cx)))
+        .ok_or_else(|| PyRuntimeError::new_err(format!("Promise method \"{name}\" is missing")))?;
+    let result = method.call(cx, this, args).to_js_err(cx)?;
+    // Reactions of settled promises are queued, which awaited promises may depend on.
+    wake_drivers();
+    let promise = JSPromise::from_value(cx, &result, true, ())
+        .to_value_err("Failed to convert to JSPromise")?;
+    Ok(promise.extend_lifetime().into())
+}
+
+/// Calls a static combinator of the global `Promise` constructor with an array of values.
+fn combine(promises: Vec<JSValueLike>, combinator: &str) -> PyResult<PyJSPromise> {
+    with_js_cx(|cx| {
+        let constructor = Object::global(cx)
+            .get(cx, "Promise")
+            .ok()
+            .flatten()
+            .filter(|value| value.handle().is_object())
+            .map(|value| value.to_object(cx))
+            .ok_or_else(|| PyRuntimeError::new_err("Promise constructor is missing"))?;
+        let array = Array::new(cx);
+        for (index, promise) in promises.iter().enumerate() {
+            if !array.set(cx, index as u32, &promise.0) {
+                return Err(PyRuntimeError::new_err(format!(
+                    "Failed to set element {index}"
+                )));
+            }
+        }
+        call_promise_method(cx, &constructor, combinator, &[array.as_value(cx)])
+    })
+}
+
 #[allow(clippy::new_without_default)]
 #[gen_stub_pymethods]
 #[pymethods]
@@ -52,14 +302,15 @@ impl PyJSPromise {
     /// Creates a new [JSPromise] which never resolves.
     #[new]
     pub fn new() -> Self {
-        with_js_cx(|cx| PyJSPromise(JSPromise::new(cx).extend_lifetime()))
+        with_js_cx(|cx| JSPromise::new(cx).extend_lifetime().into())
     }
 
     /// Creates a new [JSPromise] from a [JSValue].
     #[staticmethod]
     pub fn from_value(value: PyJSValue) -> PyResult<Self> {
+        let value = value.0.into_inner()?;
         with_js_cx(|cx| {
-            let promise = JSPromise::from_value(cx, &value.0, true, ())
+            let promise = JSPromise::from_value(cx, &value, true, ())
                 .to_value_err("Failed to convert to JSPromise")?;
             Ok(promise.extend_lifetime().into())
         })
@@ -68,38 +319,170 @@ impl PyJSPromise {
     /// Creates a new [Promise], that is resolved to the given value.
     /// Similar to `Promise.resolve`
     #[staticmethod]
-    pub fn resolved(value: &PyJSValue) -> Self {
-        with_js_cx(|cx| PyJSPromise(JSPromise::resolved(cx, &value.0).extend_lifetime()))
+    pub fn resolved(value: JSValueLike) -> Self {
+        with_js_cx(|cx| JSPromise::resolved(cx, &value.0).extend_lifetime().into())
+    }
+
+    /// Creates a new [JSPromise] which is settled with the outcome of a Python awaitable,
+    /// such as a coroutine.
+    ///
+    /// The awaitable runs as an asyncio task on the running event loop. When none is running,
+    /// it runs on a private event loop of the current thread, which is stepped alongside the
+    /// timers and other work of the event loop of the runtime, so the awaitable can itself await
+    /// [JSPromise]s. The task is waited for on the future queue of the runtime, so
+    /// `Runtime.run_event_loop` keeps running until the promise is settled.
+    /// Python exceptions reject the promise.
+    ///
+    /// # Errors
+    /// Raises [TypeError] if the object is not awaitable, and [RuntimeError] if the
+    /// macrotask queue is disabled.
+    #[staticmethod]
+    pub fn from_awaitable(py: Python<'_>, awaitable: &Bound<'_, PyAny>) -> PyResult<Py<Self>> {
+        promise_from_awaitable(py, awaitable)
     }
 
     /// Returns the ID of the [JSPromise].
-    pub fn id(&self) -> u64 {
-        self.0.id()
+    pub fn id(&self) -> PyResult<u64> {
+        Ok(self.0.get()?.id())
     }
 
     /// Returns the state of the [JSPromise].
     ///
     /// The state can be `Pending`, `Fulfilled` and `Rejected`.
-    pub fn state(&self) -> PyJSPromiseState {
-        self.0.state().into()
+    pub fn state(&self) -> PyResult<PyJSPromiseState> {
+        Ok(self.0.get()?.state().into())
     }
 
     /// Returns the result of the [JSPromise].
-    pub fn result(&self) -> PyJSValue {
-        with_js_cx(|cx| self.0.result(cx).extend_lifetime().into())
+    pub fn result(&self) -> PyResult<PyJSValue> {
+        let promise = self.0.get()?;
+        Ok(with_js_cx(|cx| promise.result(cx).extend_lifetime().into()))
     }
 
     /// Resolves the [JSPromise] with the given [JSValue].
-    pub fn resolve(&self, value: &PyJSValue) -> bool {
-        with_js_cx(|cx| self.0.resolve(cx, &value.0))
+    /// Values that are not [JSValue]s are converted with [JSValue::from_py].
+    pub fn resolve(&self, value: JSValueLike) -> PyResult<bool> {
+        let promise = self.0.get()?;
+        let settled = with_js_cx(|cx| promise.resolve(cx, &value.0));
+        wake_drivers();
+        Ok(settled)
     }
 
     /// Rejects the [JSPromise] with the given [JSValue].
-    pub fn reject(&self, value: &PyJSValue) -> bool {
-        with_js_cx(|cx| self.0.reject(cx, &value.0))
+    /// Values that are not [JSValue]s are converted with [JSValue::from_py].
+    pub fn reject(&self, value: JSValueLike) -> PyResult<bool> {
+        let promise = self.0.get()?;
+        let settled = with_js_cx(|cx| promise.reject(cx, &value.0));
+        wake_drivers();
+        Ok(settled)
+    }
+
+    /// Appends fulfillment and rejection handlers to the [JSPromise], like `Promise.prototype.then`.
+    ///
+    /// Handlers can be Python callables or [JSFunction]s. Python callables receive the value
+    /// converted with [JSValue::to_py] and their return value is converted with
+    /// [JSValue::from_py], so returning an awaitable chains it. Exceptions reject the
+    /// returned promise.
+    ///
+    /// Returns a new [JSPromise] which is settled with the result of the called handler.
+    #[pyo3(signature = (on_fulfilled = None, on_rejected = None))]
+    pub fn then(
+        &self,
+        on_fulfilled: Option<JSValueLike>,
+        on_rejected: Option<JSValueLike>,
+    ) -> PyResult<Self> {
+        let promise = self.0.get()?;
+        with_js_cx(|cx| {
+            let args = [reaction(cx, on_fulfilled)?, reaction(cx, on_rejected)?];
+            call_promise_method(cx, &promise.as_value(cx).to_object(cx), "then", &args)
+        })
+    }
+
+    /// Appends a rejection handler to the [JSPromise], like `Promise.prototype.catch`.
+    /// Returns a new [JSPromise], which is fulfilled with the value of the [JSPromise] if it
+    /// is fulfilled, or with the result of the handler if it is rejected.
+    pub fn catch_(&self, on_rejected: JSValueLike) -> PyResult<Self> {
+        let promise = self.0.get()?;
+        with_js_cx(|cx| {
+            let args = [reaction(cx, Some(on_rejected))?];
+            call_promise_method(cx, &promise.as_value(cx).to_object(cx), "catch", &args)
+        })
+    }
+
+    /// Appends a handler which is called without arguments when the [JSPromise] is settled,
+    /// like `Promise.prototype.finally`.
+    /// Returns a new [JSPromise] which is settled like the [JSPromise], unless the handler
+    /// throws or returns a rejected promise.
+    pub fn finally_(&self, on_finally: JSValueLike) -> PyResult<Self> {
+        let promise = self.0.get()?;
+        with_js_cx(|cx| {
+            let args = [reaction(cx, Some(on_finally))?];
+            call_promise_method(cx, &promise.as_value(cx).to_object(cx), "finally", &args)
+        })
+    }
+
+    /// Creates a [JSPromise] which is fulfilled with a list of the values of the given promises
+    /// when all of them are fulfilled, or rejected with the first rejection, like `Promise.all`.
+    /// Values that are not [JSPromise]s are converted with [JSValue::from_py].
+    #[staticmethod]
+    pub fn all(promises: Vec<JSValueLike>) -> PyResult<Self> {
+        combine(promises, "all")
+    }
+
+    /// Creates a [JSPromise] which is fulfilled with the value of the first fulfilled promise,
+    /// or rejected with an `AggregateError` if all of them are rejected, like `Promise.any`.
+    /// Values that are not [JSPromise]s are converted with [JSValue::from_py].
+    #[staticmethod]
+    pub fn any(promises: Vec<JSValueLike>) -> PyResult<Self> {
+        combine(promises, "any")
+    }
+
+    /// Creates a [JSPromise] which is settled like the first of the given promises to settle,
+    /// like `Promise.race`.
+    /// Values that are not [JSPromise]s are converted with [JSValue::from_py].
+    #[staticmethod]
+    pub fn race(promises: Vec<JSValueLike>) -> PyResult<Self> {
+        combine(promises, "race")
+    }
+
+    /// Creates a [JSPromise] which is fulfilled when all of the given promises are settled,
+    /// with a list of objects describing their outcomes, like `Promise.allSettled`.
+    /// Values that are not [JSPromise]s are converted with [JSValue::from_py].
+    #[staticmethod]
+    pub fn all_settled(promises: Vec<JSValueLike>) -> PyResult<Self> {
+        combine(promises, "allSettled")
+    }
+
+    /// Awaits the [JSPromise] from asyncio, driving the event loop on the current thread
+    /// until it is settled.
+    ///
+    /// The [JSPromise] completes an `asyncio.Future` of the running event loop from its
+    /// reactions, which is awaited instead. The result is the fulfilled value converted with
+    /// [JSValue::to_py], keeping values without a Python counterpart as [JSValue]s.
+    /// Rejections are raised as [JSError]s.
+    ///
+    /// The event loop runs whenever its timers or network requests may be ready, and when
+    /// promises are settled from Python, rather than at a fixed interval.
+    pub fn __await__(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let promise = self.0.get()?;
+        let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+        let future = event_loop.call_method0("create_future")?;
+        with_js_cx(|cx| {
+            let args = [
+                complete_future(cx, &future, false).as_value(cx),
+                complete_future(cx, &future, true).as_value(cx),
+            ];
+            call_promise_method(cx, &promise.as_value(cx).to_object(cx), "then", &args)
+        })?;
+
+        start_driver(&future, &event_loop)?;
+        future.call_method0("__await__").map(Bound::unbind)
     }
 
-    pub fn __str__(&self) -> String {
-        with_js_cx(|cx| format_promise(cx, Config::default(), &self.0).to_string())
+    pub fn __str__(&self) -> PyResult<String> {
+        let promise = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            format_promise(cx, Config::default(), promise).to_string()
+        }))
     }
 }
@@ -1,3 +1,4 @@
+use crate::handle::Handle;
 use crate::runtime::with_js_cx;
 use crate::traits::ExtendLifetime;
 use crate::types::object::PyJSObject;
@@ -11,17 +12,18 @@ use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
 
 #[gen_stub_pyclass]
 #[pyclass(unsendable, name = "PropertyDescriptor", module = "js_exec.js_exec")]
-pub struct PyPropertyDescriptor(PropertyDescriptor<'static>);
+pub struct PyPropertyDescriptor(Handle<PropertyDescriptor<'static>>);
 
-impl From<PyPropertyDescriptor> for PropertyDescriptor<'static> {
-    fn from(value: PyPropertyDescriptor) -> Self {
-        value.0
+impl TryFrom<PyPropertyDescriptor> for PropertyDescriptor<'static> {
+    type Error = PyErr;
+    fn try_from(value: PyPropertyDescriptor) -> PyResult<Self> {
+        value.0.into_inner()
     }
 }
 
 impl From<PropertyDescriptor<'static>> for PyPropertyDescriptor {
     fn from(value: PropertyDescriptor<'static>) -> Self {
-        PyPropertyDescriptor(value)
+        PyPropertyDescriptor(Handle::new(value))
     }
 }
 
@@ -29,60 +31,67 @@ impl From<PropertyDescriptor<'static>> for PyPropertyDescriptor {
 #[pymethods]
 impl PyPropertyDescriptor {
     #[new]
-    pub fn new(value: &PyJSValue, attrs: &PyPropertyFlags) -> Self {
-        with_js_cx(|cx| {
-            let desc = PropertyDescriptor::new(cx, &value.0, attrs.0);
-            PyPropertyDescriptor(desc.extend_lifetime())
-        })
+    pub fn new(value: &PyJSValue, attrs: &PyPropertyFlags) -> PyResult<Self> {
+        let value = value.0.get()?;
+        Ok(with_js_cx(|cx| {
+            let desc = PropertyDescriptor::new(cx, value, attrs.0);
+            desc.extend_lifetime().into()
+        }))
     }
 
     #[staticmethod]
     pub fn empty() -> Self {
         with_js_cx(|cx| {
             let desc = PropertyDescriptor::empty(cx);
-            PyPropertyDescriptor(desc.extend_lifetime())
+            desc.extend_lifetime().into()
         })
     }
 
     #[staticmethod]
-    pub fn from_object(obj: &PyJSObject) -> Option<Self> {
-        with_js_cx(|cx| {
-            let desc = PropertyDescriptor::from_object(cx, &obj.0);
-            desc.map(|d| PyPropertyDescriptor(d.extend_lifetime()))
-        })
+    pub fn from_object(obj: &PyJSObject) -> PyResult<Option<Self>> {
+        let obj = obj.0.get()?;
+        Ok(with_js_cx(|cx| {
+            let desc = PropertyDescriptor::from_object(cx, obj);
+            desc.map(|d| d.extend_lifetime().into())
+        }))
     }
 
-    pub fn to_object(&self) -> Option<PyJSObject> {
-        with_js_cx(|cx| {
-            let obj = self.0.to_object(cx);
+    pub fn to_object(&self) -> PyResult<Option<PyJSObject>> {
+        let desc = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            let obj = desc.to_object(cx);
             obj.map(|o| PyJSObject::from(o.extend_lifetime()))
-        })
+        }))
     }
 
-    pub fn is_configurable(&self) -> bool {
-        self.0.is_configurable()
+    pub fn is_configurable(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.is_configurable())
     }
 
-    pub fn is_enumerable(&self) -> bool {
-        self.0.is_enumerable()
+    pub fn is_enumerable(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.is_enumerable())
     }
 
-    pub fn is_writable(&self) -> bool {
-        self.0.is_writable()
+    pub fn is_writable(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.is_writable())
     }
 
-    pub fn is_resolving(&self) -> bool {
-        self.0.is_resolving()
+    pub fn is_resolving(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.is_resolving())
     }
 
-    pub fn value(&self) -> Option<PyJSValue> {
-        with_js_cx(|cx| {
-            let value = self.0.value(cx);
+    pub fn value(&self) -> PyResult<Option<PyJSValue>> {
+        let desc = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            let value = desc.value(cx);
             value.map(|v| PyJSValue::from(v.extend_lifetime()))
-        })
+        }))
     }
 
-    pub fn __str__(&self) -> String {
-        with_js_cx(|cx| format_descriptor(cx, Config::default(), &self.0, None).to_string())
+    pub fn __str__(&self) -> PyResult<String> {
+        let desc = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            format_descriptor(cx, Config::default(), desc, None).to_string()
+        }))
     }
 }
@@ -1,4 +1,5 @@
 use crate::errors::ErrorHandling;
+use crate::handle::Handle;
 use crate::runtime::with_js_cx;
 use crate::traits::ExtendLifetime;
 use crate::types::owned_key::PyOwnedKey;
@@ -14,26 +15,28 @@ use pyo3_stub_gen::{PyStubType, TypeInfo};
 
 #[gen_stub_pyclass]
 #[pyclass(unsendable, name = "PropertyKey", module = "js_exec.js_exec")]
-pub struct PyPropertyKey(pub PropertyKey<'static>);
+pub struct PyPropertyKey(pub Handle<PropertyKey<'static>>);
 
-impl From<PyPropertyKey> for PropertyKey<'static> {
-    fn from(key: PyPropertyKey) -> Self {
-        key.0
+impl TryFrom<PyPropertyKey> for PropertyKey<'static> {
+    type Error = PyErr;
+    fn try_from(key: PyPropertyKey) -> PyResult<Self> {
+        key.0.into_inner()
     }
 }
 
 impl From<PropertyKey<'static>> for PyPropertyKey {
     fn from(key: PropertyKey<'static>) -> Self {
-        PyPropertyKey(key)
+        PyPropertyKey(Handle::new(key))
     }
 }
 
 impl<'py> FromPyObject<'py> for PyPropertyKey {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
         if let Ok(prop_key) = ob.downcast::<PyPropertyKey>() {
+            let borrowed = prop_key.borrow();
+            let key = borrowed.0.get()?;
             with_js_cx(|cx| {
-                let borrowed = prop_key.borrow();
-                let obj = PropertyKey::from_value(cx, &borrowed.0.as_value(cx))
+                let obj = PropertyKey::from_value(cx, &key.as_value(cx))
                     .ok_or(PyValueError::new_err("Failed to convert to PropertyKey"))?;
                 Ok(obj.extend_lifetime().into())
             })
@@ -49,75 +52,81 @@ impl PyPropertyKey {
     /// Creates a [PropertyKey] from an integer.
     #[staticmethod]
     pub fn with_int(value: i32) -> Self {
-        with_js_cx(|cx| PyPropertyKey(PropertyKey::with_int(cx, value).extend_lifetime()))
+        with_js_cx(|cx| PropertyKey::with_int(cx, value).extend_lifetime().into())
     }
 
     /// Creates a [PropertyKey] from a string.
     #[staticmethod]
     pub fn with_string(value: &str) -> Option<Self> {
-        with_js_cx(|cx| {
-            PropertyKey::with_string(cx, value).map(|key| PyPropertyKey(key.extend_lifetime()))
-        })
+        with_js_cx(|cx| PropertyKey::with_string(cx, value).map(|key| key.extend_lifetime().into()))
     }
 
     #[staticmethod]
-    pub fn with_symbol(symbol: &PySymbol) -> Self {
-        with_js_cx(|cx| PyPropertyKey(PropertyKey::with_symbol(cx, &symbol.0).extend_lifetime()))
+    pub fn with_symbol(symbol: &PySymbol) -> PyResult<Self> {
+        let symbol = symbol.0.get()?;
+        Ok(with_js_cx(|cx| {
+            PropertyKey::with_symbol(cx, symbol)
+                .extend_lifetime()
+                .into()
+        }))
     }
 
     #[staticmethod]
-    pub fn from_value(value: &PyJSValue) -> Option<Self> {
-        with_js_cx(|cx| {
-            PropertyKey::from_value(cx, &value.0).map(|key| PyPropertyKey(key.extend_lifetime()))
-        })
+    pub fn from_value(value: &PyJSValue) -> PyResult<Option<Self>> {
+        let value = value.0.get()?;
+        Ok(with_js_cx(|cx| {
+            PropertyKey::from_value(cx, value).map(|key| key.extend_lifetime().into())
+        }))
     }
 
     pub fn to_owned_key(&self) -> PyResult<PyOwnedKey> {
+        let key = self.0.get()?;
         with_js_cx(|cx| {
-            self.0
-                .to_owned_key(cx)
+            key.to_owned_key(cx)
                 .map(|key| PyOwnedKey::from(key.extend_lifetime()))
                 .to_value_err("Failed to convert to owned key")
         })
     }
 
     // handle methods
-    pub fn is_void(&self) -> bool {
-        self.0.handle().is_void()
+    pub fn is_void(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_void())
     }
 
-    pub fn is_int(&self) -> bool {
-        self.0.handle().is_int()
+    pub fn is_int(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_int())
     }
 
-    pub fn is_string(&self) -> bool {
-        self.0.handle().is_string()
+    pub fn is_string(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_string())
     }
 
-    pub fn is_symbol(&self) -> bool {
-        self.0.handle().is_symbol()
+    pub fn is_symbol(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_symbol())
     }
 
     /// Garbage Collected Thing
-    pub fn is_gcthing(&self) -> bool {
-        self.0.handle().is_gcthing()
+    pub fn is_gcthing(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_gcthing())
     }
 
-    pub fn __str__(&self) -> String {
-        with_js_cx(|cx| {
-            let value = self.0.as_value(cx);
+    pub fn __str__(&self) -> PyResult<String> {
+        let key = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            let value = key.as_value(cx);
             value
                 .to_source(cx)
                 .to_owned(cx)
                 // remove the quotes
                 .map(|s| s[1..s.len() - 1].to_string())
                 .unwrap_or("undefined".to_string())
-        })
+        }))
     }
 
-    pub fn __repr__(&self) -> String {
-        with_js_cx(|cx| {
-            let value = self.0.as_value(cx);
+    pub fn __repr__(&self) -> PyResult<String> {
+        let key = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            let value = key.as_value(cx);
             let str = value
                 .to_source(cx)
                 .to_owned(cx)
@@ -125,7 +134,7 @@ impl PyPropertyKey {
                 .map(|s| s[1..s.len() - 1].to_string())
                 .unwrap_or("undefined".to_string());
             format!("PropertyKey({})", str)
-        })
+        }))
     }
 }
 
@@ -149,7 +158,7 @@ impl PyStubType for PropertyKeyTypes {
 impl<'py> FromPyObject<'py> for PropertyKeyTypes {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
         if let Ok(prop_key) = ob.extract::<PyPropertyKey>() {
-            return Ok(PropertyKeyTypes::Typed(prop_key.0));
+            return Ok(PropertyKeyTypes::Typed(prop_key.0.into_inner()?));
         }
 
         if let Ok(s) = ob.extract::<String>() {
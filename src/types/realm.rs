@@ -0,0 +1,147 @@
+use crate::capabilities::Capabilities;
+use crate::errors::{ErrorHandling, JSErrorHandling};
+use crate::handle::Handle;
+use crate::runtime::{with_execution_timeout, with_js_cx};
+use crate::traits::ExtendLifetime;
+use crate::types::object::PyJSObject;
+use crate::types::value::{JSValueLike, PyJSValue};
+use ion::object::default_new_global;
+use ion::script::Script;
+use ion::{Context, ErrorReport, Object, Value};
+use js_runtime::globals::init_globals;
+use mozjs::jsapi::{JS_WrapValue, JSAutoRealm};
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
+
+/// Wraps `value` for use in the realm `cx` is in. Objects of other compartments are replaced by
+/// cross-compartment wrappers, which forward every operation to the original object.
+fn wrap<'cx>(cx: &'cx Context, value: &Value) -> PyResult<Value<'cx>> {
+    let mut wrapped = Value::from(cx.root(value.get()));
+    if unsafe { JS_WrapValue(cx.as_ptr(), wrapped.handle_mut().into()) } {
+        Ok(wrapped)
+    } else {
+        Err(ErrorReport::new_with_exception_stack(cx)).to_js_err(cx)
+    }
+}
+
+/// An isolated realm with its own global object and built-ins, in its own compartment.
+///
+/// Scripts evaluated in different realms cannot see each other's globals. Values only cross
+/// realms explicitly, through [set](PyRealm::set) and [get](PyRealm::get), as
+/// cross-compartment wrappers of the original objects.
+///
+/// Values returned to Python are always wrapped for the main realm of the runtime, so they
+/// can be used like any other value.
+#[gen_stub_pyclass]
+#[pyclass(unsendable, name = "Realm", module = "js_exec.js_exec")]
+pub struct PyRealm {
+    global: Handle<Object<'static>>,
+}
+
+impl PyRealm {
+    /// Creates a realm whose global object has the standard globals of the runtime, restricted
+    /// to the given [Capabilities]. Timers and modules are only available in the main realm.
+    pub fn new(capabilities: Capabilities) -> PyResult<PyRealm> {
+        with_js_cx(|cx| {
+            let global = default_new_global(cx);
+            let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+            let initialized = global.set_as(cx, "global", &global.handle().get())
+                && init_globals(cx, &global)
+                && capabilities.standard_modules().init_globals(cx, &global);
+            if !initialized {
+                return Err(ErrorReport::new_with_exception_stack(cx)).to_js_err(cx);
+            }
+            capabilities.restrict(cx)?;
+            Ok(PyRealm {
+                global: Handle::new(global.extend_lifetime()),
+            })
+        })
+    }
+
+    /// Runs `f` in this realm, and wraps the value it returns for the main realm.
+    fn evaluate<F>(&self, f: F) -> PyResult<Value<'static>>
+    where
+        F: FnOnce(&Context) -> PyResult<Value<'static>>,
+    {
+        let global = self.global.get()?;
+        let value = with_js_cx(|cx| {
+            let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+            f(cx)
+        })?;
+        with_js_cx(|cx| wrap(cx, &value).map(ExtendLifetime::extend_lifetime))
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyRealm {
+    /// Returns the global object of the realm, wrapped for the main realm.
+    pub fn global_object(&self) -> PyResult<PyJSObject> {
+        let global = self.evaluate(|cx| {
+            let global = self.global.get()?;
+            Ok(Value::object(cx, global).extend_lifetime())
+        })?;
+        with_js_cx(|cx| Ok(global.to_object(cx).extend_lifetime().into()))
+    }
+
+    /// Compiles and evaluates a script in the realm, and returns its return value.
+    ///
+    /// # Arguments
+    /// * `source` - JavaScript source code to execute
+    /// * `filename` - Name to use in error messages (defaults to "inline.js")
+    /// * `timeout` - Time limit in seconds, which defaults to the timeout of the runtime
+    ///
+    /// # Errors
+    /// Raises a [JSError] subclass if the compilation or evaluation fails,
+    /// and [JSTimeoutError] if the evaluation exceeds the time limit
+    #[pyo3(signature = (source, filename = "inline.js", timeout = None))]
+    pub fn compile_and_evaluate_script(
+        &self,
+        source: &str,
+        filename: &str,
+        timeout: Option<f64>,
+    ) -> PyResult<PyJSValue> {
+        let result = with_execution_timeout(timeout, || {
+            self.evaluate(|cx| {
+                let result =
+                    Script::compile_and_evaluate(cx, filename.as_ref(), source).to_js_err(cx)?;
+                Ok(result.extend_lifetime())
+            })
+        })?;
+        Ok(result.into())
+    }
+
+    /// Transfers a value into the realm as the global variable `name`.
+    ///
+    /// Objects are shared rather than copied, so changes made in either realm are visible in
+    /// the other.
+    pub fn set(&self, name: &str, value: JSValueLike) -> PyResult<()> {
+        let global = self.global.get()?;
+        with_js_cx(|cx| {
+            let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+            let value = wrap(cx, &value.0)?;
+            if global.set(cx, name, &value) {
+                Ok(())
+            } else {
+                Err(ErrorReport::new_with_exception_stack(cx)).to_js_err(cx)
+            }
+        })
+    }
+
+    /// Transfers the global variable `name` of the realm out of it.
+    ///
+    /// Returns [None] if the realm has no global variable with that name.
+    pub fn get(&self, name: &str) -> PyResult<Option<PyJSValue>> {
+        let global = self.global.get()?;
+        let value = with_js_cx(|cx| {
+            let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+            let value = global
+                .get(cx, name)
+                .to_value_err("Failed to get global variable")?;
+            Ok::<_, PyErr>(value.map(ExtendLifetime::extend_lifetime))
+        })?;
+        value
+            .map(|value| with_js_cx(|cx| Ok(wrap(cx, &value)?.extend_lifetime().into())))
+            .transpose()
+    }
+}
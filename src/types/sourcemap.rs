@@ -1,7 +1,9 @@
+use crate::errors::ErrorHandling;
 use pyo3::prelude::*;
 use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
 use sourcemap::SourceMap;
 
+/// Maps positions of generated JavaScript back to the sources it was compiled from.
 #[gen_stub_pyclass]
 #[pyclass(unsendable, name = "SourceMap", module = "js_exec.js_exec")]
 pub struct PySourceMap(pub SourceMap);
@@ -17,9 +19,109 @@ impl From<SourceMap> for PySourceMap {
     }
 }
 
+/// Position in an original source, found by looking up a generated position in a [SourceMap].
+#[gen_stub_pyclass]
+#[pyclass(frozen, get_all, name = "OriginalPosition", module = "js_exec.js_exec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PyOriginalPosition {
+    /// Name of the original source.
+    pub source: String,
+    /// 1-based line number in the original source.
+    pub line: u32,
+    /// 1-based column number in the original source.
+    pub column: u32,
+    /// Original name of the identifier at the position, if the mapping records one.
+    pub name: Option<String>,
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyOriginalPosition {
+    pub fn __str__(&self) -> String {
+        format!("{}:{}:{}", self.source, self.line, self.column)
+    }
+
+    pub fn __repr__(&self) -> String {
+        match &self.name {
+            Some(name) => format!("OriginalPosition({}, name={name})", self.__str__()),
+            None => format!("OriginalPosition({})", self.__str__()),
+        }
+    }
+}
+
+/// Finds the original position of a 1-based generated position, using the closest mapping at or
+/// before it on the same line.
+pub fn original_position(
+    sourcemap: &SourceMap,
+    line: u32,
+    column: u32,
+) -> Option<PyOriginalPosition> {
+    let (line, column) = (line.checked_sub(1)?, column.saturating_sub(1));
+    let token = sourcemap.lookup_token(line, column)?;
+    if token.get_dst_line() != line {
+        return None;
+    }
+    Some(PyOriginalPosition {
+        source: String::from(token.get_source()?),
+        line: token.get_src_line() + 1,
+        column: token.get_src_col() + 1,
+        name: token.get_name().map(String::from),
+    })
+}
+
 #[gen_stub_pymethods]
 #[pymethods]
 impl PySourceMap {
+    /// Parses a source map from its JSON representation.
+    ///
+    /// # Errors
+    /// Raises [ValueError] if the JSON is not a valid source map.
+    #[staticmethod]
+    pub fn from_json(json: &str) -> PyResult<PySourceMap> {
+        let sourcemap =
+            SourceMap::from_slice(json.as_bytes()).to_value_err("Invalid source map")?;
+        Ok(PySourceMap(sourcemap))
+    }
+
+    /// Serialises the source map to JSON, in the format served to browsers and other tools.
+    pub fn to_json(&self) -> PyResult<String> {
+        let mut json = Vec::new();
+        self.0
+            .to_writer(&mut json)
+            .to_runtime_err("Failed to serialise source map")?;
+        String::from_utf8(json).to_runtime_err("Failed to serialise source map")
+    }
+
+    /// Finds the original position of a position in the generated code.
+    ///
+    /// # Arguments
+    /// * `line` - 1-based line number in the generated code
+    /// * `column` - 1-based column number in the generated code
+    ///
+    /// # Returns
+    /// The position in the original source, or [None] if the generated position is not mapped.
+    pub fn lookup(&self, line: u32, column: u32) -> Option<PyOriginalPosition> {
+        original_position(&self.0, line, column)
+    }
+
+    /// Returns the names of the original sources.
+    pub fn sources(&self) -> Vec<String> {
+        self.0.sources().map(String::from).collect()
+    }
+
+    /// Returns the original names of identifiers referenced by the mappings.
+    pub fn names(&self) -> Vec<String> {
+        self.0.names().map(String::from).collect()
+    }
+
+    /// Returns the contents of each original source, in the order of [PySourceMap::sources],
+    /// or [None] for sources whose contents are not embedded.
+    pub fn sources_content(&self) -> Vec<Option<String>> {
+        (0..self.0.get_source_count())
+            .map(|index| self.0.get_source_contents(index).map(String::from))
+            .collect()
+    }
+
     pub fn __repr__(&self) -> PyResult<String> {
         Ok(format!("SourceMap({:?})", self.0))
     }
@@ -0,0 +1,59 @@
+use ion::stack::{Location, StackRecord};
+use pyo3::prelude::*;
+use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
+
+/// Represents a single frame of a JavaScript stack trace.
+#[gen_stub_pyclass]
+#[pyclass(frozen, get_all, name = "StackFrame", module = "js_exec.js_exec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PyStackFrame {
+    /// Name of the function, or `None` for top-level and anonymous code.
+    pub function: Option<String>,
+    /// File the frame belongs to.
+    pub filename: String,
+    /// 1-based line number.
+    pub line: u32,
+    /// 1-based column number.
+    pub column: u32,
+    /// File of the executed code, which differs from `filename` if the frame was mapped
+    /// through a source map.
+    pub generated_filename: String,
+    /// 1-based line number in the executed code.
+    pub generated_line: u32,
+    /// 1-based column number in the executed code.
+    pub generated_column: u32,
+}
+
+impl PyStackFrame {
+    /// Creates a frame from a record whose location may have been mapped to its original
+    /// position, and the location of the record in the executed code.
+    pub fn new(record: &StackRecord, generated: &Location) -> PyStackFrame {
+        PyStackFrame {
+            function: record.function.clone(),
+            filename: record.location.file.clone(),
+            line: record.location.lineno,
+            column: record.location.column,
+            generated_filename: generated.file.clone(),
+            generated_line: generated.lineno,
+            generated_column: generated.column,
+        }
+    }
+}
+
+#[gen_stub_pymethods]
+#[pymethods]
+impl PyStackFrame {
+    pub fn __str__(&self) -> String {
+        format!(
+            "{}@{}:{}:{}",
+            self.function.as_deref().unwrap_or(""),
+            self.filename,
+            self.line,
+            self.column
+        )
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("StackFrame({})", self.__str__())
+    }
+}
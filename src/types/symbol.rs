@@ -1,3 +1,4 @@
+use crate::handle::Handle;
 use crate::runtime::with_js_cx;
 use crate::traits::ExtendLifetime;
 use ion::Symbol;
@@ -11,17 +12,18 @@ use std::fmt::Debug;
 
 #[gen_stub_pyclass]
 #[pyclass(unsendable, name = "Symbol", module = "js_exec.js_exec")]
-pub struct PySymbol(pub Symbol<'static>);
+pub struct PySymbol(pub Handle<Symbol<'static>>);
 
-impl From<PySymbol> for Symbol<'static> {
-    fn from(value: PySymbol) -> Self {
-        value.0
+impl TryFrom<PySymbol> for Symbol<'static> {
+    type Error = PyErr;
+    fn try_from(value: PySymbol) -> PyResult<Self> {
+        value.0.into_inner()
     }
 }
 
 impl From<Symbol<'static>> for PySymbol {
     fn from(value: Symbol<'static>) -> Self {
-        PySymbol(value)
+        PySymbol(Handle::new(value))
     }
 }
 
@@ -48,29 +50,33 @@ impl PySymbol {
 
     /// Returns the description of a [Symbol].
     /// Returns [None] for well-known symbols.
-    pub fn description(&self) -> Option<String> {
-        with_js_cx(|cx| self.0.description(cx))
+    pub fn description(&self) -> PyResult<Option<String>> {
+        let symbol = self.0.get()?;
+        Ok(with_js_cx(|cx| symbol.description(cx)))
     }
 
     /// Returns the identifying code of a [Symbol].
-    pub fn code(&self) -> PySymbolCode {
-        self.0.code().into()
+    pub fn code(&self) -> PyResult<PySymbolCode> {
+        Ok(self.0.get()?.code().into())
     }
 
     // handle methods
-    pub fn is_null(&self) -> bool {
-        self.0.handle().is_null()
+    pub fn is_null(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_null())
     }
 
-    pub fn is_aligned(&self) -> bool {
-        self.0.handle().is_aligned()
+    pub fn is_aligned(&self) -> PyResult<bool> {
+        Ok(self.0.get()?.handle().is_aligned())
     }
 
-    pub fn __str__(&self) -> String {
-        with_js_cx(|cx| format_symbol(cx, Config::default(), &self.0).to_string())
+    pub fn __str__(&self) -> PyResult<String> {
+        let symbol = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            format_symbol(cx, Config::default(), symbol).to_string()
+        }))
     }
 
-    pub fn __repr__(&self) -> String {
+    pub fn __repr__(&self) -> PyResult<String> {
         self.__str__()
     }
 }
@@ -1,4 +1,6 @@
+use crate::conversions::{DEFAULT_MAX_DEPTH, JSToPy, PyConversionFallback, PyToJS};
 use crate::errors::ErrorHandling;
+use crate::handle::Handle;
 use crate::runtime::with_js_cx;
 use crate::traits::ExtendLifetime;
 use crate::r#typeof;
@@ -12,9 +14,11 @@ use ion::format::{Config, format_value};
 use ion::{
     Function as JSFunction, Object as JSObject, Promise as JSPromise, Symbol, Value as JSValue,
 };
+use maplit::hashset;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use pyo3_stub_gen::derive::{gen_stub_pyclass, gen_stub_pymethods};
+use pyo3_stub_gen::{PyStubType, TypeInfo};
 
 // Macro for generating type checking methods
 macro_rules! define_is_methods {
@@ -24,8 +28,8 @@ macro_rules! define_is_methods {
         impl PyJSValue {
             $(
                 #[doc = $doc]
-                pub fn $method(&self) -> bool {
-                    self.0.handle().$method()
+                pub fn $method(&self) -> PyResult<bool> {
+                    Ok(self.0.get()?.handle().$method())
                 }
             )*
         }
@@ -40,11 +44,11 @@ macro_rules! define_to_methods {
         impl PyJSValue {
             $(
                 #[doc = $doc]
-                pub fn $method(&self) -> Option<$type> {
-                    if !self.$check() {
-                        return None;
+                pub fn $method(&self) -> PyResult<Option<$type>> {
+                    if !self.$check()? {
+                        return Ok(None);
                     }
-                    Some(self.0.handle().$method())
+                    Ok(Some(self.0.get()?.handle().$method()))
                 }
             )*
         }
@@ -61,8 +65,9 @@ macro_rules! define_conversion_methods {
             $(
                 #[doc = $doc]
                 pub fn $method(&self) -> PyResult<$py_type> {
+                    let value = self.0.get()?;
                     with_js_cx(|cx| {
-                        <$js_type>::from_value(cx, &self.0, true, ())
+                        <$js_type>::from_value(cx, value, true, ())
                             .to_value_err(concat!("Failed to convert to ", stringify!($js_type)))
                             .map(|x| x.extend_lifetime().into())
                     })
@@ -130,17 +135,18 @@ macro_rules! define_conversion_methods {
 #[gen_stub_pyclass]
 #[pyclass(unsendable, name = "JSValue", module = "js_exec.js_exec")]
 #[derive(Debug)]
-pub struct PyJSValue(pub JSValue<'static>);
+pub struct PyJSValue(pub Handle<JSValue<'static>>);
 
-impl From<PyJSValue> for JSValue<'static> {
-    fn from(value: PyJSValue) -> Self {
-        value.0
+impl TryFrom<PyJSValue> for JSValue<'static> {
+    type Error = PyErr;
+    fn try_from(value: PyJSValue) -> PyResult<Self> {
+        value.0.into_inner()
     }
 }
 
 impl From<JSValue<'static>> for PyJSValue {
     fn from(value: JSValue<'static>) -> Self {
-        PyJSValue(value)
+        PyJSValue(Handle::new(value))
     }
 }
 
@@ -152,7 +158,7 @@ impl<'a> TryFrom<&JSValue<'a>> for PyJSValue {
             let owned_value = JSValue::from_value(cx, value, true, ())
                 .to_runtime_err("Failed to convert to JSValue")?;
             println!("owned_value: {:?}", owned_value);
-            Ok(PyJSValue(owned_value.extend_lifetime()))
+            Ok(owned_value.extend_lifetime().into())
         })
     }
 }
@@ -160,9 +166,10 @@ impl<'a> TryFrom<&JSValue<'a>> for PyJSValue {
 impl<'py> FromPyObject<'py> for PyJSValue {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
         if let Ok(js_val) = ob.downcast::<PyJSValue>() {
+            let borrowed = js_val.borrow();
+            let value = borrowed.0.get()?;
             with_js_cx(|cx| {
-                let borrowed = js_val.borrow();
-                let value = JSValue::from_value(cx, &borrowed.0, true, ())
+                let value = JSValue::from_value(cx, value, true, ())
                     .to_value_err("Failed to convert to JSValue")?;
                 Ok(value.extend_lifetime().into())
             })
@@ -172,6 +179,34 @@ impl<'py> FromPyObject<'py> for PyJSValue {
     }
 }
 
+/// Argument type accepting either a [JSValue] or any Python object convertible with
+/// [JSValue::from_py].
+pub struct JSValueLike(pub JSValue<'static>);
+
+impl From<JSValueLike> for JSValue<'static> {
+    fn from(value: JSValueLike) -> Self {
+        value.0
+    }
+}
+
+impl PyStubType for JSValueLike {
+    fn type_output() -> TypeInfo {
+        TypeInfo {
+            name: "typing.Any".to_string(),
+            import: hashset! { "typing".into() },
+        }
+    }
+}
+
+impl<'py> FromPyObject<'py> for JSValueLike {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        with_js_cx(|cx| {
+            let value = PyToJS::new(cx, DEFAULT_MAX_DEPTH).convert(ob)?;
+            Ok(JSValueLike(value.extend_lifetime()))
+        })
+    }
+}
+
 #[gen_stub_pymethods]
 #[pymethods]
 impl PyJSValue {
@@ -207,32 +242,82 @@ impl PyJSValue {
 
     /// Creates a [JSValue] from a [JSBigInt].
     #[staticmethod]
-    pub fn bigint(value: &PyJSBigInt) -> Self {
-        with_js_cx(|cx| JSValue::bigint(cx, &value.0).extend_lifetime()).into()
+    pub fn bigint(value: &PyJSBigInt) -> PyResult<Self> {
+        let bigint = value.0.get()?;
+        Ok(with_js_cx(|cx| JSValue::bigint(cx, bigint).extend_lifetime()).into())
     }
 
     /// Creates a [JSValue] from a [Symbol].
     #[staticmethod]
-    pub fn symbol(value: &PySymbol) -> Self {
-        with_js_cx(|cx| JSValue::symbol(cx, &value.0).extend_lifetime()).into()
+    pub fn symbol(value: &PySymbol) -> PyResult<Self> {
+        let symbol = value.0.get()?;
+        Ok(with_js_cx(|cx| JSValue::symbol(cx, symbol).extend_lifetime()).into())
     }
 
     /// Compares two values for equality using the [SameValue algorithm](https://tc39.es/ecma262/multipage/abstract-operations.html#sec-samevalue).
     /// This is identical to strict equality (===), except that NaN's are equal and 0 !== -0.
-    pub fn is_same(&self, other: &Self) -> bool {
-        with_js_cx(|cx| self.0.is_same(cx, &other.0))
+    pub fn is_same(&self, other: &Self) -> PyResult<bool> {
+        let value = self.0.get()?;
+        let other = other.0.get()?;
+        Ok(with_js_cx(|cx| value.is_same(cx, other)))
     }
 
     /// Converts a [JSValue] to a string.
     pub fn to_source(&self) -> PyResult<String> {
+        let value = self.0.get()?;
         with_js_cx(|cx| {
-            let source = self.0.to_source(cx);
+            let source = value.to_source(cx);
             source
                 .to_owned(cx)
                 .to_value_err("Failed to convert to source")
         })
     }
 
+    /// Recursively converts the [JSValue] into the matching native Python object.
+    ///
+    /// Primitives map to `None`, `bool`, `int`, `float` and `str`, BigInts to `int`,
+    /// arrays to `list`, plain objects and `Map`s to `dict`, `Set`s to `set`, `Date`s to
+    /// timezone-aware `datetime`s and `ArrayBuffer`/`Uint8Array` to `bytes`.
+    /// Other typed arrays become a `list` of numbers.
+    ///
+    /// Values without a native counterpart (functions, symbols, promises, ...) are handled
+    /// according to `fallback`. Raises `ValueError` on cyclic structures or when nesting
+    /// exceeds `max_depth`.
+    #[pyo3(signature = (max_depth = DEFAULT_MAX_DEPTH, fallback = PyConversionFallback::Error))]
+    pub fn to_py(
+        &self,
+        py: Python<'_>,
+        max_depth: usize,
+        fallback: PyConversionFallback,
+    ) -> PyResult<PyObject> {
+        let value = self.0.get()?;
+        with_js_cx(|cx| {
+            JSToPy::new(cx, py, max_depth, fallback)
+                .convert(value)
+                .map(Bound::unbind)
+        })
+    }
+
+    /// Creates a [JSValue] by recursively converting a native Python object.
+    ///
+    /// `None` becomes `null`, `dict`s and other mappings become plain objects, `list`s and
+    /// `tuple`s become arrays, `bytes` become a `Uint8Array` and `datetime`s become a `Date`.
+    /// Integers outside the safe integer range become BigInts and `Decimal`s become numbers.
+    /// Awaitables become promises as with [JSPromise::from_awaitable], callables become
+    /// functions as with [JSFunction::from_py] and existing JavaScript wrappers are passed
+    /// through unchanged.
+    ///
+    /// Raises `TypeError` for unsupported objects and `ValueError` for cyclic structures
+    /// or when nesting exceeds `max_depth`.
+    #[staticmethod]
+    #[pyo3(signature = (obj, max_depth = DEFAULT_MAX_DEPTH))]
+    pub fn from_py(obj: &Bound<'_, PyAny>, max_depth: usize) -> PyResult<Self> {
+        with_js_cx(|cx| {
+            let value = PyToJS::new(cx, max_depth).convert(obj)?;
+            Ok(value.extend_lifetime().into())
+        })
+    }
+
     /// Creates an `undefined` [JSValue].
     #[staticmethod]
     pub fn undefined() -> Self {
@@ -246,27 +331,31 @@ impl PyJSValue {
     }
 
     /// basically "typeof"
-    pub fn debug_info(&self) -> String {
-        let type_str = r#typeof(self);
-        with_js_cx(|cx| {
-            let value_str = format_value(cx, Config::default(), &self.0).to_string();
+    pub fn debug_info(&self) -> PyResult<String> {
+        let type_str = r#typeof(self)?;
+        let value = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            let value_str = format_value(cx, Config::default(), value).to_string();
             format!("JSValue<{}>({})", type_str, value_str)
-        })
+        }))
     }
 
     // magic methods
-    pub fn __eq__(&self, other: &Self) -> bool {
+    pub fn __eq__(&self, other: &Self) -> PyResult<bool> {
         self.is_same(other)
     }
-    pub fn __ne__(&self, other: &Self) -> bool {
-        !self.is_same(other)
+    pub fn __ne__(&self, other: &Self) -> PyResult<bool> {
+        Ok(!self.is_same(other)?)
     }
 
-    pub fn __str__(&self) -> String {
-        with_js_cx(|cx| format_value(cx, Config::default(), &self.0).to_string())
+    pub fn __str__(&self) -> PyResult<String> {
+        let value = self.0.get()?;
+        Ok(with_js_cx(|cx| {
+            format_value(cx, Config::default(), value).to_string()
+        }))
     }
 
-    pub fn __repr__(&self) -> String {
+    pub fn __repr__(&self) -> PyResult<String> {
         self.debug_info()
     }
 }
@@ -0,0 +1,713 @@
+use crate::types::diagnostic::PyTypeScriptDiagnostic;
+use js_runtime::cache::map::save_sourcemap;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use sourcemap::SourceMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use swc_core::common::comments::SingleThreadedComments;
+use swc_core::common::errors::{DiagnosticBuilder, Emitter, HANDLER, Handler, Level};
+use swc_core::common::source_map::SourceMapGenConfig;
+use swc_core::common::sync::Lrc;
+use swc_core::common::{
+    FileName, GLOBALS, Globals, Mark, SourceMap as SwcSourceMap, Span, Spanned,
+};
+use swc_core::ecma::ast::{
+    ArrowExpr, AssignExpr, AssignOp, BigInt, BinExpr, BinaryOp, CatchClause, ClassProp, Decl,
+    EsVersion, ForOfStmt, Function, ModuleDecl, ModuleItem, ObjectLit, ObjectPat, ObjectPatProp,
+    OptChainExpr, PrivateMethod, PrivateProp, Program, PropOrSpread, StaticBlock, Stmt, TsEnumDecl,
+    TsExportAssignment, TsImportEqualsDecl, TsModuleDecl, TsNamespaceBody, TsParamProp,
+};
+use swc_core::ecma::codegen::text_writer::JsWriter;
+use swc_core::ecma::codegen::{Config as CodegenConfig, Emitter as CodeEmitter};
+use swc_core::ecma::parser::error::{Error as ParseError, SyntaxError};
+use swc_core::ecma::parser::lexer::Lexer;
+use swc_core::ecma::parser::{Parser, StringInput, Syntax, TsSyntax};
+use swc_core::ecma::transforms::base::fixer::fixer;
+use swc_core::ecma::transforms::base::helpers::{HELPERS, Helpers, inject_helpers};
+use swc_core::ecma::transforms::base::hygiene::hygiene;
+use swc_core::ecma::transforms::base::resolver;
+use swc_core::ecma::transforms::module::common_js::{self, common_js};
+use swc_core::ecma::transforms::module::path::Resolver;
+use swc_core::ecma::transforms::proposal::decorators::{self, decorators};
+use swc_core::ecma::transforms::react::{self, react};
+use swc_core::ecma::transforms::typescript::{self, TsxConfig, tsx};
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+/// Code of the diagnostics for syntax which cannot be erased in strip-only mode, matching the
+/// error of `tsc` for its `erasableSyntaxOnly` option.
+const NOT_ERASABLE_CODE: &str = "TS1294";
+
+/// How JSX in TypeScript sources is compiled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JsxMode {
+    /// JSX is left unchanged.
+    Preserve,
+    /// JSX is compiled to calls of `React.createElement`.
+    React,
+    /// JSX is compiled to calls of the automatic runtime of `react/jsx-runtime`.
+    ReactJsx,
+}
+
+/// Module format of compiled TypeScript.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ModuleFormat {
+    /// ECMAScript `import` and `export` statements are left unchanged.
+    EsNext,
+    /// Imports and exports are compiled to `require` and `exports`.
+    CommonJs,
+}
+
+/// Options for compiling TypeScript, named after the matching options of `tsc`.
+#[derive(Clone, Debug)]
+pub struct TypeScriptOptions {
+    pub target: EsVersion,
+    pub jsx: Option<JsxMode>,
+    pub module: ModuleFormat,
+    pub strip_only: bool,
+    pub emit_decorators: bool,
+    /// Function called for JSX elements by [JsxMode::React], `React.createElement` if unset.
+    pub jsx_factory: Option<String>,
+    /// Component of JSX fragments for [JsxMode::React], `React.Fragment` if unset.
+    pub jsx_fragment: Option<String>,
+    /// Module which [JsxMode::ReactJsx] imports `jsx-runtime` from, `react` if unset.
+    pub jsx_import_source: Option<String>,
+}
+
+impl Default for TypeScriptOptions {
+    fn default() -> TypeScriptOptions {
+        TypeScriptOptions {
+            target: EsVersion::EsNext,
+            jsx: None,
+            module: ModuleFormat::EsNext,
+            strip_only: false,
+            emit_decorators: false,
+            jsx_factory: None,
+            jsx_fragment: None,
+            jsx_import_source: None,
+        }
+    }
+}
+
+impl TypeScriptOptions {
+    /// Creates options from their names, as accepted by `tsc`.
+    ///
+    /// The target only selects the syntax which is allowed, as syntax is not compiled down to
+    /// older versions. Targets before ES2015 are rejected, and [compile_typescript] reports
+    /// syntax which is newer than the target, rather than producing output which still uses it.
+    ///
+    /// # Errors
+    /// Raises [ValueError] for unknown or unsupported targets, JSX modes and module formats.
+    pub fn new(
+        target: &str,
+        jsx: Option<&str>,
+        module: &str,
+        strip_only: bool,
+        emit_decorators: bool,
+    ) -> PyResult<TypeScriptOptions> {
+        let target = match target.to_ascii_lowercase().as_str() {
+            "es3" | "es5" => {
+                return Err(PyValueError::new_err(format!(
+                    "Target \"{target}\" is not supported as syntax is not compiled down, \
+                     expected \"es2015\" to \"es2022\" or \"esnext\""
+                )));
+            }
+            "es6" | "es2015" => EsVersion::Es2015,
+            "es2016" => EsVersion::Es2016,
+            "es2017" => EsVersion::Es2017,
+            "es2018" => EsVersion::Es2018,
+            "es2019" => EsVersion::Es2019,
+            "es2020" => EsVersion::Es2020,
+            "es2021" => EsVersion::Es2021,
+            "es2022" => EsVersion::Es2022,
+            "esnext" => EsVersion::EsNext,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown target \"{target}\", expected \"es2015\" to \"es2022\" or \"esnext\""
+                )));
+            }
+        };
+        let jsx = jsx
+            .map(|jsx| match jsx {
+                "preserve" => Ok(JsxMode::Preserve),
+                "react" => Ok(JsxMode::React),
+                "react-jsx" => Ok(JsxMode::ReactJsx),
+                _ => Err(PyValueError::new_err(format!(
+                    "Unknown JSX mode \"{jsx}\", expected \"preserve\", \"react\" or \"react-jsx\""
+                ))),
+            })
+            .transpose()?;
+        let module = match module.to_ascii_lowercase().as_str() {
+            "es2015" | "es2020" | "es2022" | "esnext" => ModuleFormat::EsNext,
+            "commonjs" => ModuleFormat::CommonJs,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown module format \"{module}\", expected \"esnext\" or \"commonjs\""
+                )));
+            }
+        };
+        Ok(TypeScriptOptions {
+            target,
+            jsx,
+            module,
+            strip_only,
+            emit_decorators,
+            ..TypeScriptOptions::default()
+        })
+    }
+
+    /// Sets the factory and fragment of classic JSX, or the import source of the automatic
+    /// runtime, as `jsxFactory`, `jsxFragmentFactory` and `jsxImportSource` of `tsc` do.
+    ///
+    /// # Errors
+    /// Raises [ValueError] if an option does not apply to the JSX mode.
+    pub fn with_jsx_pragmas(
+        self,
+        factory: Option<&str>,
+        fragment: Option<&str>,
+        import_source: Option<&str>,
+    ) -> PyResult<TypeScriptOptions> {
+        if (factory.is_some() || fragment.is_some()) && self.jsx != Some(JsxMode::React) {
+            return Err(PyValueError::new_err(
+                "The JSX factory and fragment require the \"react\" JSX mode",
+            ));
+        }
+        if import_source.is_some() && self.jsx != Some(JsxMode::ReactJsx) {
+            return Err(PyValueError::new_err(
+                "The JSX import source requires the \"react-jsx\" JSX mode",
+            ));
+        }
+        Ok(TypeScriptOptions {
+            jsx_factory: factory.map(String::from),
+            jsx_fragment: fragment.map(String::from),
+            jsx_import_source: import_source.map(String::from),
+            ..self
+        })
+    }
+
+    /// Returns the options for compiling a source in the given module format, which only
+    /// compiles JSX if the source is JSX or TSX.
+    pub fn for_source(&self, jsx: bool, module: ModuleFormat) -> TypeScriptOptions {
+        TypeScriptOptions {
+            jsx: self.jsx.filter(|_| jsx),
+            module,
+            ..self.clone()
+        }
+    }
+}
+
+/// A problem reported while compiling, located by its [Span].
+struct SpannedDiagnostic {
+    code: Option<String>,
+    message: String,
+    span: Span,
+}
+
+/// Collects the errors reported to the [Handler] by transforms.
+struct DiagnosticCollector(Arc<Mutex<Vec<SpannedDiagnostic>>>);
+
+impl Emitter for DiagnosticCollector {
+    fn emit(&mut self, db: &mut DiagnosticBuilder<'_>) {
+        if !matches!(
+            db.level,
+            Level::Bug | Level::Fatal | Level::PhaseFatal | Level::Error
+        ) {
+            return;
+        }
+        // Transforms do not report errors with the codes of `tsc`.
+        let span = db.span.primary_span().unwrap_or_default();
+        self.0.lock().unwrap().push(SpannedDiagnostic {
+            code: None,
+            message: db.message(),
+            span,
+        });
+    }
+}
+
+/// Returns the `tsc` code of the syntax errors which the parser reports under it.
+macro_rules! typescript_codes {
+    ($kind:expr, $($code:ident),* $(,)?) => {
+        match $kind {
+            $(SyntaxError::$code { .. } => Some(stringify!($code)),)*
+            _ => None,
+        }
+    };
+}
+
+/// Returns the `tsc` code of a syntax error, or [None] if `tsc` has no matching error.
+fn typescript_code(kind: &SyntaxError) -> Option<&'static str> {
+    typescript_codes!(
+        kind, TS1003, TS1005, TS1009, TS1014, TS1015, TS1029, TS1030, TS1031, TS1038, TS1042,
+        TS1047, TS1048, TS1056, TS1085, TS1089, TS1092, TS1093, TS1096, TS1098, TS1100, TS1102,
+        TS1105, TS1106, TS1107, TS1109, TS1110, TS1114, TS1115, TS1116, TS1123, TS1141, TS1162,
+        TS1164, TS1171, TS1172, TS1173, TS1174, TS1175, TS1183, TS1184, TS1185, TS1196, TS1242,
+        TS1243, TS1244, TS1245, TS1267, TS1273, TS1274, TS1277, TS2206, TS2207, TS2369, TS2371,
+        TS2406, TS2410, TS2414, TS2427, TS2452, TS2483, TS2491, TS2499, TS2703, TS4112, TS8038,
+        TS18010,
+    )
+}
+
+/// Converts a syntax error of the parser into a diagnostic.
+fn syntax_diagnostic(error: ParseError) -> SpannedDiagnostic {
+    SpannedDiagnostic {
+        code: typescript_code(error.kind()).map(String::from),
+        message: error.kind().msg().into_owned(),
+        span: error.span(),
+    }
+}
+
+/// Finds syntax with runtime semantics, which cannot be compiled by only erasing types.
+#[derive(Default)]
+struct ErasableSyntaxChecker(Vec<SpannedDiagnostic>);
+
+impl ErasableSyntaxChecker {
+    fn report(&mut self, span: Span, syntax: &str) {
+        self.0.push(SpannedDiagnostic {
+            code: Some(String::from(NOT_ERASABLE_CODE)),
+            message: format!("{syntax} are not supported when only stripping types"),
+            span,
+        });
+    }
+}
+
+/// Checks if a namespace declares values, rather than only types.
+fn is_instantiated(body: &TsNamespaceBody) -> bool {
+    match body {
+        TsNamespaceBody::TsModuleBlock(block) => block.body.iter().any(|item| {
+            let decl = match item {
+                ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+                _ => return true,
+            };
+            match decl {
+                Decl::TsInterface(_) | Decl::TsTypeAlias(_) => false,
+                Decl::TsModule(module) => {
+                    !module.declare && module.body.as_ref().is_some_and(is_instantiated)
+                }
+                _ => true,
+            }
+        }),
+        TsNamespaceBody::TsNamespaceDecl(namespace) => is_instantiated(&namespace.body),
+    }
+}
+
+impl Visit for ErasableSyntaxChecker {
+    fn visit_ts_enum_decl(&mut self, decl: &TsEnumDecl) {
+        if !decl.declare {
+            self.report(decl.span, "Enums");
+        }
+    }
+
+    fn visit_ts_module_decl(&mut self, decl: &TsModuleDecl) {
+        if !decl.declare && decl.body.as_ref().is_some_and(is_instantiated) {
+            self.report(decl.span, "Namespaces with values");
+        } else {
+            decl.visit_children_with(self);
+        }
+    }
+
+    fn visit_ts_param_prop(&mut self, prop: &TsParamProp) {
+        self.report(prop.span, "Parameter properties");
+        prop.visit_children_with(self);
+    }
+
+    fn visit_ts_import_equals_decl(&mut self, decl: &TsImportEqualsDecl) {
+        if !decl.is_type_only {
+            self.report(decl.span, "`import =` aliases");
+        }
+    }
+
+    fn visit_ts_export_assignment(&mut self, assignment: &TsExportAssignment) {
+        self.report(assignment.span, "`export =` assignments");
+    }
+}
+
+/// Finds syntax which is newer than the target, as it is not compiled down to older versions.
+struct TargetSyntaxChecker {
+    target: EsVersion,
+    diagnostics: Vec<SpannedDiagnostic>,
+}
+
+impl TargetSyntaxChecker {
+    fn new(target: EsVersion) -> TargetSyntaxChecker {
+        TargetSyntaxChecker {
+            target,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn check(&mut self, span: Span, syntax: &str, version: EsVersion) {
+        if self.target < version {
+            self.diagnostics.push(SpannedDiagnostic {
+                code: None,
+                message: format!(
+                    "{syntax} require a target of {} or later, as syntax is not compiled down",
+                    version_name(version)
+                ),
+                span,
+            });
+        }
+    }
+}
+
+/// Returns the name of an ECMAScript version, as accepted by [TypeScriptOptions::new].
+fn version_name(version: EsVersion) -> &'static str {
+    match version {
+        EsVersion::Es3 => "ES3",
+        EsVersion::Es5 => "ES5",
+        EsVersion::Es2015 => "ES2015",
+        EsVersion::Es2016 => "ES2016",
+        EsVersion::Es2017 => "ES2017",
+        EsVersion::Es2018 => "ES2018",
+        EsVersion::Es2019 => "ES2019",
+        EsVersion::Es2020 => "ES2020",
+        EsVersion::Es2021 => "ES2021",
+        EsVersion::Es2022 => "ES2022",
+        _ => "ESNext",
+    }
+}
+
+impl Visit for TargetSyntaxChecker {
+    fn visit_bin_expr(&mut self, expr: &BinExpr) {
+        match expr.op {
+            BinaryOp::Exp => self.check(expr.span, "Exponentiation operators", EsVersion::Es2016),
+            BinaryOp::NullishCoalescing => {
+                self.check(expr.span, "Nullish coalescing operators", EsVersion::Es2020)
+            }
+            _ => {}
+        }
+        expr.visit_children_with(self);
+    }
+
+    fn visit_assign_expr(&mut self, expr: &AssignExpr) {
+        match expr.op {
+            AssignOp::ExpAssign => {
+                self.check(expr.span, "Exponentiation operators", EsVersion::Es2016)
+            }
+            AssignOp::AndAssign | AssignOp::OrAssign | AssignOp::NullishAssign => {
+                self.check(expr.span, "Logical assignment operators", EsVersion::Es2021)
+            }
+            _ => {}
+        }
+        expr.visit_children_with(self);
+    }
+
+    fn visit_function(&mut self, function: &Function) {
+        match (function.is_async, function.is_generator) {
+            (true, true) => self.check(function.span, "Async generators", EsVersion::Es2018),
+            (true, false) => self.check(function.span, "Async functions", EsVersion::Es2017),
+            _ => {}
+        }
+        function.visit_children_with(self);
+    }
+
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        if arrow.is_async {
+            self.check(arrow.span, "Async functions", EsVersion::Es2017);
+        }
+        arrow.visit_children_with(self);
+    }
+
+    fn visit_for_of_stmt(&mut self, stmt: &ForOfStmt) {
+        if stmt.is_await {
+            self.check(stmt.span, "`for await` loops", EsVersion::Es2018);
+        }
+        stmt.visit_children_with(self);
+    }
+
+    fn visit_object_lit(&mut self, lit: &ObjectLit) {
+        if lit
+            .props
+            .iter()
+            .any(|prop| matches!(prop, PropOrSpread::Spread(_)))
+        {
+            self.check(lit.span, "Object spread properties", EsVersion::Es2018);
+        }
+        lit.visit_children_with(self);
+    }
+
+    fn visit_object_pat(&mut self, pat: &ObjectPat) {
+        if pat
+            .props
+            .iter()
+            .any(|prop| matches!(prop, ObjectPatProp::Rest(_)))
+        {
+            self.check(pat.span, "Object rest properties", EsVersion::Es2018);
+        }
+        pat.visit_children_with(self);
+    }
+
+    fn visit_catch_clause(&mut self, clause: &CatchClause) {
+        if clause.param.is_none() {
+            self.check(clause.span, "Optional catch bindings", EsVersion::Es2019);
+        }
+        clause.visit_children_with(self);
+    }
+
+    fn visit_opt_chain_expr(&mut self, expr: &OptChainExpr) {
+        self.check(expr.span, "Optional chains", EsVersion::Es2020);
+        expr.visit_children_with(self);
+    }
+
+    fn visit_big_int(&mut self, literal: &BigInt) {
+        self.check(literal.span, "BigInt literals", EsVersion::Es2020);
+    }
+
+    fn visit_class_prop(&mut self, prop: &ClassProp) {
+        self.check(prop.span, "Class fields", EsVersion::Es2022);
+        prop.visit_children_with(self);
+    }
+
+    fn visit_private_prop(&mut self, prop: &PrivateProp) {
+        self.check(prop.span, "Private class members", EsVersion::Es2022);
+        prop.visit_children_with(self);
+    }
+
+    fn visit_private_method(&mut self, method: &PrivateMethod) {
+        self.check(method.span, "Private class members", EsVersion::Es2022);
+        method.visit_children_with(self);
+    }
+
+    fn visit_static_block(&mut self, block: &StaticBlock) {
+        self.check(block.span, "Static blocks", EsVersion::Es2022);
+        block.visit_children_with(self);
+    }
+}
+
+/// Embeds the sources in the generated source maps, so they can be used without the files.
+struct SourceMapConfig;
+
+impl SourceMapGenConfig for SourceMapConfig {
+    fn file_name_to_source(&self, name: &FileName) -> String {
+        name.to_string()
+    }
+
+    fn inline_sources_content(&self, _: &FileName) -> bool {
+        true
+    }
+}
+
+/// Converts located diagnostics into [PyTypeScriptDiagnostic]s with 1-based positions.
+fn locate(
+    cm: &SwcSourceMap,
+    filename: &str,
+    diagnostics: Vec<SpannedDiagnostic>,
+) -> Vec<PyTypeScriptDiagnostic> {
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| {
+            let location = cm.lookup_char_pos(diagnostic.span.lo);
+            PyTypeScriptDiagnostic {
+                code: diagnostic.code,
+                message: diagnostic.message,
+                filename: String::from(filename),
+                line: location.line as u32,
+                column: location.col.0 as u32 + 1,
+            }
+        })
+        .collect()
+}
+
+/// Formats diagnostics with one line per diagnostic, in the style of `tsc`.
+pub fn format_diagnostics(diagnostics: &[PyTypeScriptDiagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(PyTypeScriptDiagnostic::__str__)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Compiles the TypeScript source of a module and registers its source map under `path`, so
+/// errors raised by the module refer to its original lines.
+///
+/// Returns the formatted diagnostics if the source cannot be compiled.
+pub fn compile_module(
+    path: &Path,
+    source: &str,
+    options: &TypeScriptOptions,
+) -> Result<String, String> {
+    let filename = path.to_string_lossy();
+    let (script, sourcemap) =
+        compile_typescript(&filename, source, options).map_err(|diagnostics| {
+            format!(
+                "Failed to compile {}:\n{}",
+                path.display(),
+                format_diagnostics(&diagnostics)
+            )
+        })?;
+    save_sourcemap(path, sourcemap);
+    Ok(script)
+}
+
+/// Compiles TypeScript into JavaScript and a source map which maps it back to `source`.
+///
+/// Returns the diagnostics of all errors if the source cannot be compiled.
+pub fn compile_typescript(
+    filename: &str,
+    source: &str,
+    options: &TypeScriptOptions,
+) -> Result<(String, SourceMap), Vec<PyTypeScriptDiagnostic>> {
+    let cm: Lrc<SwcSourceMap> = Lrc::default();
+    let file = cm.new_source_file(
+        Lrc::new(FileName::Real(PathBuf::from(filename))),
+        String::from(source),
+    );
+    let comments = SingleThreadedComments::default();
+
+    let syntax = Syntax::Typescript(TsSyntax {
+        tsx: options.jsx.is_some(),
+        decorators: true,
+        ..TsSyntax::default()
+    });
+    let lexer = Lexer::new(
+        syntax,
+        options.target,
+        StringInput::from(&*file),
+        Some(&comments),
+    );
+    let mut parser = Parser::new_from(lexer);
+    let program = parser.parse_program();
+    let mut errors = parser.take_errors();
+    let program = match program {
+        Ok(program) if errors.is_empty() => program,
+        result => {
+            errors.extend(result.err());
+            let diagnostics = errors.into_iter().map(syntax_diagnostic).collect();
+            return Err(locate(&cm, filename, diagnostics));
+        }
+    };
+
+    let mut checker = ErasableSyntaxChecker::default();
+    if options.strip_only {
+        program.visit_with(&mut checker);
+        if !checker.0.is_empty() {
+            return Err(locate(&cm, filename, checker.0));
+        }
+    }
+
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    let handler =
+        Handler::with_emitter(true, false, Box::new(DiagnosticCollector(reported.clone())));
+    let program = GLOBALS.set(&Globals::new(), || {
+        HELPERS.set(&Helpers::new(false), || {
+            HANDLER.set(&handler, || transform(program, &cm, &comments, options))
+        })
+    });
+    let reported = std::mem::take(&mut *reported.lock().unwrap());
+    if !reported.is_empty() {
+        return Err(locate(&cm, filename, reported));
+    }
+
+    let mut checker = TargetSyntaxChecker::new(options.target);
+    program.visit_with(&mut checker);
+    if !checker.diagnostics.is_empty() {
+        return Err(locate(&cm, filename, checker.diagnostics));
+    }
+
+    let mut code = Vec::new();
+    let mut mappings = Vec::new();
+    let mut emitter = CodeEmitter {
+        cfg: CodegenConfig::default().with_target(options.target),
+        cm: cm.clone(),
+        comments: Some(&comments),
+        wr: JsWriter::new(cm.clone(), "\n", &mut code, Some(&mut mappings)),
+    };
+    let emitted = emitter.emit_program(&program);
+    drop(emitter);
+    let emit_error = |message: String| {
+        vec![PyTypeScriptDiagnostic {
+            code: None,
+            message,
+            filename: String::from(filename),
+            line: 1,
+            column: 1,
+        }]
+    };
+    emitted.map_err(|error| emit_error(error.to_string()))?;
+    let code = String::from_utf8(code).map_err(|error| emit_error(error.to_string()))?;
+
+    // The source map of swc may be of another version of the `sourcemap` crate.
+    let mut map = Vec::new();
+    cm.build_source_map(&mappings, None, SourceMapConfig)
+        .to_writer(&mut map)
+        .map_err(|error| emit_error(error.to_string()))?;
+    let map = SourceMap::from_slice(&map).map_err(|error| emit_error(error.to_string()))?;
+    Ok((code, map))
+}
+
+/// Applies the transforms selected by `options`, which turn a parsed TypeScript program into
+/// JavaScript. Must be called with the swc globals, helpers and handler set.
+fn transform(
+    mut program: Program,
+    cm: &Lrc<SwcSourceMap>,
+    comments: &SingleThreadedComments,
+    options: &TypeScriptOptions,
+) -> Program {
+    let unresolved_mark = Mark::new();
+    let top_level_mark = Mark::new();
+    program.mutate(resolver(unresolved_mark, top_level_mark, true));
+
+    if options.emit_decorators {
+        program.mutate(decorators(decorators::Config {
+            legacy: true,
+            emit_metadata: true,
+            use_define_for_class_fields: false,
+        }));
+    }
+
+    let config = typescript::Config::default();
+    match options.jsx {
+        Some(JsxMode::React | JsxMode::ReactJsx) => {
+            let react_options = react::Options {
+                runtime: Some(if options.jsx == Some(JsxMode::React) {
+                    react::Runtime::Classic
+                } else {
+                    react::Runtime::Automatic
+                }),
+                pragma: options.jsx_factory.clone().map(Into::into),
+                pragma_frag: options.jsx_fragment.clone().map(Into::into),
+                import_source: options.jsx_import_source.clone().map(Into::into),
+                ..react::Options::default()
+            };
+            // Imports which are only used by the JSX factory must not be elided as unused.
+            let tsx_config = TsxConfig {
+                pragma: options.jsx_factory.clone(),
+                pragma_frag: options.jsx_fragment.clone(),
+            };
+            program.mutate(tsx(
+                cm.clone(),
+                config,
+                tsx_config,
+                comments,
+                unresolved_mark,
+                top_level_mark,
+            ));
+            program.mutate(react(
+                cm.clone(),
+                Some(comments),
+                react_options,
+                top_level_mark,
+                unresolved_mark,
+            ));
+        }
+        _ => program.mutate(typescript::typescript(
+            config,
+            unresolved_mark,
+            top_level_mark,
+        )),
+    }
+
+    if options.module == ModuleFormat::CommonJs {
+        program.mutate(common_js(
+            Resolver::Default,
+            unresolved_mark,
+            common_js::Config::default(),
+            common_js::FeatureFlag::default(),
+        ));
+    }
+
+    program.mutate(inject_helpers(unresolved_mark));
+    program.mutate(hygiene());
+    program.mutate(fixer(Some(comments)));
+    program
+}
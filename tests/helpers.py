@@ -0,0 +1,16 @@
+import json
+import subprocess
+import sys
+import textwrap
+
+
+def run_in_new_runtime(code, cwd):
+    """Runs code in a fresh interpreter, since only one Runtime can be configured per process.
+
+    The code has access to everything exported by `js_exec` and must assign a JSON-serialisable `result`.
+    """
+    script = "import json\nfrom js_exec import *\n" + textwrap.dedent(code) + "\nprint(json.dumps(result))"
+    completed = subprocess.run([sys.executable, "-c", script], cwd=cwd, capture_output=True, text=True)
+    if completed.returncode != 0:
+        raise AssertionError(completed.stderr)
+    return json.loads(completed.stdout.splitlines()[-1])
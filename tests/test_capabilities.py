@@ -0,0 +1,164 @@
+import os
+import tempfile
+import textwrap
+import unittest
+
+from helpers import run_in_new_runtime
+
+# Prelude for the subprocesses, which reports whether evaluating some code raises a JSError.
+PRELUDE = """
+def throws(runtime, source):
+    try:
+        runtime.compile_and_evaluate_script(source)
+        return False
+    except JSError:
+        return True
+
+def import_fails(runtime, source):
+    try:
+        _, promise = runtime.compile_and_evaluate_module(source, "main.js", "main.js")
+        runtime.run_event_loop()
+        return promise is not None and promise.state() == JSPromiseState.Rejected
+    except JSError:
+        return True
+
+def import_error(runtime, source):
+    try:
+        _, promise = runtime.compile_and_evaluate_module(source, "main.js", "main.js")
+        runtime.run_event_loop()
+        return promise.result().to_py()["message"] if promise is not None else None
+    except JSError as error:
+        return error.message
+"""
+
+
+def run_sandboxed(code, cwd=None):
+    """Runs code in a new runtime, with the helpers of the prelude available."""
+    return run_in_new_runtime(PRELUDE + textwrap.dedent(code), cwd or os.getcwd())
+
+
+class CapabilityTests(unittest.TestCase):
+    """Tests for runtimes created with restricted capabilities."""
+
+    def test_defaults(self):
+        """Test that runtimes allow everything by default."""
+        result = run_sandboxed(
+            """
+            runtime = Runtime()
+            runtime.register_module("lib.js", "export default 1;")
+            result = [
+                throws(runtime, "typeof fetch"),
+                throws(runtime, "setTimeout(() => {}, 0)"),
+                throws(runtime, "eval('1 + 1')"),
+                import_fails(runtime, "import value from './lib.js';"),
+            ]
+            """
+        )
+        self.assertEqual(result, [False, False, False, False])
+
+    def test_deny_net(self):
+        """Test that fetch throws when network access is disallowed."""
+        result = run_sandboxed(
+            """
+            runtime = Runtime(allow_net=False)
+            result = [
+                throws(runtime, "fetch('https://example.com')"),
+                throws(runtime, "delete globalThis.fetch; globalThis.fetch = () => 1; fetch()"),
+            ]
+            """
+        )
+        self.assertEqual(result, [True, True])
+
+    def test_deny_timers(self):
+        """Test that timers throw when they are disallowed."""
+        result = run_sandboxed(
+            """
+            runtime = Runtime(allow_timers=False)
+            result = [
+                throws(runtime, "setTimeout(() => {}, 0)"),
+                throws(runtime, "setInterval(() => {}, 10)"),
+                throws(runtime, "Promise.resolve(1).then((x) => x + 1); 1"),
+            ]
+            """
+        )
+        self.assertEqual(result, [True, True, False])
+
+    def test_deny_eval(self):
+        """Test that strings cannot be compiled when eval is disallowed."""
+        result = run_sandboxed(
+            """
+            runtime = Runtime(allow_eval=False)
+            result = [
+                throws(runtime, "eval('1 + 1')"),
+                throws(runtime, "new Function('return 1')()"),
+                throws(runtime, "(() => 1)()"),
+            ]
+            """
+        )
+        self.assertEqual(result, [True, True, False])
+
+    def test_deny_fs(self):
+        """Test that modules on disk cannot be loaded when filesystem access is disallowed."""
+        with tempfile.TemporaryDirectory() as directory:
+            with open(os.path.join(directory, "lib.js"), "w") as file:
+                file.write("export default 1;")
+            result = run_sandboxed(
+                """
+                runtime = Runtime(allow_fs=False)
+                runtime.register_module("virtual.js", "export default 2;")
+                result = [
+                    import_fails(runtime, "import value from './lib.js';"),
+                    import_fails(runtime, "import fs from 'fs';"),
+                    import_fails(runtime, "import value from './virtual.js'; globalThis.value = value;"),
+                    runtime.compile_and_evaluate_script("globalThis.value").to_py(),
+                ]
+                """,
+                directory,
+            )
+        self.assertEqual(result, [True, True, False, 2])
+
+    def test_deny_fs_packages(self):
+        """Test that denied packages fail alike whether they exist or not, so the disk is not probed."""
+        with tempfile.TemporaryDirectory() as directory:
+            package = os.path.join(directory, "node_modules", "present")
+            os.makedirs(package)
+            with open(os.path.join(package, "package.json"), "w") as file:
+                file.write('{"name": "present", "main": "index.js"}')
+            with open(os.path.join(package, "index.js"), "w") as file:
+                file.write("export default 1;")
+            result = run_sandboxed(
+                """
+                runtime = Runtime(allow_fs=False)
+                runtime.register_module("registered", "export default 2;")
+                result = [
+                    import_error(runtime, "import value from 'present';").replace("present", "<name>"),
+                    import_error(runtime, "import value from 'missing';").replace("missing", "<name>"),
+                    import_fails(runtime, "import value from 'registered'; globalThis.value = value;"),
+                    runtime.compile_and_evaluate_script("globalThis.value").to_py(),
+                ]
+                """,
+                directory,
+            )
+        self.assertEqual(result[0], result[1])
+        self.assertIn("filesystem access is not allowed", result[0])
+        self.assertEqual(result[2:], [False, 2])
+
+    def test_deny_modules(self):
+        """Test that every import fails when modules are disallowed."""
+        result = run_sandboxed(
+            """
+            runtime = Runtime(allow_modules=False)
+            runtime.register_module("virtual.js", "export default 2;")
+            result = [
+                import_fails(runtime, "import value from './virtual.js';"),
+                import_fails(runtime, "import * as path from 'path';"),
+                import_fails(runtime, "globalThis.value = 3;"),
+                runtime.compile_and_evaluate_script("globalThis.value").to_py(),
+            ]
+            """
+        )
+        self.assertEqual(result, [True, True, False, 3])
+
+
+if __name__ == "__main__":
+    unittest.main()
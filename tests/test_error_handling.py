@@ -1,5 +1,15 @@
 import unittest
-from js_exec import Runtime
+from js_exec import (
+    Runtime,
+    JSError,
+    JSSyntaxError,
+    JSTypeError,
+    JSRangeError,
+    JSReferenceError,
+    JSCompileError,
+    JSThrownValue,
+    JSFunction,
+)
 from contextlib import contextmanager
 
 class ErrorHandlingTests(unittest.TestCase):
@@ -113,5 +123,93 @@ class ErrorHandlingTests(unittest.TestCase):
             self.assertIn("Test error with stack", str(result))
             self.assertIn("Uncaught Error", str(result))
 
+    def test_error_classes(self):
+        """Test that JavaScript errors are raised as the matching exception class."""
+        cases = [
+            ("function() { return 1; }", JSSyntaxError),
+            ("undefinedVariable", JSReferenceError),
+            ("let a = null; a.property", JSTypeError),
+            ("new Array(-1)", JSRangeError),
+            ("throw new Error('plain')", JSError),
+        ]
+        for source, error_class in cases:
+            with self.subTest(source=source):
+                with self.assertRaises(error_class) as context:
+                    self.runtime.compile_and_evaluate_script(source)
+                self.assertIsInstance(context.exception, JSError)
+                self.assertIsInstance(context.exception, RuntimeError)
+
+    def test_error_attributes(self):
+        """Test the structured information attached to JavaScript errors."""
+        script = """
+        function level2() {
+            throw new TypeError('bad type');
+        }
+        function level1() {
+            return level2();
+        }
+        level1();
+        """
+        with self.assertRaises(JSTypeError) as context:
+            self.runtime.compile_and_evaluate_script(script, "errors.js")
+
+        error = context.exception
+        self.assertEqual(error.message, "bad type")
+        self.assertEqual(error.filename, "errors.js")
+        self.assertEqual(error.line, 3)
+        self.assertIsNotNone(error.column)
+        self.assertTrue(error.value.is_object())
+        self.assertEqual(str(error.value.to_object().get("message")), "bad type")
+
+        functions = [frame.function for frame in error.stack]
+        self.assertEqual(functions[:2], ["level2", "level1"])
+        self.assertEqual(error.stack[0].filename, "errors.js")
+        self.assertEqual(error.stack[0].line, 3)
+
+    def test_thrown_value(self):
+        """Test that non-Error values are raised as JSThrownValue."""
+        with self.assertRaises(JSThrownValue) as context:
+            self.runtime.compile_and_evaluate_script("throw 42")
+        self.assertEqual(context.exception.value.to_number(), 42.0)
+
+        with self.assertRaises(JSThrownValue) as context:
+            self.runtime.compile_and_evaluate_script("throw { code: 'E_CUSTOM' }")
+        self.assertEqual(context.exception.value.to_py(), {"code": "E_CUSTOM"})
+
+    def test_function_call_error(self):
+        """Test that errors thrown by called functions keep their class and value."""
+        func = self.runtime.compile_and_evaluate_script(
+            "(function () { throw new RangeError('out of range'); })"
+        ).to_function()
+        with self.assertRaises(JSRangeError) as context:
+            func.call([])
+        self.assertEqual(context.exception.message, "out of range")
+
+    def test_module_errors(self):
+        """Test that module compilation failures raise JSCompileError or JSSyntaxError."""
+        with self.assertRaises(JSSyntaxError):
+            self.runtime.compile_and_evaluate_module("export const = 1;", "broken.js", "broken.js")
+
+        with self.assertRaises((JSCompileError, JSSyntaxError)):
+            self.runtime.compile_and_evaluate_module(
+                "import { missing } from './errors_nonexistent_module.js';", "missing.js", "missing.js"
+            )
+
+    def test_js_error_rethrown_through_python(self):
+        """Test that JavaScript errors passing through Python callbacks keep their value."""
+        def forward(callback):
+            return callback.to_object().to_function().call([])
+
+        catcher = self.runtime.compile_and_evaluate_script("""
+        (forward) => {
+            try {
+                forward(() => { throw new RangeError('inner'); });
+            } catch (e) {
+                return [e instanceof RangeError, e.message];
+            }
+        }
+        """).to_function()
+        self.assertEqual(catcher.call([JSFunction.from_py(forward)]).to_py(), [True, "inner"])
+
 if __name__ == "__main__":
     unittest.main()
\ No newline at end of file
@@ -0,0 +1,106 @@
+import time
+import unittest
+
+from js_exec import Runtime, JSPromise, JSError, EventLoopStatus
+
+
+class EventLoopTests(unittest.TestCase):
+    """Tests for running the event loop with bounds."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+    def evaluate(self, source):
+        return self.runtime.compile_and_evaluate_script(source)
+
+    def start_interval(self):
+        """Starts a runaway interval, which is cleared at the end of the test."""
+        self.evaluate("globalThis.ticks = 0; globalThis.interval = setInterval(() => ticks++, 0);")
+        self.addCleanup(self.stop_interval)
+
+    def stop_interval(self):
+        self.evaluate("clearInterval(globalThis.interval);")
+        self.assertEqual(self.runtime.run_event_loop(timeout=5), EventLoopStatus.Idle)
+
+    def test_idle(self):
+        """Test that an event loop without work reports being idle."""
+        self.evaluate("globalThis.done = false; setTimeout(() => { done = true; }, 5);")
+        self.assertEqual(self.runtime.run_event_loop(), EventLoopStatus.Idle)
+        self.assertTrue(self.evaluate("done").to_boolean())
+
+    def test_timeout(self):
+        """Test that a timeout stops a runaway interval."""
+        self.start_interval()
+        start = time.monotonic()
+        self.assertEqual(self.runtime.run_event_loop(timeout=0.05), EventLoopStatus.TimedOut)
+        self.assertLess(time.monotonic() - start, 2)
+        self.assertGreater(self.evaluate("ticks").to_number(), 0)
+
+    def test_max_ticks(self):
+        """Test that the number of ticks of the event loop can be limited."""
+        self.start_interval()
+        self.assertEqual(self.runtime.run_event_loop(max_ticks=5), EventLoopStatus.TimedOut)
+        ticks = self.evaluate("ticks").to_number()
+        self.assertGreater(ticks, 0)
+        self.assertLessEqual(ticks, 5)
+
+        # Pending work continues on the next run.
+        self.assertEqual(self.runtime.run_event_loop(max_ticks=5), EventLoopStatus.TimedOut)
+        self.assertGreater(self.evaluate("ticks").to_number(), ticks)
+
+    def test_invalid_timeout(self):
+        """Test that negative timeouts are rejected."""
+        with self.assertRaises(ValueError):
+            self.runtime.run_event_loop(timeout=-1)
+
+    def test_run_microtasks(self):
+        """Test that only microtasks are run."""
+        self.evaluate(
+            """
+            globalThis.order = [];
+            setTimeout(() => order.push("timer"), 0);
+            Promise.resolve().then(() => order.push("microtask"));
+            """
+        )
+        self.runtime.run_microtasks()
+        self.assertEqual(self.evaluate("order").to_py(), ["microtask"])
+        self.runtime.run_event_loop()
+        self.assertEqual(self.evaluate("order").to_py(), ["microtask", "timer"])
+
+    def test_run_until_settled(self):
+        """Test running the event loop until a promise is settled."""
+        self.start_interval()
+        promise = JSPromise.from_value(
+            self.evaluate("new Promise((resolve) => setTimeout(() => resolve('settled'), 10))")
+        )
+        status = self.runtime.run_until_settled(promise, timeout=5)
+        self.assertEqual(status, EventLoopStatus.PromiseSettled)
+        self.assertEqual(promise.result().to_py(), "settled")
+
+    def test_run_until_settled_timeout(self):
+        """Test that waiting for a promise times out while other work is pending."""
+        self.start_interval()
+        status = self.runtime.run_until_settled(JSPromise(), timeout=0.05)
+        self.assertEqual(status, EventLoopStatus.TimedOut)
+
+    def test_run_until_settled_idle(self):
+        """Test that waiting for a promise ends when the event loop runs out of work."""
+        self.assertEqual(self.runtime.run_until_settled(JSPromise()), EventLoopStatus.Idle)
+        self.assertEqual(
+            self.runtime.run_until_settled(JSPromise.resolved(1)), EventLoopStatus.PromiseSettled
+        )
+
+    def test_error(self):
+        """Test that errors thrown by tasks are raised."""
+        self.evaluate("setTimeout(() => { throw new Error('task failure'); }, 0);")
+        with self.assertRaises(JSError):
+            self.runtime.run_event_loop(timeout=5)
+
+
+if __name__ == "__main__":
+    unittest.main()
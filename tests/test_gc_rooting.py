@@ -0,0 +1,82 @@
+import os
+import unittest
+
+from helpers import run_in_new_runtime
+
+HOLD_VALUES = """
+values = []
+for i in range(200):
+    values.append(("object", i, runtime.compile_and_evaluate_script(f"({{ index: {i}, items: [{i}, 'x'] }})")))
+    values.append(("string", i, runtime.compile_and_evaluate_script(f"'string ' + {i}")))
+    values.append(("function", i, runtime.compile_and_evaluate_script(f"(x) => x + {i}").to_function()))
+    values.append(("bigint", i, runtime.compile_and_evaluate_script(f"{i}n ** 20n")))
+    values.append(("symbol", i, runtime.compile_and_evaluate_script(f"Symbol('symbol {i}')").to_symbol()))
+    values.append(("key", i, runtime.compile_and_evaluate_script(f"({{ ['key {i}']: 1 }})").to_object().keys()[0]))
+    # Garbage, so that the collections triggered by the zeal mode have work to do
+    runtime.compile_and_evaluate_script("Array.from({ length: 50 }, (_, i) => ({ i }))")
+
+runtime.gc(shrinking=True)
+
+
+def check(kind, i, value):
+    if kind == "object":
+        return value.to_py() == {"index": i, "items": [i, "x"]}
+    if kind == "string":
+        return value.to_py() == f"string {i}"
+    if kind == "function":
+        return value.call([1]).to_py() == 1 + i
+    if kind == "bigint":
+        return value.to_py() == i**20
+    if kind == "symbol":
+        return value.description() == f"symbol {i}"
+    return str(value) == f"key {i}"
+
+
+result = all(check(*value) for value in values)
+"""
+
+
+class GCRootingTests(unittest.TestCase):
+    """Tests that values held by Python survive garbage collections which move or free objects."""
+
+    def stress(self, gc_zeal, gc_zeal_frequency):
+        return run_in_new_runtime(
+            f"runtime = Runtime(gc_zeal={gc_zeal}, gc_zeal_frequency={gc_zeal_frequency})\n" + HOLD_VALUES,
+            os.getcwd(),
+        )
+
+    def test_generational_zeal(self):
+        """Test values across minor collections, which move objects out of the nursery."""
+        self.assertTrue(self.stress(7, 10))
+
+    def test_compacting_zeal(self):
+        """Test values across compacting collections, which move tenured objects."""
+        self.assertTrue(self.stress(14, 50))
+
+    def test_alloc_zeal(self):
+        """Test values across frequent full collections."""
+        self.assertTrue(self.stress(2, 20))
+
+    def test_roots_released(self):
+        """Test that values are unrooted when Python no longer references them."""
+        result = run_in_new_runtime(
+            """
+            import gc
+
+            runtime = Runtime()
+            before = runtime.heap_stats().persistent_roots
+            values = [runtime.compile_and_evaluate_script(f"({{ index: {i} }})") for i in range(100)]
+            held = runtime.heap_stats().persistent_roots
+            del values
+            gc.collect()
+            runtime.gc()
+            after = runtime.heap_stats().persistent_roots
+            result = [held - before >= 100, after <= before + 1]
+            """,
+            os.getcwd(),
+        )
+        self.assertEqual(result, [True, True])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -150,6 +150,60 @@ class JSFunctionTests(unittest.TestCase):
         value = instance_obj.get("value")
         self.assertEqual(value.to_number(), 42.0)
 
+    def test_call_with_python_arguments(self):
+        """Test that native Python arguments are converted implicitly."""
+        script = """
+        (function (name, options, values) {
+            return `${name}:${options.scale * values.reduce((a, b) => a + b, 0)}`;
+        })
+        """
+        func = self.runtime.compile_and_evaluate_script(script).to_function()
+        result = func.call(["total", {"scale": 2}, [1, 2, 3]])
+        self.assertEqual(str(result), "total:12")
+
+        obj = JSObject()
+        self.assertTrue(obj.set("config", {"enabled": True}))
+        self.assertEqual(obj.get("config").to_py(), {"enabled": True})
+
+    def test_python_callable_from_js(self):
+        """Test exposing a Python callable to JavaScript."""
+        calls = []
+
+        def greet(name, options):
+            calls.append((name, options))
+            return {"greeting": f"Hello, {name}{options['suffix']}"}
+
+        func = JSFunction.from_py(greet, nargs=2)
+        self.assertEqual(func.name(), "greet")
+        self.assertEqual(func.length(), 2)
+
+        JSObject.global_object().set("pyGreet", func)
+        result = self.runtime.compile_and_evaluate_script("pyGreet('JS', { suffix: '!' }).greeting")
+        self.assertEqual(str(result), "Hello, JS!")
+        self.assertEqual(calls, [("JS", {"suffix": "!"})])
+
+    def test_python_callable_receives_js_objects(self):
+        """Test that non-convertible arguments arrive as JSValue wrappers."""
+        received = []
+        JSObject.global_object().set("pyCollect", lambda value: received.append(value))
+        self.runtime.compile_and_evaluate_script("pyCollect(function inner() { return 5; })")
+
+        self.assertEqual(len(received), 1)
+        self.assertIsInstance(received[0], JSValue)
+        self.assertEqual(received[0].to_function().call().to_number(), 5.0)
+
+    def test_python_exception_rethrown_in_js(self):
+        """Test that Python exceptions surface as catchable JavaScript errors."""
+
+        def fail():
+            raise ValueError("boom from python")
+
+        JSObject.global_object().set("pyFail", JSFunction.from_py(fail, name="pyFail"))
+        result = self.runtime.compile_and_evaluate_script(
+            "(() => { try { pyFail(); } catch (e) { return e instanceof Error && e.message; } })()"
+        )
+        self.assertIn("boom from python", str(result))
+
     def test_function_source(self):
         """Test getting the source of a JavaScript function."""
         script = """
@@ -1,6 +1,9 @@
 import unittest
 
-from js_exec import Runtime, JSValue, JSBigInt, Symbol, ESClass, WellKnownSymbolCode
+from datetime import datetime, timezone
+from decimal import Decimal
+
+from js_exec import Runtime, JSValue, JSBigInt, Symbol, ESClass, WellKnownSymbolCode, ConversionFallback
 
 
 class TypeConversionTests(unittest.TestCase):
@@ -163,5 +166,123 @@ class TypeConversionTests(unittest.TestCase):
         self.assertEqual(unboxed_number.to_number(), 42.0)
         self.assertEqual(unboxed_boolean.to_boolean(), True)
 
+    def test_to_py_nested_structures(self):
+        """Test deep conversion of nested JavaScript values into Python objects."""
+        result = self.runtime.compile_and_evaluate_script("""
+        ({
+            name: "js",
+            count: 3,
+            ratio: 0.5,
+            flags: [true, false, null, undefined],
+            nested: { 1: "one", big: 12345678901234567890n },
+            map: new Map([["a", 1], [2, [3]]]),
+            set: new Set([1, 2, 2]),
+            boxed: new String("boxed"),
+        })
+        """)
+        self.assertEqual(
+            result.to_py(),
+            {
+                "name": "js",
+                "count": 3,
+                "ratio": 0.5,
+                "flags": [True, False, None, None],
+                "nested": {"1": "one", "big": 12345678901234567890},
+                "map": {"a": 1, 2: [3]},
+                "set": {1, 2},
+                "boxed": "boxed",
+            },
+        )
+
+    def test_to_py_dates_and_binary(self):
+        """Test conversion of Dates, ArrayBuffers and typed arrays."""
+        date = self.runtime.compile_and_evaluate_script("new Date(Date.UTC(2024, 0, 2, 3, 4, 5))")
+        self.assertEqual(date.to_py(), datetime(2024, 1, 2, 3, 4, 5, tzinfo=timezone.utc))
+
+        data = self.runtime.compile_and_evaluate_script("new Uint8Array([1, 2, 255])")
+        self.assertEqual(data.to_py(), b"\x01\x02\xff")
+
+        buffer = self.runtime.compile_and_evaluate_script("new Uint8Array([4, 5]).buffer")
+        self.assertEqual(buffer.to_py(), b"\x04\x05")
+
+        floats = self.runtime.compile_and_evaluate_script("new Float64Array([1.5, -2])")
+        self.assertEqual(floats.to_py(), [1.5, -2.0])
+
+    def test_to_py_fallback_policies(self):
+        """Test handling of values without a native Python counterpart."""
+        result = self.runtime.compile_and_evaluate_script("({ fn() {}, value: 1 })")
+
+        with self.assertRaises(TypeError):
+            result.to_py()
+
+        self.assertEqual(result.to_py(fallback=ConversionFallback.Null), {"fn": None, "value": 1})
+
+        wrapped = result.to_py(fallback=ConversionFallback.Wrap)
+        self.assertIsInstance(wrapped["fn"], JSValue)
+        self.assertTrue(wrapped["fn"].is_object())
+
+    def test_to_py_cycles_and_depth(self):
+        """Test that cyclic and overly deep structures are rejected."""
+        cyclic = self.runtime.compile_and_evaluate_script("(() => { const a = {}; a.self = a; return a; })()")
+        with self.assertRaises(ValueError):
+            cyclic.to_py()
+
+        shared = self.runtime.compile_and_evaluate_script("(() => { const b = [1]; return [b, b]; })()")
+        self.assertEqual(shared.to_py(), [[1], [1]])
+
+        deep = self.runtime.compile_and_evaluate_script("[[[[1]]]]")
+        self.assertEqual(deep.to_py(max_depth=4), [[[[1]]]])
+        with self.assertRaises(ValueError):
+            deep.to_py(max_depth=3)
+
+    def test_from_py_roundtrip(self):
+        """Test converting native Python objects into JavaScript values."""
+        data = {
+            "name": "py",
+            "items": [1, 2.5, None, True],
+            "pair": (1, "two"),
+            "nested": {"deep": {"value": -7}},
+        }
+        value = JSValue.from_py(data)
+        self.assertTrue(value.is_object())
+        self.assertEqual(value.to_py(), {**data, "pair": [1, "two"]})
+
+        describe = self.runtime.compile_and_evaluate_script(
+            "(v) => [Array.isArray(v.items), Array.isArray(v.pair), typeof v.nested.deep.value]"
+        ).to_function()
+        self.assertEqual(describe.call([data]).to_py(), [True, True, "number"])
+
+    def test_from_py_special_types(self):
+        """Test conversion of large ints, bytes, datetimes and Decimals."""
+        typeof_fn = self.runtime.compile_and_evaluate_script("(v) => typeof v").to_function()
+        self.assertEqual(str(typeof_fn.call([2**31])), "number")
+        self.assertEqual(str(typeof_fn.call([2**64])), "bigint")
+        self.assertEqual(JSValue.from_py(2**64).to_py(), 2**64)
+        self.assertEqual(JSValue.from_py(-(2**70)).to_py(), -(2**70))
+
+        bytes_value = JSValue.from_py(b"\x00\x01\xfe")
+        self.assertEqual(bytes_value.to_object().get_builtin_class(), ESClass.other())
+        self.assertEqual(bytes_value.to_py(), b"\x00\x01\xfe")
+
+        moment = datetime(2020, 5, 17, 12, 30, tzinfo=timezone.utc)
+        date_value = JSValue.from_py(moment)
+        self.assertEqual(date_value.to_object().get_builtin_class(), ESClass.date())
+        self.assertEqual(date_value.to_py(), moment)
+
+        self.assertEqual(JSValue.from_py(Decimal("1.25")).to_py(), 1.25)
+
+    def test_from_py_errors(self):
+        """Test that unsupported and cyclic Python objects are rejected."""
+        with self.assertRaises(TypeError):
+            JSValue.from_py(object())
+
+        cyclic = []
+        cyclic.append(cyclic)
+        with self.assertRaises(ValueError):
+            JSValue.from_py(cyclic)
+
+        with self.assertRaises(ValueError):
+            JSValue.from_py([[[1]]], max_depth=2)
+
 if __name__ == "__main__":
     unittest.main()
\ No newline at end of file
@@ -0,0 +1,175 @@
+import os
+import tempfile
+import unittest
+
+from helpers import run_in_new_runtime
+
+# Renders elements to HTML strings, in the style of a server-side rendering library.
+RENDERER = """
+export const Fragment = Symbol("Fragment");
+
+function render(type, props, children) {
+    const content = children.flat().join("");
+    if (type === Fragment) {
+        return content;
+    }
+    if (typeof type === "function") {
+        return type({ ...props, children: content });
+    }
+    const attributes = Object.entries(props || {})
+        .filter(([name]) => name !== "children")
+        .map(([name, value]) => ` ${name}="${value}"`)
+        .join("");
+    return `<${type}${attributes}>${content}</${type}>`;
+}
+
+export function h(type, props, ...children) {
+    return render(type, props, children);
+}
+
+export function jsx(type, props) {
+    const { children = [], ...rest } = props;
+    return render(type, rest, [children]);
+}
+
+export const jsxs = jsx;
+"""
+
+
+class JSXModuleTests(unittest.TestCase):
+    """Tests for importing JSX, TSX and other module files by their extension."""
+
+    def setUp(self):
+        self.directory = tempfile.TemporaryDirectory()
+        self.root = os.path.realpath(self.directory.name)
+        self.write("renderer.js", RENDERER)
+
+    def tearDown(self):
+        self.directory.cleanup()
+
+    def write(self, path, content):
+        """Writes a file relative to the temporary project directory."""
+        with open(os.path.join(self.root, path), "w") as file:
+            file.write(content)
+
+    def run_main(self, runtime_options, main):
+        """Evaluates `main` as a module of the project, which must assign `globalThis.result`."""
+        return run_in_new_runtime(
+            f"""
+            runtime = Runtime({runtime_options})
+            module, promise = runtime.compile_and_evaluate_module(
+                {main!r}, "main.js", {os.path.join(self.root, "main.js")!r}
+            )
+            runtime.run_event_loop()
+            result = runtime.compile_and_evaluate_script("globalThis.result").to_py()
+            """,
+            self.root,
+        )
+
+    def test_tsx_with_factory(self):
+        """Test that TSX is compiled with a custom factory and fragment."""
+        self.write(
+            "page.tsx",
+            """import { h, Fragment } from "./renderer.js";
+
+interface Props {
+    name: string;
+}
+
+function Greeting({ name }: Props) {
+    return <p class="greeting">Hello {name}</p>;
+}
+
+export function Page(props: Props): string {
+    return <><h1>Title</h1><Greeting name={props.name} /></>;
+}
+""",
+        )
+        result = self.run_main(
+            'jsx_factory="h", jsx_fragment="Fragment"',
+            "import { Page } from './page.tsx'; globalThis.result = Page({ name: 'World' });",
+        )
+        self.assertEqual(result, '<h1>Title</h1><p class="greeting">Hello World</p>')
+
+    def test_jsx_automatic_runtime(self):
+        """Test that JSX is compiled to imports of the configured automatic runtime."""
+        self.write("page.jsx", "export const page = <div id=\"app\"><span>{1 + 1}</span></div>;\n")
+        result = self.run_main(
+            'jsx="react-jsx", jsx_import_source="ssr", '
+            'import_map={"imports": {"ssr/jsx-runtime": "./renderer.js"}}',
+            "import { page } from './page.jsx'; globalThis.result = page;",
+        )
+        self.assertEqual(result, '<div id="app"><span>2</span></div>')
+
+    def test_module_extensions(self):
+        """Test that ES modules and CommonJS modules are loaded by their extension."""
+        self.write("values.mts", "export const typed: number = 1;\n")
+        self.write("values.mjs", "export const plain = 2;\n")
+        self.write("values.cjs", "const base = 3;\nmodule.exports = { common: base };\n")
+        self.write("values.cts", "const typedCommon: number = 4;\nexport default { typedCommon };\n")
+        result = self.run_main(
+            "",
+            """
+            import { typed } from './values.mts';
+            import { plain } from './values.mjs';
+            import common from './values.cjs';
+            import typedCommon from './values.cts';
+            globalThis.result = [typed, plain, common.common, typedCommon.typedCommon];
+            """,
+        )
+        self.assertEqual(result, [1, 2, 3, 4])
+
+    def test_commonjs_require(self):
+        """Test that calls of require in CommonJS modules throw a clear error."""
+        self.write("legacy.cjs", "module.exports = () => require('path');\n")
+        result = self.run_main(
+            "",
+            """
+            import load from './legacy.cjs';
+            try {
+                load();
+            } catch (error) {
+                globalThis.result = error.message;
+            }
+            """,
+        )
+        self.assertIn("require() is not supported", result)
+
+    def test_compile_error(self):
+        """Test that modules which fail to compile report their diagnostics."""
+        self.write("broken.tsx", "export const element = <div>;\n")
+        result = run_in_new_runtime(
+            f"""
+            runtime = Runtime()
+            try:
+                runtime.compile_and_evaluate_module(
+                    "import './broken.tsx';", "main.js", {os.path.join(self.root, "main.js")!r}
+                )
+                result = None
+            except JSError as error:
+                result = str(error)
+            """,
+            self.root,
+        )
+        self.assertIn("broken.tsx:1:", result)
+
+    def test_invalid_pragmas(self):
+        """Test that pragmas which do not apply to the JSX mode are rejected."""
+        result = run_in_new_runtime(
+            """
+            errors = []
+            for options in ({"jsx": "react-jsx", "jsx_factory": "h"}, {"jsx": "react", "jsx_import_source": "preact"}):
+                try:
+                    Runtime(**options)
+                    errors.append(False)
+                except ValueError:
+                    errors.append(True)
+            result = errors
+            """,
+            os.getcwd(),
+        )
+        self.assertEqual(result, [True, True])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,102 @@
+import os
+import unittest
+
+from js_exec import Runtime, HeapStats
+
+from helpers import run_in_new_runtime
+
+
+class HeapStatsTests(unittest.TestCase):
+    """Tests for heap statistics and forced garbage collections."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+    def test_heap_stats(self):
+        """Test that heap statistics are reported."""
+        stats = self.runtime.heap_stats()
+        self.assertIsInstance(stats, HeapStats)
+        self.assertGreater(stats.gc_heap_bytes, 0)
+        self.assertGreaterEqual(stats.malloc_bytes, 0)
+        self.assertGreater(stats.max_heap_bytes, 0)
+        self.assertGreaterEqual(stats.gc_count, stats.major_gc_count)
+        self.assertIn("gc_heap_bytes=", repr(stats))
+
+    def test_gc(self):
+        """Test that collections can be forced from Python."""
+        before = self.runtime.heap_stats()
+        self.runtime.gc()
+        after = self.runtime.heap_stats()
+        self.assertGreater(after.major_gc_count, before.major_gc_count)
+
+        self.runtime.gc(shrinking=True)
+        self.assertGreater(self.runtime.heap_stats().major_gc_count, after.major_gc_count)
+
+    def test_gc_frees_garbage(self):
+        """Test that collecting releases unreachable objects."""
+        self.runtime.compile_and_evaluate_script(
+            "globalThis.garbage = Array.from({ length: 100000 }, (_, i) => ({ i })); undefined"
+        )
+        self.runtime.gc()
+        retained = self.runtime.heap_stats().gc_heap_bytes
+        self.runtime.compile_and_evaluate_script("globalThis.garbage = null;")
+        self.runtime.gc(shrinking=True)
+        self.assertLess(self.runtime.heap_stats().gc_heap_bytes, retained)
+
+
+class HeapLimitTests(unittest.TestCase):
+    """Tests for heap limits and GC tuning options of a runtime."""
+
+    def test_max_heap_bytes(self):
+        """Test that exceeding the heap limit raises instead of growing the heap."""
+        result = run_in_new_runtime(
+            """
+            runtime = Runtime(max_heap_bytes=32 * 1024 * 1024)
+            limit = runtime.heap_stats().max_heap_bytes
+            try:
+                runtime.compile_and_evaluate_script(
+                    "const chunks = []; while (true) chunks.push(new Array(10000).fill({}));"
+                )
+                failed = False
+            except JSError:
+                failed = True
+            value = runtime.compile_and_evaluate_script("1 + 1").to_number()
+            result = [limit, failed, value]
+            """,
+            os.getcwd(),
+        )
+        self.assertEqual(result, [32 * 1024 * 1024, True, 2.0])
+
+    def test_tuning_options(self):
+        """Test that nursery and zeal options are accepted."""
+        result = run_in_new_runtime(
+            """
+            runtime = Runtime(max_nursery_bytes=1024 * 1024, gc_zeal=0, gc_zeal_frequency=100)
+            result = runtime.compile_and_evaluate_script("[1, 2, 3].length").to_number()
+            """,
+            os.getcwd(),
+        )
+        self.assertEqual(result, 3.0)
+
+    def test_invalid_gc_zeal(self):
+        """Test that unknown zeal modes are rejected."""
+        result = run_in_new_runtime(
+            """
+            try:
+                Runtime(gc_zeal=99)
+                result = None
+            except ValueError as error:
+                result = str(error)
+            """,
+            os.getcwd(),
+        )
+        self.assertIn("gc_zeal", result)
+
+
+if __name__ == "__main__":
+    unittest.main()
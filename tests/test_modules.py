@@ -0,0 +1,338 @@
+import json
+import os
+import tempfile
+import unittest
+
+from js_exec import Runtime, JSError
+
+from helpers import run_in_new_runtime
+
+
+class ModuleResolutionTests(unittest.TestCase):
+    """Tests for resolving and loading JavaScript modules."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+        cls.directory = tempfile.TemporaryDirectory()
+        cls.root = os.path.realpath(cls.directory.name)
+
+    @classmethod
+    def tearDownClass(cls):
+        cls.directory.cleanup()
+
+    def write(self, path, content):
+        """Writes a file relative to the temporary project directory."""
+        path = os.path.join(self.root, path)
+        os.makedirs(os.path.dirname(path), exist_ok=True)
+        with open(path, "w") as file:
+            file.write(content if isinstance(content, str) else json.dumps(content))
+        return path
+
+    def evaluate_module(self, name, source):
+        """Evaluates a module in the project directory and returns the value of `globalThis.result`."""
+        path = self.write(os.path.join("src", name), source)
+        self.runtime.compile_and_evaluate_module(source, path, path)
+        self.runtime.run_event_loop()
+        return self.runtime.compile_and_evaluate_script("globalThis.result").to_py()
+
+    def test_node_modules_main(self):
+        """Test resolving packages through the `module` and `main` fields."""
+        self.write("node_modules/main-pkg/package.json", {"main": "lib/main.js"})
+        self.write("node_modules/main-pkg/lib/main.js", "export default 'main';")
+        self.write("node_modules/esm-pkg/package.json", {"main": "cjs.js", "module": "esm.js"})
+        self.write("node_modules/esm-pkg/esm.js", "export default 'module';")
+        self.write("node_modules/index-pkg/index.js", "export default 'index';")
+
+        result = self.evaluate_module("main.js", """
+        import main from "main-pkg";
+        import esm from "esm-pkg";
+        import index from "index-pkg";
+        globalThis.result = [main, esm, index];
+        """)
+        self.assertEqual(result, ["main", "module", "index"])
+
+    def test_node_modules_exports(self):
+        """Test resolving packages through conditional and subpath `exports`."""
+        self.write("node_modules/@scope/exports-pkg/package.json", {
+            "exports": {
+                ".": {"require": "./main.cjs", "import": "./main.mjs"},
+                "./feature": ["./missing.js", "./feature.js"],
+                "./utils/*": "./src/utils/*.js",
+                "./internal/*": None,
+            },
+        })
+        self.write("node_modules/@scope/exports-pkg/main.mjs", "export default 'import';")
+        self.write("node_modules/@scope/exports-pkg/feature.js", "export default 'feature';")
+        self.write("node_modules/@scope/exports-pkg/src/utils/math.js", "export const add = (a, b) => a + b;")
+
+        result = self.evaluate_module("exports.js", """
+        import main from "@scope/exports-pkg";
+        import feature from "@scope/exports-pkg/feature";
+        import { add } from "@scope/exports-pkg/utils/math";
+        globalThis.result = [main, feature, add(1, 2)];
+        """)
+        self.assertEqual(result, ["import", "feature", 3])
+
+    def test_node_modules_parent_directories(self):
+        """Test that node_modules directories in parent directories are searched."""
+        self.write("node_modules/parent-pkg/index.js", "export const name = 'parent';")
+
+        result = self.evaluate_module("nested/deeper/parent.js", """
+        import { name } from "parent-pkg";
+        globalThis.result = name;
+        """)
+        self.assertEqual(result, "parent")
+
+    def test_node_modules_not_found(self):
+        """Test that unresolvable packages raise an error instead of crashing."""
+        self.write("node_modules/hidden-pkg/package.json", {"exports": {".": "./index.js"}})
+        self.write("node_modules/hidden-pkg/index.js", "export default 1;")
+        self.write("node_modules/hidden-pkg/private.js", "export default 2;")
+
+        for specifier in ["missing-pkg", "hidden-pkg/private"]:
+            with self.subTest(specifier=specifier):
+                with self.assertRaises(JSError):
+                    self.evaluate_module("missing.js", f"import value from '{specifier}';")
+
+
+class VirtualModuleTests(unittest.TestCase):
+    """Tests for modules registered from Python without files."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+    def evaluate_module(self, path, source):
+        """Evaluates a module and returns the value of `globalThis.result`."""
+        self.runtime.compile_and_evaluate_module(source, path, path)
+        self.runtime.run_event_loop()
+        return self.runtime.compile_and_evaluate_script("globalThis.result").to_py()
+
+    def test_register_module(self):
+        """Test importing registered modules, including relative imports between them."""
+        self.runtime.register_module("virtual/lib/math.js", "export const double = (x) => x * 2;")
+        self.runtime.register_module(
+            "virtual/lib/index.js",
+            "export { double } from './math.js'; export { default as config } from '../config.json';",
+        )
+        self.runtime.register_module("virtual/config.json", '{"factor": 3}', kind="json")
+
+        result = self.evaluate_module("virtual/main.js", """
+        import { double, config } from "./lib/index.js";
+        globalThis.result = [double(4), config.factor];
+        """)
+        self.assertEqual(result, [8, 3])
+
+    def test_register_typescript_module(self):
+        """Test registering modules written in TypeScript."""
+        self.runtime.register_module(
+            "virtual_ts/greet.ts",
+            "export function greet(name: string): string { return `Hello, ${name}`; }",
+            kind="ts",
+        )
+        result = self.evaluate_module("virtual_ts/main.js", """
+        import { greet } from "./greet.ts";
+        globalThis.result = greet("TS");
+        """)
+        self.assertEqual(result, "Hello, TS")
+
+    def test_register_module_bare_specifier(self):
+        """Test that registered modules can be imported by their specifier from anywhere."""
+        self.runtime.register_module("generated/answer.js", "export default 42;")
+        result = self.evaluate_module("elsewhere/main.js", """
+        import answer from "generated/answer.js";
+        globalThis.result = answer;
+        """)
+        self.assertEqual(result, 42)
+
+    def test_register_module_errors(self):
+        """Test invalid registrations."""
+        self.runtime.register_module("virtual_errors/a.js", "export default 1;")
+        with self.assertRaises(ValueError):
+            self.runtime.register_module("virtual_errors/./a.js", "export default 2;")
+        with self.assertRaises(ValueError):
+            self.runtime.register_module("virtual_errors/b.js", "", kind="wasm")
+        with self.assertRaises(ValueError):
+            self.runtime.register_module("virtual_errors/c.json", "{invalid", kind="json")
+
+
+class SyntheticModuleTests(unittest.TestCase):
+    """Tests for modules defined from Python values."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+    def evaluate_module(self, path, source):
+        """Evaluates a module and returns the value of `globalThis.result`."""
+        self.runtime.compile_and_evaluate_module(source, path, path)
+        self.runtime.run_event_loop()
+        return self.runtime.compile_and_evaluate_script("globalThis.result").to_py()
+
+    def test_synthetic_module(self):
+        """Test importing values and callables from a synthetic module."""
+        rows = {1: "alice", 2: "bob"}
+
+        def query(row_id):
+            return rows.get(row_id)
+
+        exports = {
+            "query": query,
+            "tables": ["users"],
+            "default": {"name": "db"},
+        }
+        self.runtime.define_synthetic_module("py:db", exports)
+        # The exports are converted when the module is defined.
+        exports["tables"] = ["changed"]
+        result = self.evaluate_module("synthetic_main.js", """
+        import db, { query, tables } from "py:db";
+        import * as namespace from "py:db";
+        globalThis.result = [query(2), tables, db.name, Object.keys(namespace).sort()];
+        """)
+        self.assertEqual(result, ["bob", ["users"], "db", ["default", "query", "tables"]])
+
+    def test_synthetic_module_export_names(self):
+        """Test that exports which are not identifiers are supported."""
+        self.runtime.define_synthetic_module("py:names", {"kebab-case": 1, "with space": 2})
+        result = self.evaluate_module("synthetic_names.js", """
+        import { "kebab-case" as kebab, "with space" as spaced } from "py:names";
+        globalThis.result = kebab + spaced;
+        """)
+        self.assertEqual(result, 3)
+
+    def test_synthetic_module_errors(self):
+        """Test invalid synthetic module definitions."""
+        self.runtime.define_synthetic_module("py:duplicate", {})
+        with self.assertRaises(ValueError):
+            self.runtime.define_synthetic_module("py:duplicate", {})
+        with self.assertRaises(ValueError):
+            self.runtime.register_module("py:duplicate", "export const value = 1;")
+        with self.assertRaises(TypeError):
+            self.runtime.define_synthetic_module("py:keys", {1: "one"})
+
+
+class ModuleNamespaceTests(unittest.TestCase):
+    """Tests for reading the exports of evaluated modules."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+    def test_module_exports(self):
+        """Test reading and calling the exports of a module."""
+        module, _ = self.runtime.compile_and_evaluate_module("""
+        export const version = "1.0";
+        export function add(a, b) { return a + b; }
+        export default class Counter {}
+        """, "namespace_exports.js", "namespace_exports.js")
+
+        self.assertTrue(module.is_linked())
+        self.assertEqual(module.export_names(), ["add", "default", "version"])
+        self.assertEqual(str(module.get_export("version")), "1.0")
+        self.assertIsNone(module.get_export("missing"))
+
+        add = module.get_export("add").to_function()
+        self.assertEqual(add.call([2, 3]).to_py(), 5)
+
+        self.assertEqual(module.default.to_function().name(), "Counter")
+        self.assertEqual(str(module.namespace().get("version")), "1.0")
+
+    def test_module_without_default(self):
+        """Test that modules without a default export have no default."""
+        module, _ = self.runtime.compile_and_evaluate_module(
+            "export const value = 1;", "namespace_no_default.js", "namespace_no_default.js"
+        )
+        self.assertIsNone(module.default)
+
+
+class ImportMapTests(unittest.TestCase):
+    """Tests for resolving modules through import maps."""
+
+    def setUp(self):
+        self.directory = tempfile.TemporaryDirectory()
+        self.root = os.path.realpath(self.directory.name)
+        files = {
+            "vendor/lodash/lodash.js": "export const name = 'lodash';",
+            "vendor/lodash-v3/lodash.js": "export const name = 'lodash-v3';",
+            "vendor/utils/strings.js": "export const upper = (s) => s.toUpperCase();",
+            "src/legacy/app.js": "import { name } from 'lodash'; export default name;",
+        }
+        for path, content in files.items():
+            path = os.path.join(self.root, path)
+            os.makedirs(os.path.dirname(path), exist_ok=True)
+            with open(path, "w") as file:
+                file.write(content)
+
+    def tearDown(self):
+        self.directory.cleanup()
+
+    def test_import_map(self):
+        """Test exact, trailing-slash prefix and scoped import map entries."""
+        result = run_in_new_runtime("""
+        import_map = {
+            "imports": {
+                "lodash": "./vendor/lodash/lodash.js",
+                "utils/": "./vendor/utils/",
+            },
+            "scopes": {
+                "./src/legacy/": {"lodash": "./vendor/lodash-v3/lodash.js"},
+            },
+        }
+        runtime = Runtime(import_map=import_map)
+        source = (
+            "import { name } from 'lodash';"
+            "import { upper } from 'utils/strings.js';"
+            "import legacy from './legacy/app.js';"
+            "globalThis.result = [name, upper('ok'), legacy];"
+        )
+        runtime.compile_and_evaluate_module(source, "src/main.js", "src/main.js")
+        runtime.run_event_loop()
+        result = runtime.compile_and_evaluate_script("globalThis.result").to_py()
+        """, self.root)
+        self.assertEqual(result, ["lodash", "OK", "lodash-v3"])
+
+    def test_import_map_json_text(self):
+        """Test that import maps can be given as JSON text."""
+        result = run_in_new_runtime("""
+        runtime = Runtime(import_map='{"imports": {"lodash": "./vendor/lodash/lodash.js"}}')
+        source = "import { name } from 'lodash'; globalThis.result = name;"
+        runtime.compile_and_evaluate_module(source, "main.js", "main.js")
+        runtime.run_event_loop()
+        result = runtime.compile_and_evaluate_script("globalThis.result").to_py()
+        """, self.root)
+        self.assertEqual(result, "lodash")
+
+    def test_invalid_import_map(self):
+        """Test that invalid import maps are rejected."""
+        result = run_in_new_runtime("""
+        result = []
+        for import_map in ['{"imports": [', '{"imports": {"a/": "./b"}}', '{"imports": {"a": "b"}}']:
+            try:
+                Runtime(import_map=import_map)
+                result.append("accepted")
+            except ValueError:
+                result.append("rejected")
+        """, self.root)
+        self.assertEqual(result, ["rejected", "rejected", "rejected"])
+
+
+if __name__ == "__main__":
+    unittest.main()
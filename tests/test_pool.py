@@ -0,0 +1,110 @@
+import os
+import tempfile
+import unittest
+from concurrent.futures import Future, ThreadPoolExecutor
+
+from js_exec import RuntimePool, JSError, JSThrownValue, JSTimeoutError, JSTypeError
+
+
+class RuntimePoolTests(unittest.TestCase):
+    """Tests for running JavaScript on a pool of runtimes from any thread."""
+
+    @classmethod
+    def setUpClass(cls):
+        cls.pool = RuntimePool(size=2)
+        cls.directory = tempfile.TemporaryDirectory()
+        cls.module = os.path.join(cls.directory.name, "math.js")
+        with open(cls.module, "w") as file:
+            file.write(
+                """
+                export function add(a, b) { return a + b; }
+                export async function greet(name) { return `Hello, ${name}`; }
+                export const notAFunction = 1;
+                """
+            )
+
+    @classmethod
+    def tearDownClass(cls):
+        cls.pool.shutdown()
+        cls.directory.cleanup()
+
+    def test_submit(self):
+        """Test that scripts are evaluated and their results converted into plain values."""
+        future = self.pool.submit("1 + 2")
+        self.assertIsInstance(future, Future)
+        self.assertEqual(future.result(timeout=10), 3)
+        self.assertEqual(
+            self.pool.submit("({ list: [1, 'two'], nested: { ok: true } })").result(timeout=10),
+            {"list": [1, "two"], "nested": {"ok": True}},
+        )
+        self.assertEqual(self.pool.size, 2)
+
+    def test_submit_promise(self):
+        """Test that promises are awaited before their result is delivered."""
+        source = "new Promise((resolve) => setTimeout(() => resolve(41), 10)).then((x) => x + 1)"
+        self.assertEqual(self.pool.submit(source).result(timeout=10), 42)
+
+    def test_submit_errors(self):
+        """Test that errors are delivered through the future."""
+        with self.assertRaises(JSTypeError):
+            self.pool.submit("null.property").result(timeout=10)
+        with self.assertRaises(JSError) as context:
+            self.pool.submit("Promise.reject(new Error('rejected'))").result(timeout=10)
+        self.assertEqual(context.exception.message, "rejected")
+        with self.assertRaises(JSThrownValue) as context:
+            self.pool.submit("throw { code: 7 }").result(timeout=10)
+        self.assertEqual(context.exception.value, {"code": 7})
+
+    def test_call(self):
+        """Test calling functions exported by a module."""
+        self.assertEqual(self.pool.call(self.module, "add", [2, 3]).result(timeout=10), 5)
+        self.assertEqual(self.pool.call(self.module, "greet", ("pool",)).result(timeout=10), "Hello, pool")
+        with self.assertRaises(JSTypeError):
+            self.pool.call(self.module, "notAFunction").result(timeout=10)
+        with self.assertRaises(JSError):
+            self.pool.call(os.path.join(self.directory.name, "missing.js"), "add").result(timeout=10)
+
+    def test_many_threads(self):
+        """Test that the pool can be used from several Python threads at once."""
+        with ThreadPoolExecutor(max_workers=8) as executor:
+            futures = [executor.submit(lambda i=i: self.pool.submit(f"{i} * 2").result(timeout=10)) for i in range(32)]
+            self.assertEqual([future.result() for future in futures], [i * 2 for i in range(32)])
+
+    def test_timeout(self):
+        """Test that runaway code is interrupted and the pool stays usable."""
+        with self.assertRaises(JSTimeoutError):
+            self.pool.submit("while (true) {}", timeout=0.1).result(timeout=10)
+        with self.assertRaises(JSTimeoutError):
+            self.pool.submit("new Promise(() => setTimeout(() => {}, 60000))", timeout=0.1).result(timeout=10)
+        self.assertEqual(self.pool.submit("'still alive'").result(timeout=10), "still alive")
+        with self.assertRaises(ValueError):
+            self.pool.submit("1", timeout=-1)
+
+
+class RuntimePoolLifecycleTests(unittest.TestCase):
+    """Tests for creating and shutting down pools."""
+
+    def test_invalid_size(self):
+        """Test that a pool needs at least one runtime."""
+        with self.assertRaises(ValueError):
+            RuntimePool(size=0)
+
+    def test_runtime_options(self):
+        """Test that keyword arguments are passed on to the runtimes."""
+        with RuntimePool(size=1, allow_eval=False) as pool:
+            with self.assertRaises(JSError):
+                pool.submit("eval('1 + 1')").result(timeout=10)
+            self.assertEqual(pool.submit("1 + 1").result(timeout=10), 2)
+
+    def test_shutdown(self):
+        """Test that queued work is finished on shutdown and new work is refused."""
+        pool = RuntimePool(size=1)
+        futures = [pool.submit(f"{i}") for i in range(5)]
+        pool.shutdown()
+        self.assertEqual([future.result(timeout=10) for future in futures], [0, 1, 2, 3, 4])
+        with self.assertRaises(RuntimeError):
+            pool.submit("1")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,334 @@
+import asyncio
+import unittest
+
+from js_exec import (
+    Runtime,
+    JSValue,
+    JSPromise,
+    JSPromiseState,
+    JSFunction,
+    JSError,
+    JSTypeError,
+    JSThrownValue,
+)
+
+
+class PromiseAwaitTests(unittest.IsolatedAsyncioTestCase):
+    """Tests for awaiting JavaScript promises from asyncio."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+    def promise(self, source):
+        """Evaluates a script which returns a promise."""
+        return JSPromise.from_value(self.runtime.compile_and_evaluate_script(source))
+
+    async def test_await_resolved(self):
+        """Test awaiting promises which are already or immediately resolved."""
+        self.assertEqual(await JSPromise.resolved(42), 42)
+        self.assertEqual(await self.promise("Promise.resolve({ list: [1, 'two'] })"), {"list": [1, "two"]})
+        self.assertEqual(await self.promise("Promise.resolve().then(() => 'chained')"), "chained")
+
+    async def test_await_timer(self):
+        """Test awaiting a promise settled by a timer of the event loop."""
+        promise = self.promise("new Promise((resolve) => setTimeout(() => resolve('later'), 20))")
+        self.assertEqual(await promise, "later")
+
+    async def test_await_concurrently(self):
+        """Test that awaiting promises cooperates with other asyncio tasks."""
+        order = []
+
+        async def python_task():
+            await asyncio.sleep(0.01)
+            order.append("python")
+
+        async def js_task():
+            await self.promise("new Promise((resolve) => setTimeout(resolve, 50))")
+            order.append("js")
+
+        await asyncio.wait_for(asyncio.gather(js_task(), python_task()), timeout=5)
+        self.assertEqual(order, ["python", "js"])
+
+    async def test_await_resolved_from_python(self):
+        """Test awaiting a promise which is resolved by Python code."""
+        promise = JSPromise()
+        asyncio.get_running_loop().call_later(0.01, promise.resolve, "from python")
+        self.assertEqual(await asyncio.wait_for(promise, timeout=5), "from python")
+
+    async def test_await_resolved_from_javascript_call(self):
+        """Test awaiting a promise which is resolved by a JavaScript function called from Python."""
+        promise = self.promise("new Promise((resolve) => { globalThis.resolveLater = resolve; })")
+        resolve = self.runtime.compile_and_evaluate_script("resolveLater").to_function()
+        asyncio.get_running_loop().call_later(0.01, resolve.call, ["from javascript"])
+        self.assertEqual(await asyncio.wait_for(promise, timeout=5), "from javascript")
+
+    async def test_await_timers(self):
+        """Test awaiting promises settled by intervals and by timers scheduled after cleared ones."""
+        promise = self.promise(
+            """
+            new Promise((resolve) => {
+                const cleared = setTimeout(() => resolve("cleared"), 60000);
+                clearTimeout(cleared);
+                let ticks = 0;
+                const interval = setInterval(() => {
+                    if (++ticks === 3) {
+                        clearInterval(interval);
+                        setTimeout(() => resolve(ticks), 10);
+                    }
+                }, 5);
+            })
+            """
+        )
+        self.assertEqual(await asyncio.wait_for(promise, timeout=5), 3)
+
+    async def test_await_rejected(self):
+        """Test that rejections are raised as JSErrors."""
+        with self.assertRaises(JSTypeError) as context:
+            await self.promise("Promise.reject(new TypeError('rejected'))")
+        self.assertEqual(context.exception.message, "rejected")
+
+        with self.assertRaises(JSThrownValue) as context:
+            await self.promise("new Promise((_, reject) => setTimeout(() => reject(7), 5))")
+        self.assertEqual(context.exception.value.to_number(), 7.0)
+
+        with self.assertRaises(JSError):
+            await self.promise("(async () => { throw new Error('async failure'); })()")
+
+    async def test_await_unconvertible_value(self):
+        """Test that values without a Python counterpart are returned as JSValues."""
+        result = await self.promise("Promise.resolve(function named() {})")
+        self.assertIsInstance(result, JSValue)
+        self.assertEqual(result.to_function().name(), "named")
+
+    async def test_await_cancelled(self):
+        """Test that awaiting a promise can be cancelled."""
+        with self.assertRaises(asyncio.TimeoutError):
+            await asyncio.wait_for(JSPromise(), timeout=0.05)
+
+
+class PromiseFromAwaitableTests(unittest.TestCase):
+    """Tests for converting Python awaitables into JavaScript promises."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+    def test_fulfilled(self):
+        """Test that the result of a coroutine fulfils the promise."""
+
+        async def compute():
+            await asyncio.sleep(0.01)
+            return {"value": 42}
+
+        promise = JSPromise.from_awaitable(compute())
+        self.assertEqual(promise.state(), JSPromiseState.Pending)
+        self.runtime.run_event_loop()
+        self.assertEqual(promise.state(), JSPromiseState.Fulfilled)
+        self.assertEqual(promise.result().to_py(), {"value": 42})
+
+    def test_rejected(self):
+        """Test that exceptions raised by a coroutine reject the promise."""
+
+        async def fail():
+            await asyncio.sleep(0)
+            raise ValueError("async failure")
+
+        promise = JSPromise.from_awaitable(fail())
+        self.runtime.run_event_loop()
+        self.assertEqual(promise.state(), JSPromiseState.Rejected)
+
+    def test_async_function_from_javascript(self):
+        """Test that JavaScript can await Python async functions."""
+
+        async def fetch(key):
+            await asyncio.sleep(0.01)
+            return f"value of {key}"
+
+        function = JSFunction.from_py(fetch)
+        caller = self.runtime.compile_and_evaluate_script(
+            "(async (fetch) => (await fetch('a')) + ', ' + (await fetch('b')))"
+        ).to_function()
+        promise = JSPromise.from_value(caller.call([function]))
+        self.runtime.run_event_loop()
+        self.assertEqual(promise.result().to_py(), "value of a, value of b")
+
+    def test_awaitable_awaits_javascript(self):
+        """Test that a coroutine can await a promise while timers of the event loop keep running."""
+        settled_later = JSPromise.from_value(
+            self.runtime.compile_and_evaluate_script(
+                "globalThis.ticks = [];\n"
+                "setTimeout(() => ticks.push('timer'), 10);\n"
+                "new Promise((resolve) => setTimeout(() => resolve('settled'), 50));"
+            )
+        )
+
+        async def wait():
+            value = await settled_later
+            return [value, self.runtime.compile_and_evaluate_script("ticks").to_py()]
+
+        promise = JSPromise.from_awaitable(wait())
+        self.runtime.run_event_loop(timeout=5)
+        self.assertEqual(promise.state(), JSPromiseState.Fulfilled)
+        self.assertEqual(promise.result().to_py(), ["settled", ["timer"]])
+
+    def test_not_awaitable(self):
+        """Test that objects which are not awaitable are rejected."""
+        with self.assertRaises(TypeError):
+            JSPromise.from_awaitable(42)
+
+
+class PromiseFromAwaitableAsyncTests(unittest.IsolatedAsyncioTestCase):
+    """Tests for converting awaitables while an asyncio event loop is running."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+    async def test_round_trip(self):
+        """Test awaiting a promise created from a task on the running event loop."""
+
+        async def compute():
+            await asyncio.sleep(0.01)
+            return [1, 2, 3]
+
+        self.assertEqual(await JSPromise.from_awaitable(compute()), [1, 2, 3])
+
+    async def test_future(self):
+        """Test converting an asyncio future resolved by another task."""
+        future = asyncio.get_running_loop().create_future()
+        asyncio.get_running_loop().call_later(0.01, future.set_result, "done")
+        self.assertEqual(await JSPromise.from_awaitable(future), "done")
+
+    async def test_exception(self):
+        """Test that exceptions of the awaitable are raised when awaiting the promise."""
+
+        async def fail():
+            raise ValueError("async failure")
+
+        with self.assertRaises(JSError) as context:
+            await JSPromise.from_awaitable(fail())
+        self.assertIn("async failure", context.exception.message)
+
+
+class PromiseChainingTests(unittest.TestCase):
+    """Tests for reactions and combinators of promises."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+    def settle(self, promise):
+        """Runs the event loop and returns the state and converted result of the promise."""
+        self.runtime.run_event_loop()
+        return promise.state(), promise.result().to_py()
+
+    def test_then(self):
+        """Test chaining Python callables and JSFunctions with then."""
+        double = self.runtime.compile_and_evaluate_script("(x) => x * 2").to_function()
+        promise = JSPromise.resolved(5).then(lambda x: x + 1).then(double)
+        self.assertIsInstance(promise, JSPromise)
+        self.assertEqual(self.settle(promise), (JSPromiseState.Fulfilled, 12))
+
+    def test_then_rejected(self):
+        """Test that then calls the rejection handler and that exceptions reject the chain."""
+        promise = JSPromise()
+        handled = promise.then(None, lambda reason: f"handled {reason}")
+        promise.reject("failure")
+        self.assertEqual(self.settle(handled), (JSPromiseState.Fulfilled, "handled failure"))
+
+        def fail(_):
+            raise ValueError("broken handler")
+
+        state, _ = self.settle(JSPromise.resolved(1).then(fail))
+        self.assertEqual(state, JSPromiseState.Rejected)
+
+    def test_then_awaitable(self):
+        """Test that awaitables returned by handlers are chained."""
+
+        async def later(value):
+            await asyncio.sleep(0.01)
+            return value * 10
+
+        promise = JSPromise.resolved(4).then(later)
+        self.assertEqual(self.settle(promise), (JSPromiseState.Fulfilled, 40))
+
+    def test_catch_and_finally(self):
+        """Test catch_ and finally_."""
+        calls = []
+        rejected = self.runtime.compile_and_evaluate_script("Promise.reject(new Error('boom'))")
+        promise = (
+            JSPromise.from_value(rejected)
+            .catch_(lambda error: "recovered")
+            .finally_(lambda: calls.append("finally"))
+        )
+        self.assertEqual(self.settle(promise), (JSPromiseState.Fulfilled, "recovered"))
+        self.assertEqual(calls, ["finally"])
+
+    def test_invalid_handler(self):
+        """Test that handlers must be callables or JSFunctions."""
+        with self.assertRaises(TypeError):
+            JSPromise.resolved(1).then(42)
+        with self.assertRaises(TypeError):
+            JSPromise.resolved(1).catch_("not callable")
+
+    def test_all(self):
+        """Test JSPromise.all with promises and plain values."""
+        pending = JSPromise()
+        promise = JSPromise.all([JSPromise.resolved(1), pending, 3])
+        pending.resolve(2)
+        self.assertEqual(self.settle(promise), (JSPromiseState.Fulfilled, [1, 2, 3]))
+
+        rejected = JSPromise()
+        rejected.reject("first")
+        state, reason = self.settle(JSPromise.all([JSPromise(), rejected]))
+        self.assertEqual((state, reason), (JSPromiseState.Rejected, "first"))
+
+    def test_any_and_race(self):
+        """Test JSPromise.any and JSPromise.race."""
+        rejected = JSPromise()
+        rejected.reject("failure")
+        any_promise = JSPromise.any([rejected, JSPromise.resolved("success")])
+        self.assertEqual(self.settle(any_promise), (JSPromiseState.Fulfilled, "success"))
+
+        state, _ = self.settle(JSPromise.any([rejected]))
+        self.assertEqual(state, JSPromiseState.Rejected)
+
+        race = JSPromise.race([JSPromise(), rejected])
+        self.assertEqual(self.settle(race), (JSPromiseState.Rejected, "failure"))
+
+    def test_all_settled(self):
+        """Test JSPromise.all_settled."""
+        rejected = JSPromise()
+        rejected.reject("failure")
+        promise = JSPromise.all_settled([JSPromise.resolved(1), rejected])
+        self.assertEqual(
+            self.settle(promise),
+            (
+                JSPromiseState.Fulfilled,
+                [
+                    {"status": "fulfilled", "value": 1},
+                    {"status": "rejected", "reason": "failure"},
+                ],
+            ),
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
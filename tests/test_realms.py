@@ -0,0 +1,108 @@
+import os
+import unittest
+
+from js_exec import Runtime, Realm, JSError
+
+from helpers import run_in_new_runtime
+
+
+class RealmTests(unittest.TestCase):
+    """Tests for isolated realms within a runtime."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+    def test_isolated_globals(self):
+        """Test that realms do not see each other's globals."""
+        first = self.runtime.new_realm()
+        second = self.runtime.new_realm()
+        self.assertIsInstance(first, Realm)
+
+        first.compile_and_evaluate_script("var tenant = 'first';")
+        second.compile_and_evaluate_script("var tenant = 'second';")
+        self.assertEqual(first.compile_and_evaluate_script("tenant").to_py(), "first")
+        self.assertEqual(second.compile_and_evaluate_script("tenant").to_py(), "second")
+        self.assertEqual(self.runtime.compile_and_evaluate_script("typeof tenant").to_py(), "undefined")
+
+    def test_isolated_builtins(self):
+        """Test that changes to the built-ins of a realm do not leak into other realms."""
+        realm = self.runtime.new_realm()
+        realm.compile_and_evaluate_script("Array.prototype.polluted = true;")
+        self.assertTrue(realm.compile_and_evaluate_script("[].polluted").to_py())
+        self.assertEqual(self.runtime.compile_and_evaluate_script("typeof [].polluted").to_py(), "undefined")
+        self.assertEqual(
+            self.runtime.new_realm().compile_and_evaluate_script("typeof [].polluted").to_py(), "undefined"
+        )
+
+    def test_returned_values(self):
+        """Test that values returned from a realm can be used from Python."""
+        realm = self.runtime.new_realm()
+        obj = realm.compile_and_evaluate_script("({ answer: 42 })").to_object()
+        self.assertEqual(obj.get("answer").to_number(), 42.0)
+
+        double = realm.compile_and_evaluate_script("(x) => x * 2").to_function()
+        self.assertEqual(double.call([21]).to_number(), 42.0)
+
+    def test_transfer_between_realms(self):
+        """Test that objects transferred between realms are shared."""
+        first = self.runtime.new_realm()
+        second = self.runtime.new_realm()
+        first.compile_and_evaluate_script("var shared = { count: 0 };")
+        second.set("shared", first.get("shared"))
+        second.compile_and_evaluate_script("shared.count += 1;")
+        self.assertEqual(first.compile_and_evaluate_script("shared.count").to_py(), 1)
+        self.assertFalse(second.compile_and_evaluate_script("shared instanceof Object").to_py())
+
+    def test_set_python_values(self):
+        """Test that Python values can be transferred into a realm."""
+        realm = self.runtime.new_realm()
+        realm.set("config", {"factor": 3})
+        self.assertEqual(realm.compile_and_evaluate_script("config.factor * 2").to_py(), 6)
+        self.assertEqual(realm.get("config").to_py(), {"factor": 3})
+        self.assertIsNone(realm.get("missing"))
+
+    def test_global_object(self):
+        """Test that the global object of a realm is accessible from Python."""
+        realm = self.runtime.new_realm()
+        realm.compile_and_evaluate_script("var name = 'realm';")
+        self.assertEqual(realm.global_object().get("name").to_py(), "realm")
+        self.assertTrue(realm.compile_and_evaluate_script("global === globalThis").to_py())
+
+    def test_errors(self):
+        """Test that errors thrown in a realm are raised in Python."""
+        realm = self.runtime.new_realm()
+        with self.assertRaises(JSError) as context:
+            realm.compile_and_evaluate_script("throw new Error('inside realm')")
+        self.assertIn("inside realm", str(context.exception))
+        self.assertEqual(realm.compile_and_evaluate_script("1 + 1").to_py(), 2)
+
+
+class RealmCapabilityTests(unittest.TestCase):
+    """Tests for realms of runtimes with restricted capabilities."""
+
+    def test_capabilities(self):
+        """Test that realms inherit the capabilities of the runtime."""
+        result = run_in_new_runtime(
+            """
+            runtime = Runtime(allow_net=False, allow_eval=False)
+            realm = runtime.new_realm()
+            result = []
+            for source in ["fetch('https://example.com')", "eval('1')", "1 + 1"]:
+                try:
+                    realm.compile_and_evaluate_script(source)
+                    result.append(False)
+                except JSError:
+                    result.append(True)
+            """,
+            os.getcwd(),
+        )
+        self.assertEqual(result, [True, True, False])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,162 @@
+import os
+import unittest
+
+from helpers import run_in_new_runtime
+
+
+def raises_closed(expression):
+    """Returns code which records whether evaluating `expression` raises JSRuntimeClosedError."""
+    return f"""
+try:
+    {expression}
+    closed_errors.append(False)
+except JSRuntimeClosedError:
+    closed_errors.append(True)
+"""
+
+
+class RuntimeCloseTests(unittest.TestCase):
+    """Tests for closing a runtime and using its values afterwards."""
+
+    def test_values_after_close(self):
+        """Test that values created by a closed runtime raise instead of crashing."""
+        result = run_in_new_runtime(
+            """
+            runtime = Runtime()
+            value = runtime.compile_and_evaluate_script("({ answer: 42 })")
+            obj = value.to_object()
+            function = runtime.compile_and_evaluate_script("(x) => x").to_function()
+            promise = runtime.compile_and_evaluate_script("Promise.resolve(1)").to_promise()
+            runtime.close()
+            closed_errors = []
+            """
+            + raises_closed("value.to_object()")
+            + raises_closed("obj.get('answer')")
+            + raises_closed("str(obj)")
+            + raises_closed("function.call([1])")
+            + raises_closed("promise.state()")
+            + raises_closed("typeof(value)")
+            + "result = [runtime.closed, closed_errors]",
+            os.getcwd(),
+        )
+        self.assertEqual(result, [True, [True] * 6])
+
+    def test_runtime_after_close(self):
+        """Test that the methods of a closed runtime raise."""
+        result = run_in_new_runtime(
+            """
+            runtime = Runtime()
+            runtime.close()
+            closed_errors = []
+            """
+            + raises_closed("runtime.compile_and_evaluate_script('1')")
+            + raises_closed("runtime.run_event_loop()")
+            + raises_closed("runtime.heap_stats()")
+            + "result = closed_errors",
+            os.getcwd(),
+        )
+        self.assertEqual(result, [True, True, True])
+
+    def test_close_twice(self):
+        """Test that closing a closed runtime does nothing."""
+        result = run_in_new_runtime(
+            """
+            runtime = Runtime()
+            runtime.close()
+            runtime.close()
+            result = runtime.closed
+            """,
+            os.getcwd(),
+        )
+        self.assertTrue(result)
+
+    def test_new_runtime_after_close(self):
+        """Test that a new runtime can be created, and values of the old one stay invalid."""
+        result = run_in_new_runtime(
+            """
+            first = Runtime()
+            old = first.compile_and_evaluate_script("'old'")
+            first.close()
+            second = Runtime()
+            new = second.compile_and_evaluate_script("'new'")
+            try:
+                old.to_py()
+                old_closed = False
+            except JSRuntimeClosedError:
+                old_closed = True
+            result = [old_closed, new.to_py(), first.closed, second.closed]
+            """,
+            os.getcwd(),
+        )
+        self.assertEqual(result, [True, "new", True, False])
+
+    def test_process_options(self):
+        """Test that later runtimes must use the process-wide options of the first one."""
+        result = run_in_new_runtime(
+            """
+            Runtime(log_level=2).close()
+            errors = []
+            for options in ({}, {"log_level": 3}, {"script": True}, {"typescript": False}):
+                try:
+                    Runtime(**options).close()
+                    errors.append(False)
+                except ValueError:
+                    errors.append(True)
+            runtime = Runtime(log_level=2)
+            result = [errors, runtime.compile_and_evaluate_script("1 + 1").to_py()]
+            """,
+            os.getcwd(),
+        )
+        self.assertEqual(result, [[True, True, True, True], 2])
+
+    def test_drop_empty_reference(self):
+        """Test that dropping a reference returned by Runtime.empty keeps the runtime open."""
+        result = run_in_new_runtime(
+            """
+            runtime = Runtime()
+            reference = Runtime.empty()
+            del reference
+            result = [runtime.closed, runtime.compile_and_evaluate_script("1 + 1").to_py()]
+            """,
+            os.getcwd(),
+        )
+        self.assertEqual(result, [False, 2])
+
+    def test_context_manager(self):
+        """Test that the runtime is closed when leaving a with block."""
+        result = run_in_new_runtime(
+            """
+            with Runtime() as runtime:
+                value = runtime.compile_and_evaluate_script("1 + 1").to_py()
+            result = [value, runtime.closed]
+            """,
+            os.getcwd(),
+        )
+        self.assertEqual(result, [2, True])
+
+    def test_close_from_callback(self):
+        """Test that a runtime cannot be closed while it is running JavaScript code."""
+        result = run_in_new_runtime(
+            """
+            runtime = Runtime()
+            callback = runtime.compile_and_evaluate_script("(f) => f()").to_function()
+
+            def close():
+                try:
+                    runtime.close()
+                    return "closed"
+                except JSRuntimeClosedError:
+                    return "closed error"
+                except RuntimeError:
+                    return "refused"
+
+            outcome = callback.call([JSFunction.from_py(close)]).to_py()
+            result = [outcome, runtime.closed]
+            """,
+            os.getcwd(),
+        )
+        self.assertEqual(result, ["refused", False])
+
+
+if __name__ == "__main__":
+    unittest.main()
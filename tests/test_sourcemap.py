@@ -0,0 +1,176 @@
+import json
+import os
+import tempfile
+import unittest
+
+from js_exec import Runtime, JSError, SourceMap
+
+SOURCE = """interface Greeting {
+    text: string;
+}
+
+const value: number = 1;
+function greet(name: string): string {
+    return name;
+}
+"""
+
+
+class SourceMapTests(unittest.TestCase):
+    """Tests for inspecting source maps and looking up original positions."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+        cls.code, cls.sourcemap = cls.runtime.compile_typescript(SOURCE, "greet.ts")
+
+    def generated_position(self, text):
+        """Returns the 1-based position of `text` in the generated code."""
+        for line, content in enumerate(self.code.splitlines(), start=1):
+            if text in content:
+                return line, content.index(text) + 1
+        self.fail(f"{text!r} not found in generated code")
+
+    def test_lookup(self):
+        """Test that generated positions are mapped to the TypeScript source."""
+        position = self.sourcemap.lookup(*self.generated_position("const value"))
+        self.assertEqual((position.source, position.line, position.column), ("greet.ts", 5, 1))
+
+        position = self.sourcemap.lookup(*self.generated_position("return"))
+        self.assertEqual((position.source, position.line, position.column), ("greet.ts", 7, 5))
+        self.assertEqual(str(position), "greet.ts:7:5")
+
+    def test_lookup_unmapped(self):
+        """Test that positions outside the generated code are not mapped."""
+        self.assertIsNone(self.sourcemap.lookup(1000, 1))
+        self.assertIsNone(self.sourcemap.lookup(0, 1))
+
+    def test_sources(self):
+        """Test that the original sources and their contents are listed."""
+        self.assertEqual(self.sourcemap.sources(), ["greet.ts"])
+        self.assertEqual(self.sourcemap.sources_content(), [SOURCE])
+        self.assertIsInstance(self.sourcemap.names(), list)
+
+    def test_json_round_trip(self):
+        """Test that source maps are serialised to and parsed from JSON."""
+        serialised = self.sourcemap.to_json()
+        parsed = json.loads(serialised)
+        self.assertEqual(parsed["version"], 3)
+        self.assertEqual(parsed["sources"], ["greet.ts"])
+
+        sourcemap = SourceMap.from_json(serialised)
+        self.assertEqual(sourcemap.sources(), self.sourcemap.sources())
+        position = self.generated_position("return")
+        self.assertEqual(sourcemap.lookup(*position), self.sourcemap.lookup(*position))
+
+    def test_from_json_handwritten(self):
+        """Test that source maps produced by other tools can be parsed."""
+        sourcemap = SourceMap.from_json(
+            json.dumps(
+                {
+                    "version": 3,
+                    "sources": ["a.js"],
+                    "sourcesContent": [None],
+                    "names": ["original"],
+                    "mappings": "AAAAA",
+                }
+            )
+        )
+        self.assertEqual(sourcemap.sources_content(), [None])
+        self.assertEqual(sourcemap.names(), ["original"])
+        position = sourcemap.lookup(1, 1)
+        self.assertEqual((position.source, position.line, position.column), ("a.js", 1, 1))
+        self.assertEqual(position.name, "original")
+
+    def test_from_json_invalid(self):
+        """Test that invalid source maps are rejected."""
+        with self.assertRaises(ValueError):
+            SourceMap.from_json("not a source map")
+
+
+class StackRemappingTests(unittest.TestCase):
+    """Tests for rewriting error locations through registered source maps."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+        cls.directory = tempfile.TemporaryDirectory()
+        cls.root = os.path.realpath(cls.directory.name)
+
+    @classmethod
+    def tearDownClass(cls):
+        cls.directory.cleanup()
+
+    def test_function_call(self):
+        """Test that errors of called functions keep both original and generated positions."""
+        function = self.runtime.compile_and_evaluate_typescript(
+            SOURCE.replace("return name;", "throw new Error(name);") + "\ngreet;\n",
+            "remap_call.ts",
+        ).to_function()
+        with self.assertRaises(JSError) as context:
+            function.call(["remapped"])
+
+        error = context.exception
+        self.assertEqual(error.message, "remapped")
+        self.assertEqual((error.filename, error.line), ("remap_call.ts", 7))
+        self.assertEqual(error.generated_filename, "remap_call.ts")
+        self.assertLess(error.generated_line, error.line)
+
+        frame = error.stack[0]
+        self.assertEqual((frame.function, frame.filename, frame.line), ("greet", "remap_call.ts", 7))
+        self.assertEqual((frame.generated_filename, frame.generated_line), ("remap_call.ts", error.generated_line))
+
+    def test_loaded_typescript_module(self):
+        """Test that errors of TypeScript modules loaded from files are remapped."""
+        with open(os.path.join(self.root, "shapes.ts"), "w") as file:
+            file.write(
+                "type Shape = { width: number; height: number };\n"
+                "\n"
+                "export function area(shape: Shape): number {\n"
+                "    if (shape.width < 0) {\n"
+                "        throw new RangeError('negative width');\n"
+                "    }\n"
+                "    return shape.width * shape.height;\n"
+                "}\n"
+            )
+        module, _ = self.runtime.compile_and_evaluate_module(
+            "export { area } from './shapes.ts';",
+            "main.js",
+            os.path.join(self.root, "main.js"),
+        )
+        area = module.get_export("area").to_function()
+        self.assertEqual(area.call([{"width": 2, "height": 3}]).to_py(), 6)
+
+        with self.assertRaises(JSError) as context:
+            area.call([{"width": -1, "height": 3}])
+        frame = context.exception.stack[0]
+        self.assertTrue(frame.filename.endswith("shapes.ts"))
+        self.assertEqual((frame.function, frame.line), ("area", 5))
+        self.assertEqual(context.exception.line, 5)
+
+    def test_unmapped_error(self):
+        """Test that errors in files without a source map keep their positions."""
+        with self.assertRaises(JSError) as context:
+            self.runtime.compile_and_evaluate_script("\n\nthrow new Error('plain');", "plain.js")
+        error = context.exception
+        self.assertEqual((error.filename, error.line), ("plain.js", 3))
+        self.assertEqual(
+            (error.generated_filename, error.generated_line, error.generated_column),
+            (error.filename, error.line, error.column),
+        )
+        for frame in error.stack:
+            self.assertEqual((frame.generated_line, frame.generated_column), (frame.line, frame.column))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,111 @@
+import os
+import time
+import unittest
+
+from js_exec import Runtime, JSFunction, JSError, JSTimeoutError, EventLoopStatus
+
+from helpers import run_in_new_runtime
+
+
+class ExecutionTimeoutTests(unittest.TestCase):
+    """Tests for limiting the execution time of JavaScript code."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+    def assertTimesOut(self, f, limit=5):
+        start = time.monotonic()
+        with self.assertRaises(JSTimeoutError):
+            f()
+        self.assertLess(time.monotonic() - start, limit)
+
+    def test_script_timeout(self):
+        """Test that an infinite loop in a script is interrupted."""
+        self.assertTimesOut(
+            lambda: self.runtime.compile_and_evaluate_script("while (true) {}", timeout=0.1)
+        )
+        self.assertTrue(issubclass(JSTimeoutError, JSError))
+
+    def test_runtime_usable_after_timeout(self):
+        """Test that the runtime keeps working after code is interrupted."""
+        self.assertTimesOut(
+            lambda: self.runtime.compile_and_evaluate_script("for (;;) {}", timeout=0.05)
+        )
+        self.assertEqual(self.runtime.compile_and_evaluate_script("1 + 2").to_number(), 3.0)
+        result = self.runtime.compile_and_evaluate_script("let i = 0; while (i < 1000) i++; i", timeout=5)
+        self.assertEqual(result.to_number(), 1000.0)
+
+    def test_uncatchable(self):
+        """Test that JavaScript code cannot catch the interruption."""
+        source = "for (;;) { try { while (true) {} } catch (e) {} }"
+        self.assertTimesOut(lambda: self.runtime.compile_and_evaluate_script(source, timeout=0.05))
+
+    def test_function_timeout(self):
+        """Test that function calls can be limited."""
+        spin = self.runtime.compile_and_evaluate_script("(function spin() { while (true) {} })")
+        self.assertTimesOut(lambda: spin.to_function().call(timeout=0.05))
+
+        add = self.runtime.compile_and_evaluate_script("(a, b) => a + b").to_function()
+        self.assertEqual(add.call([1, 2], timeout=1).to_number(), 3.0)
+
+    def test_nested_timeout(self):
+        """Test that the earliest deadline of nested calls applies."""
+        spin = self.runtime.compile_and_evaluate_script("(function spin() { while (true) {} })")
+        nested = JSFunction.from_py(lambda: spin.to_function().call(timeout=60))
+        caller = self.runtime.compile_and_evaluate_script("(f) => { f(); while (true) {} }")
+        self.assertTimesOut(lambda: caller.to_function().call([nested], timeout=0.1), limit=10)
+
+    def test_module_timeout(self):
+        """Test that module evaluation can be limited."""
+        self.assertTimesOut(
+            lambda: self.runtime.compile_and_evaluate_module(
+                "while (true) {}", "spin.js", "spin.js", timeout=0.05
+            )
+        )
+
+    def test_event_loop_task_timeout(self):
+        """Test that a task which never returns is interrupted at the deadline of the event loop."""
+        self.runtime.compile_and_evaluate_script("setTimeout(() => { while (true) {} }, 0);")
+        self.assertTimesOut(lambda: self.runtime.run_event_loop(timeout=0.1))
+        self.assertEqual(self.runtime.run_event_loop(timeout=5), EventLoopStatus.Idle)
+
+    def test_invalid_timeout(self):
+        """Test that negative timeouts are rejected."""
+        with self.assertRaises(ValueError):
+            self.runtime.compile_and_evaluate_script("1", timeout=-1)
+
+
+class RuntimeTimeoutTests(unittest.TestCase):
+    """Tests for the default timeout of a runtime."""
+
+    def test_default_timeout(self):
+        """Test that the timeout of the runtime applies to calls without their own timeout."""
+        result = run_in_new_runtime(
+            """
+            runtime = Runtime(timeout=0.1)
+            try:
+                runtime.compile_and_evaluate_script("while (true) {}")
+                timed_out = False
+            except JSTimeoutError:
+                timed_out = True
+            spin = runtime.compile_and_evaluate_script("(function spin() { while (true) {} })")
+            try:
+                spin.to_function().call()
+                call_timed_out = False
+            except JSTimeoutError:
+                call_timed_out = True
+            value = runtime.compile_and_evaluate_script("6 * 7").to_number()
+            result = [timed_out, call_timed_out, value]
+            """,
+            os.getcwd(),
+        )
+        self.assertEqual(result, [True, True, 42.0])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,226 @@
+import unittest
+
+from js_exec import Runtime, JSCompileError, JSError, JSModule, TypeScriptError
+
+
+class TypeScriptTests(unittest.TestCase):
+    """Tests for compiling TypeScript with options and diagnostics."""
+
+    @classmethod
+    def setUpClass(cls):
+        # Create a single JavaScript runtime for all tests
+        if Runtime.is_initialized():
+            cls.runtime = Runtime.empty()
+        else:
+            cls.runtime = Runtime()
+
+    def test_compile_and_evaluate(self):
+        """Test that types are erased and the output can be evaluated."""
+        code, sourcemap = self.runtime.compile_typescript(
+            "function add(a: number, b: number): number { return a + b; }\nadd(2, 3);",
+            "add.ts",
+        )
+        self.assertNotIn(": number", code)
+        self.assertEqual(self.runtime.compile_and_evaluate_script(code).to_py(), 5)
+        self.assertIsNotNone(sourcemap)
+
+    def test_commonjs_module(self):
+        """Test that exports are rewritten for CommonJS output."""
+        code, _ = self.runtime.compile_typescript(
+            "export const answer: number = 42;",
+            "answer.ts",
+            module="commonjs",
+        )
+        self.assertIn("exports", code)
+        self.assertNotIn("export const", code)
+
+    def test_target(self):
+        """Test that newer syntax is kept when targeting a recent version."""
+        code, _ = self.runtime.compile_typescript(
+            "const value = a ?? b;",
+            target="es2022",
+        )
+        self.assertIn("??", code)
+
+    def test_jsx_react(self):
+        """Test that JSX is compiled to React.createElement calls."""
+        code, _ = self.runtime.compile_typescript(
+            "const element = <div id=\"root\">{name}</div>;",
+            "element.tsx",
+            jsx="react",
+        )
+        self.assertIn("React.createElement", code)
+
+    def test_jsx_react_jsx(self):
+        """Test that JSX is compiled to calls of the automatic runtime."""
+        code, _ = self.runtime.compile_typescript(
+            "export const element = <div>{name}</div>;",
+            "element.tsx",
+            jsx="react-jsx",
+        )
+        self.assertIn("react/jsx-runtime", code)
+
+    def test_jsx_preserve(self):
+        """Test that JSX is kept as is when preserved."""
+        code, _ = self.runtime.compile_typescript(
+            "const element = <div>{name as string}</div>;",
+            "element.tsx",
+            jsx="preserve",
+        )
+        self.assertIn("<div>", code)
+        self.assertNotIn(" as string", code)
+
+    def test_strip_only(self):
+        """Test that syntax which cannot be erased is rejected in strip-only mode."""
+        with self.assertRaises(TypeScriptError) as context:
+            self.runtime.compile_typescript(
+                "let x: number = 1;\nenum Color { Red, Green }",
+                "color.ts",
+                strip_only=True,
+            )
+        diagnostics = context.exception.diagnostics
+        self.assertEqual(len(diagnostics), 1)
+        self.assertEqual(diagnostics[0].code, "TS1294")
+        self.assertEqual(diagnostics[0].filename, "color.ts")
+        self.assertEqual(diagnostics[0].line, 2)
+        self.assertEqual(diagnostics[0].column, 1)
+
+        code, _ = self.runtime.compile_typescript(
+            "enum Color { Red, Green }\nColor.Green;",
+            "color.ts",
+        )
+        self.assertEqual(self.runtime.compile_and_evaluate_script(code).to_py(), 1)
+
+    def test_emit_decorators(self):
+        """Test that legacy decorators are compiled and applied."""
+        code, _ = self.runtime.compile_typescript(
+            """
+            function tag(target: any) { target.tagged = true; }
+            @tag
+            class Service {}
+            (Service as any).tagged;
+            """,
+            "service.ts",
+            emit_decorators=True,
+        )
+        self.assertNotIn("@tag", code)
+        self.assertTrue(self.runtime.compile_and_evaluate_script(code).to_py())
+
+    def test_decorators_without_emit(self):
+        """Test that decorators are parsed and kept as is unless they are compiled."""
+        code, _ = self.runtime.compile_typescript("@tag\nclass Service {}", "service.ts")
+        self.assertIn("@tag", code)
+
+    def test_syntax_error(self):
+        """Test that syntax errors are reported as structured diagnostics."""
+        with self.assertRaises(TypeScriptError) as context:
+            self.runtime.compile_typescript("let a = 1;\nlet x: number = ;", "broken.ts")
+        error = context.exception
+        self.assertIsInstance(error, JSCompileError)
+        self.assertGreaterEqual(len(error.diagnostics), 1)
+
+        diagnostic = error.diagnostics[0]
+        if diagnostic.code is not None:
+            self.assertRegex(diagnostic.code, r"^TS\d+$")
+        self.assertTrue(diagnostic.message)
+        self.assertEqual(diagnostic.filename, "broken.ts")
+        self.assertEqual(diagnostic.line, 2)
+        self.assertEqual(diagnostic.column, 17)
+        self.assertEqual((error.filename, error.line, error.column), ("broken.ts", 2, 17))
+        self.assertIn("broken.ts:2:17", str(error))
+
+    def test_evaluate_script(self):
+        """Test that TypeScript is evaluated directly as a script."""
+        value = self.runtime.compile_and_evaluate_typescript(
+            "const double = (x: number): number => x * 2;\ndouble(21);",
+            "double.ts",
+        )
+        self.assertEqual(value.to_py(), 42)
+
+    def test_evaluate_module(self):
+        """Test that TypeScript is evaluated directly as a module."""
+        module, _ = self.runtime.compile_and_evaluate_typescript(
+            "export const answer: number = 42;",
+            "answer_module.ts",
+            as_module=True,
+        )
+        self.assertIsInstance(module, JSModule)
+
+    def test_evaluate_compile_error(self):
+        """Test that compilation errors of evaluated TypeScript raise TypeScriptError."""
+        with self.assertRaises(TypeScriptError):
+            self.runtime.compile_and_evaluate_typescript("let x: = 1;", "invalid.ts")
+
+    def test_error_location_mapped(self):
+        """Test that errors point to lines of the TypeScript source, not the compiled output."""
+        source = """interface Point {
+    x: number;
+    y: number;
+}
+
+export function fail(point: Point): never {
+    throw new Error(`bad point ${point.x}`);
+}
+"""
+        with self.assertRaises(JSError) as context:
+            self.runtime.compile_and_evaluate_typescript(
+                source.replace("export ", "") + "\nfail({ x: 1, y: 2 });\n", "fail_script.ts"
+            )
+        error = context.exception
+        self.assertEqual(error.message, "bad point 1")
+        self.assertEqual((error.filename, error.line), ("fail_script.ts", 7))
+        frames = [frame for frame in error.stack if frame.function == "fail"]
+        self.assertEqual([frame.line for frame in frames], [7])
+
+        # Errors of evaluating a module reject its promise, so throw from an exported function.
+        module, _ = self.runtime.compile_and_evaluate_typescript(
+            source, "fail_module.ts", as_module=True
+        )
+        with self.assertRaises(JSError) as context:
+            module.get_export("fail").to_function().call([{"x": 2, "y": 3}])
+        self.assertEqual((context.exception.filename, context.exception.line), ("fail_module.ts", 7))
+
+    def test_default_filenames(self):
+        """Test that sources evaluated without a filename keep their own source maps."""
+        first = self.runtime.compile_and_evaluate_typescript(
+            "const a: number = 1;\n\n(function first(): never { throw new Error('first'); });"
+        ).to_function()
+        self.runtime.compile_and_evaluate_typescript(
+            "(function second(): never {\n    throw new Error('second');\n});"
+        )
+        with self.assertRaises(JSError) as context:
+            first.call([])
+        error = context.exception
+        self.assertRegex(error.filename, r"^inline-\d+\.ts$")
+        self.assertEqual(error.line, 3)
+
+    def test_invalid_options(self):
+        """Test that unknown option values are rejected."""
+        for options in ({"target": "es1"}, {"jsx": "vue"}, {"module": "amd"}):
+            with self.subTest(options=options), self.assertRaises(ValueError):
+                self.runtime.compile_typescript("let x = 1;", **options)
+
+    def test_syntax_newer_than_target(self):
+        """Test that syntax which is newer than the target is rejected rather than kept."""
+        sources = {
+            "es2015": "const value = 2 ** 3;",
+            "es2019": "const value = a?.b ?? c;",
+            "es2021": "class Counter { static { this.count = 0; } }",
+        }
+        for target, source in sources.items():
+            with self.subTest(target=target), self.assertRaises(TypeScriptError) as context:
+                self.runtime.compile_typescript(source, "newer.ts", target=target)
+            self.assertIn("as syntax is not compiled down", context.exception.diagnostics[0].message)
+
+        code, _ = self.runtime.compile_typescript("const value = a?.b ?? c;", "newer.ts", target="es2020")
+        self.assertIn("?.", code)
+
+    def test_unsupported_target(self):
+        """Test that targets which would require compiling syntax down are rejected."""
+        for target in ("es3", "es5"):
+            with self.subTest(target=target), self.assertRaises(ValueError):
+                self.runtime.compile_typescript("let x = 1;", target=target)
+
+
+if __name__ == "__main__":
+    unittest.main()